@@ -0,0 +1,32 @@
+use std::time::Instant;
+
+use piece_table::PieceTable;
+
+fn append_lines(table: &mut PieceTable) -> std::time::Duration {
+    let start = Instant::now();
+    for i in 0..100_000 {
+        table.append(format!("line {i}\n"));
+    }
+    start.elapsed()
+}
+
+fn main() {
+    let mut plain = PieceTable::from("");
+    let plain_elapsed = append_lines(&mut plain);
+
+    let mut runs = PieceTable::from("");
+    runs.set_append_runs(Some(usize::MAX));
+    let runs_elapsed = append_lines(&mut runs);
+
+    assert_eq!(plain.to_string(), runs.to_string());
+    for (name, table, elapsed) in [
+        ("separate commits", &plain, plain_elapsed),
+        ("append runs", &runs, runs_elapsed),
+    ] {
+        println!(
+            "100k appends, {name}: {elapsed:?}, {} history entries, {} pieces",
+            table.history_stats().total_entries,
+            table.stats().piece_count
+        );
+    }
+}
@@ -0,0 +1,16 @@
+use std::time::Instant;
+
+use piece_table::PieceTable;
+
+fn main() {
+    let mut table = PieceTable::from("");
+
+    let start = Instant::now();
+    for _ in 0..10_000 {
+        table.insert(0, "a");
+    }
+    let elapsed = start.elapsed();
+
+    assert_eq!(table.len(), 10_000);
+    println!("10k front inserts: {elapsed:?}");
+}
@@ -0,0 +1,25 @@
+use std::time::Instant;
+
+use piece_table::PieceTable;
+
+fn main() {
+    let mut table = PieceTable::from("");
+
+    let start = Instant::now();
+    for line in 0..5_000 {
+        let depth = line % 4 + 1;
+        table.append("    ".repeat(depth));
+        table.append("let");
+        table.append(" x = ");
+        table.append((line % 10).to_string());
+        table.append(";\n");
+    }
+    let elapsed = start.elapsed();
+
+    println!("5k indented lines: {elapsed:?}");
+    println!(
+        "Text: {} bytes, addition buffer: {} bytes",
+        table.len(),
+        table.addition_text().len()
+    );
+}
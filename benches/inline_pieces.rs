@@ -0,0 +1,51 @@
+use std::time::{Duration, Instant};
+
+use piece_table::{PieceTable, PieceTableSlice};
+
+/// Types 20k single chars at scattered positions, so no insertion continues the previous one
+fn type_scattered(table: &mut PieceTable) -> Duration {
+    let start = Instant::now();
+    let mut pos = 0;
+    for i in 0..20_000 {
+        pos = (pos + 7_919) % (table.len() + 1);
+        table.insert(pos, ["a", "b", " ", "\n"][i % 4]);
+    }
+    start.elapsed()
+}
+
+/// Reads the whole text and 1k lines of it
+fn read(table: &PieceTable) -> Duration {
+    let start = Instant::now();
+    let mut checksum = table.to_string().len();
+    for i in 0..1_000 {
+        let line = table.line_byte_range(i * 3).expect("Line exists");
+        checksum += table.slice(line).len();
+    }
+    assert!(checksum > 0);
+    start.elapsed()
+}
+
+fn main() {
+    let text = "fn main() {\n    println!(\"Hello, World!\");\n}\n".repeat(100);
+
+    let mut addition = PieceTable::from(&text);
+    let addition_typed = type_scattered(&mut addition);
+    let addition_read = read(&addition);
+
+    let mut inline = PieceTable::from(&text);
+    inline.set_inline_pieces(true);
+    let inline_typed = type_scattered(&mut inline);
+    let inline_read = read(&inline);
+
+    assert_eq!(addition.to_string(), inline.to_string());
+    for (name, table, typed, read) in [
+        ("addition buffer", &addition, addition_typed, addition_read),
+        ("inline pieces", &inline, inline_typed, inline_read),
+    ] {
+        println!(
+            "20k scattered keystrokes, {name}: typing {typed:?}, reading {read:?}, {} pieces, {} addition bytes",
+            table.stats().piece_count,
+            table.stats().addition_bytes
+        );
+    }
+}
@@ -0,0 +1,31 @@
+use std::time::Instant;
+
+use piece_table::PieceTable;
+
+fn main() {
+    let text: String = (0..100_000)
+        .map(|i| format!("line {i} of the file\n"))
+        .collect();
+
+    for enabled in [false, true] {
+        let mut table = PieceTable::from(&text);
+        table.set_line_index(enabled);
+
+        // Every lookup follows an edit, as when typing with a line number gutter
+        let start = Instant::now();
+        let mut checksum = 0;
+        for i in 0..1_000 {
+            let line = i * 97 % 100_000;
+            let line_start = table.byte_of_line_col(line, 0);
+            table.insert(line_start, "x\n");
+
+            let range = table.line_byte_range(line * 7 % 100_000).unwrap();
+            let (line_of_start, _) = table.line_col_of(range.start);
+            checksum += range.len() + line_of_start + table.line_count();
+        }
+        let elapsed = start.elapsed();
+
+        let name = if enabled { "line index" } else { "scanning" };
+        println!("100k lines, 1000 edits each followed by line lookups, {name}: {elapsed:?} (checksum {checksum})");
+    }
+}
@@ -0,0 +1,41 @@
+use std::time::Instant;
+
+use piece_table::PieceTable;
+
+fn main() {
+    let text: String = (0..10_000)
+        .map(|i| format!("line {i:>5} of the file\n"))
+        .collect();
+    let line_len = "line     0 of the file\n".len();
+
+    // Move 1000 blocks of 10 lines each to the front
+    let mut moved = PieceTable::from(&text);
+    let start = Instant::now();
+    for i in 0..1_000 {
+        let from = (i * 7 % 990 + 10) * line_len;
+        moved.move_range(from, 10 * line_len, 0);
+    }
+    let moved_elapsed = start.elapsed();
+
+    let mut naive = PieceTable::from(&text);
+    let start = Instant::now();
+    for i in 0..1_000 {
+        let from = (i * 7 % 990 + 10) * line_len;
+        let block = naive.range_slice(from..from + 10 * line_len);
+        naive.remove(from, block.len());
+        naive.insert(0, block);
+    }
+    let naive_elapsed = start.elapsed();
+
+    assert_eq!(moved.to_string(), naive.to_string());
+    println!(
+        "1000 block moves, move_range: {moved_elapsed:?}, {} bytes copied, {} pieces",
+        moved.addition_text().len(),
+        moved.stats().piece_count
+    );
+    println!(
+        "1000 block moves, remove + insert: {naive_elapsed:?}, {} bytes copied, {} pieces",
+        naive.addition_text().len(),
+        naive.stats().piece_count
+    );
+}
@@ -0,0 +1,47 @@
+use std::time::Instant;
+
+use piece_table::PieceTable;
+
+fn main() {
+    // 100k pieces: every other byte of the original text is followed by an insertion
+    let mut table = PieceTable::from("ab".repeat(50_000));
+    for pos in (0..50_000).rev() {
+        table.insert_no_history(2 * pos + 1, "x");
+    }
+    let len = table.len();
+    let positions: Vec<usize> = (0..10_000).map(|i| i * 7919 % (len - 1)).collect();
+
+    let start = Instant::now();
+    let indexed: String = positions
+        .iter()
+        .map(|pos| table.range_slice(*pos..pos + 1))
+        .collect();
+    let indexed_elapsed = start.elapsed();
+
+    let start = Instant::now();
+    let scanned: String = positions
+        .iter()
+        .flat_map(|pos| table.pieces_in(*pos..pos + 1))
+        .map(|(info, range)| &info.text[range])
+        .collect();
+    let scanned_elapsed = start.elapsed();
+
+    assert_eq!(indexed, scanned);
+    println!(
+        "10k slices of {} pieces, indexed lookup: {indexed_elapsed:?}, linear scan: {scanned_elapsed:?}",
+        table.stats().piece_count
+    );
+
+    // Typing in the middle only invalidates the start positions behind the cursor
+    let start = Instant::now();
+    for i in 0..10_000 {
+        table.insert(len / 2 + i, "y");
+    }
+    println!("10k typed chars in the middle: {:?}", start.elapsed());
+
+    let start = Instant::now();
+    for i in 0..10_000 {
+        table.remove(len / 2 + 10_000 - i - 1, 1);
+    }
+    println!("10k removed chars in the middle: {:?}", start.elapsed());
+}
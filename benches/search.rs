@@ -0,0 +1,30 @@
+use std::time::Instant;
+
+use piece_table::PieceTable;
+
+fn main() {
+    // ~1MB of text in many pieces, the needle only occurs at the very end
+    let mut table = PieceTable::from("lorem ipsum dolor sit amet ".repeat(40_000));
+    for i in 0..2_000 {
+        table.insert(i * 500, "consectetur ");
+    }
+    table.append("needle");
+
+    let start = Instant::now();
+    let naive: Vec<usize> = table
+        .to_string()
+        .match_indices("needle")
+        .map(|(pos, _)| pos)
+        .collect();
+    let naive_elapsed = start.elapsed();
+
+    let start = Instant::now();
+    let kmp = table.find_all("needle");
+    let kmp_elapsed = start.elapsed();
+
+    assert_eq!(naive, kmp);
+    println!(
+        "{} bytes: assembled match_indices {naive_elapsed:?}, streaming KMP {kmp_elapsed:?}",
+        table.len()
+    );
+}
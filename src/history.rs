@@ -10,6 +10,7 @@ use entry::Entry;
 /// Manipulation history of a Piece Tree.
 ///
 /// Creates a tree of changes that can be traversed forward and backward.
+#[derive(Clone)]
 pub(crate) struct History {
     pub(crate) changes: Vec<Entry>,
     pub(crate) head: usize,
@@ -24,11 +25,13 @@ impl History {
     }
 
     /// Updates the history and adds the latest commit
+    ///
+    /// An empty history gets a new root entry first.
     pub(crate) fn save(&mut self, commit: Commit) {
-        assert!(
-            self.changes.is_empty().not(),
-            "History is empty when saving"
-        );
+        if self.changes.is_empty() {
+            self.changes.push(Entry::new(Commit::new()));
+            self.head = 0;
+        }
         assert!(self.head < self.changes.len(), "Head is out of bounds");
 
         let prev_head = self.head;
@@ -41,6 +44,113 @@ impl History {
         self.changes.push(entry);
     }
 
+    /// Whether the commit at the head may be extended, i.e. it is not the root
+    /// and no other entries follow it
+    pub(crate) fn can_amend(&self) -> bool {
+        self.head != 0
+            && self
+                .changes
+                .get(self.head)
+                .is_some_and(|entry| entry.next.is_empty())
+    }
+
+    /// Merges `commit` into the commit at the head instead of adding a new entry
+    pub(crate) fn amend(&mut self, commit: Commit) {
+        assert!(self.can_amend(), "Head commit can't be amended");
+        self.changes[self.head].commit.append(commit);
+        self.changes[self.head].amends += 1;
+    }
+
+    /// Appends the changes of `commit`, which must leave the text as it is (e.g. merged
+    /// pieces), to the commit at the head
+    ///
+    /// Unlike `History::amend` this doesn't count as an edit of the head commit.
+    pub(crate) fn extend_head(&mut self, commit: Commit) {
+        assert!(self.can_amend(), "Head commit can't be extended");
+        self.changes[self.head]
+            .commit
+            .changes
+            .extend(commit.changes);
+    }
+
+    /// Checks that the head and all links between the entries point at existing entries
+    /// and agree with each other
+    pub(crate) fn is_valid(&self) -> bool {
+        if self.head >= self.changes.len() {
+            return false;
+        }
+
+        self.changes.iter().enumerate().all(|(idx, entry)| {
+            let previous_valid = match entry.previous {
+                None => idx == 0,
+                Some(previous) => {
+                    previous < self.changes.len() && self.changes[previous].next.contains(&idx)
+                }
+            };
+            let next_valid = entry.next.iter().all(|next| {
+                *next < self.changes.len() && self.changes[*next].previous == Some(idx)
+            });
+            let hot_path_valid = entry
+                .hot_path
+                .is_none_or(|hot_path| entry.next.contains(&hot_path));
+
+            previous_valid && next_valid && hot_path_valid
+        })
+    }
+
+    /// Returns the number of entries reachable from the root by following `next`
+    pub(crate) fn reachable_entries(&self) -> usize {
+        if self.changes.is_empty() {
+            return 0;
+        }
+
+        let mut visited = vec![false; self.changes.len()];
+        let mut stack = vec![0];
+        let mut count = 0;
+        while let Some(idx) = stack.pop() {
+            if visited[idx] {
+                continue;
+            }
+            visited[idx] = true;
+            count += 1;
+            stack.extend(
+                self.changes[idx]
+                    .next
+                    .iter()
+                    .filter(|next| **next < self.changes.len()),
+            );
+        }
+
+        count
+    }
+
+    /// Returns the number of `previous` links from the head to the root
+    pub(crate) fn depth(&self) -> usize {
+        let mut depth = 0;
+        let mut idx = self.head;
+        while let Some(previous) = self.changes.get(idx).and_then(|entry| entry.previous) {
+            depth += 1;
+            idx = previous;
+            assert!(depth <= self.changes.len(), "History has a cycle");
+        }
+
+        depth
+    }
+
+    /// Whether the head has a previous entry to undo to
+    pub(crate) fn can_undo(&self) -> bool {
+        self.changes
+            .get(self.head)
+            .is_some_and(|entry| entry.previous.is_some())
+    }
+
+    /// Whether any entry follows the head, i.e. `History::redo` would move the head
+    pub(crate) fn can_redo(&self) -> bool {
+        self.changes
+            .get(self.head)
+            .is_some_and(|entry| entry.next.is_empty().not())
+    }
+
     /// Undos a commit by updating the history to the new head and returning the commit
     pub(crate) fn undo(&mut self) -> Option<&Commit> {
         assert!(
@@ -78,4 +188,39 @@ impl History {
 
         Some(&self.changes[self.head].commit)
     }
+
+    /// Redos on the hot path, or on the newest branch following the head if there is
+    /// no hot path, and makes the redone commit the hot path
+    pub(crate) fn redo(&mut self) -> Option<&Commit> {
+        assert!(self.head < self.changes.len(), "Head is out of bounds");
+
+        let entry = &self.changes[self.head];
+        let next = match entry.hot_path {
+            Some(hot_head) => hot_head,
+            None => *entry.next.last()?,
+        };
+        assert!(next < self.changes.len(), "Redo head is out of bounds");
+        assert!(
+            entry.next.contains(&next),
+            "Hot path is not in path of change"
+        );
+
+        self.changes[self.head].hot_path = Some(next);
+        self.head = next;
+
+        Some(&self.changes[self.head].commit)
+    }
+
+    /// Redos the `branch`th commit following the head and makes it the hot path
+    pub(crate) fn redo_branch(&mut self, branch: usize) -> Option<&Commit> {
+        assert!(self.head < self.changes.len(), "Head is out of bounds");
+
+        let next = *self.changes[self.head].next.get(branch)?;
+        assert!(next < self.changes.len(), "Branch head is out of bounds");
+
+        self.changes[self.head].hot_path = Some(next);
+        self.head = next;
+
+        Some(&self.changes[self.head].commit)
+    }
 }
@@ -1,12 +1,14 @@
 use crate::piece_table::piece::Piece;
 
-#[cfg_attr(test, derive(PartialEq, Debug, Clone, Copy))]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+#[derive(Clone, Copy)]
 pub(crate) enum ChangeType {
     Deletion,
     Insertion,
 }
 
-#[cfg_attr(test, derive(PartialEq, Debug, Clone, Copy))]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+#[derive(Clone, Copy)]
 /// The change to the Piece Table
 pub(crate) struct Change {
     /// Position of the Piece in the Piece Table (Vec)
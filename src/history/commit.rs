@@ -1,21 +1,57 @@
-use crate::piece_table::piece::Piece;
+use crate::piece_table::{edit_delta::EditDelta, piece::Piece};
 
 use super::change::{Change, ChangeType};
 
-#[cfg_attr(test, derive(PartialEq, Debug, Clone))]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+#[derive(Clone)]
 /// Collection of Changes
 pub(crate) struct Commit {
     pub(crate) changes: Vec<Change>,
+    /// Byte range affected by the changes (`None` for empty commits)
+    pub(crate) edit: Option<EditDelta>,
+    /// Untracked commits drop their changes, they are never saved to the history
+    pub(crate) tracked: bool,
+    /// Number of appends merged into the last piece (see `PieceTable::set_append_runs`)
+    pub(crate) appends: usize,
 }
 
 impl Commit {
     pub(crate) fn new() -> Commit {
         Commit {
             changes: Vec::new(),
+            edit: None,
+            tracked: true,
+            appends: 0,
+        }
+    }
+
+    /// Creates a commit that does not record any changes
+    pub(crate) fn untracked() -> Commit {
+        Commit {
+            tracked: false,
+            ..Commit::new()
         }
     }
 
     pub(crate) fn add_change(&mut self, pos: usize, piece: Piece, typ: ChangeType) {
-        self.changes.push(Change::new(pos, piece, typ));
+        if self.tracked {
+            self.changes.push(Change::new(pos, piece, typ));
+        }
+    }
+
+    /// Extends the affected byte range by an edit applied after the previous ones
+    pub(crate) fn add_edit(&mut self, edit: EditDelta) {
+        self.edit = Some(match self.edit {
+            Some(prev) => prev.then(edit),
+            None => edit,
+        });
+    }
+
+    /// Appends the changes of a commit applied after this one
+    pub(crate) fn append(&mut self, commit: Commit) {
+        self.changes.extend(commit.changes);
+        if let Some(edit) = commit.edit {
+            self.add_edit(edit);
+        }
     }
 }
@@ -1,6 +1,7 @@
 use super::commit::Commit;
 
 #[cfg_attr(test, derive(PartialEq, Debug))]
+#[derive(Clone)]
 /// History Entry with position information inside the History
 pub(crate) struct Entry {
     /// Index of the previous/following (if any) entries in the history
@@ -12,6 +13,8 @@ pub(crate) struct Entry {
     pub(crate) hot_path: Option<usize>,
 
     pub(crate) commit: Commit,
+    /// Number of edits merged into the commit after it was saved
+    pub(crate) amends: usize,
 }
 
 impl Entry {
@@ -21,6 +24,7 @@ impl Entry {
             next: Vec::new(),
             hot_path: None,
             commit,
+            amends: 0,
         }
     }
 }
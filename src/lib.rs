@@ -1,5 +1,24 @@
 mod piece_table;
+pub use crate::piece_table::anchor::{Anchor, SpanId};
+pub use crate::piece_table::bounds_policy::BoundsPolicy;
+pub use crate::piece_table::commit_strategy::{CommitStrategy, PerEdit, PerLine, PerWord};
+pub use crate::piece_table::cursor::Cursor;
+pub use crate::piece_table::diff::LineDiff;
+pub use crate::piece_table::display_trait::{EscapedDisplay, RangeDisplay};
+pub use crate::piece_table::edit_delta::EditDelta;
+pub use crate::piece_table::edit_error::EditError;
+pub use crate::piece_table::inverse_edit::InverseEdit;
+pub use crate::piece_table::line_ending::{LineEnding, LineEndingStats};
+#[cfg(feature = "mmap")]
+pub use crate::piece_table::mmap::MappedText;
+pub use crate::piece_table::piece::PieceSource;
+pub use crate::piece_table::piece_info::PieceInfo;
+pub use crate::piece_table::position::{BytePos, CharPos};
 pub use crate::piece_table::slice_trait::PieceTableSlice;
+pub use crate::piece_table::stats::{HistoryStats, Stats};
+pub use crate::piece_table::structural_delta::{PieceChange, StructuralDelta};
+pub use crate::piece_table::text_source::TextSource;
+pub use crate::piece_table::version::VersionId;
 pub use piece_table::PieceTable;
 
 mod history;
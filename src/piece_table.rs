@@ -1,11 +1,67 @@
+pub mod anchor;
+pub mod bounds_policy;
+pub mod commit_strategy;
+pub mod cursor;
+pub mod diff;
 pub mod display_trait;
+pub mod edit_delta;
+pub mod edit_error;
+pub mod inverse_edit;
+pub mod line_ending;
+#[cfg(feature = "mmap")]
+pub mod mmap;
+pub mod piece_info;
+pub mod position;
 pub mod slice_trait;
+pub mod stats;
+pub mod structural_delta;
+pub mod text_source;
+pub mod version;
+
+pub(crate) mod append_run;
+pub(crate) mod clone_trait;
+#[cfg(feature = "unicode")]
+pub(crate) mod graphemes;
+#[cfg(feature = "inline-pieces")]
+pub(crate) mod inline_pieces;
+pub(crate) mod line_index;
+pub(crate) mod lines;
+pub(crate) mod lsp;
+pub(crate) mod reorder;
+pub(crate) mod search;
+pub(crate) mod write_trait;
 
 pub(crate) mod piece;
+pub(crate) mod piece_index;
+pub(crate) mod piece_tree;
+pub(crate) mod slice_cache;
+
+#[cfg(feature = "intern-additions")]
+use std::collections::HashMap;
+use std::{
+    collections::VecDeque,
+    fmt,
+    ops::{Bound, Not, Range, RangeBounds},
+    str::{self, Utf8Error},
+    sync::{Arc, Mutex},
+};
 
-use std::ops::Not;
+use bounds_policy::BoundsPolicy;
+use commit_strategy::CommitStrategy;
+use display_trait::{EscapedDisplay, RangeDisplay};
+use edit_delta::EditDelta;
+use edit_error::EditError;
+use line_ending::LineEnding;
+use line_index::LineIndex;
+use piece::{Piece, PieceSource, INLINE_LEN};
+use piece_index::PieceIndex;
+use slice_cache::SliceCache;
+use structural_delta::{StructuralDelta, StructuralObserver};
+use text_source::TextSource;
 
-use piece::{Piece, PieceSource};
+/// Maximum length of an inserted string that is interned
+#[cfg(feature = "intern-additions")]
+const INTERN_LEN: usize = 64;
 
 use crate::{
     history::{change::ChangeType, commit::Commit},
@@ -15,17 +71,55 @@ use crate::{
 /// Simple Piece Table with a history to enable undo/redo operations
 pub struct PieceTable {
     /// Read only input data
-    pub(crate) original: String,
+    pub(crate) original: Arc<dyn TextSource>,
     /// Data added while editing
     pub(crate) addition: String,
     /// List of pieces that point to data contained in text
-    pub(crate) pieces: Vec<Piece>,
+    ///
+    /// A deque makes insertions at the very front as cheap as at the end
+    pub(crate) pieces: VecDeque<Piece>,
 
     /// Length of the text contained in the Piece Table
     pub(crate) total_length: usize,
 
     /// Edit history
     pub(crate) history: History,
+    /// Whether edits are recorded in the history
+    pub(crate) history_enabled: bool,
+    /// Incremented whenever the history is discarded or an edit bypasses it,
+    /// invalidates all earlier `VersionId`s
+    pub(crate) history_epoch: usize,
+    /// Byte range affected by the last edit, undo or redo
+    pub(crate) last_edit: Option<EditDelta>,
+    /// Whether the last edit was merged into the previous undo step
+    pub(crate) last_edit_coalesced: bool,
+    /// Maximum length insertions may grow the text to
+    pub(crate) max_len: Option<usize>,
+    /// Handling of out-of-range positions
+    pub(crate) bounds_policy: BoundsPolicy,
+    /// Callback notified about changes of the piece list
+    pub(crate) structural_observer: Option<StructuralObserver>,
+    /// Grouping of edits into undo steps, every edit is a step if unset
+    pub(crate) commit_strategy: Option<Box<dyn CommitStrategy>>,
+    /// Maximum number of appends merged into one undo step, appends aren't merged if unset
+    pub(crate) max_append_run: Option<usize>,
+    /// Line ending style the text is written with
+    pub(crate) line_ending: LineEnding,
+    /// Recently sliced text, disabled if unset
+    pub(crate) slice_cache: Option<Mutex<SliceCache>>,
+    /// Line endings of the text per piece, disabled if unset
+    pub(crate) line_index: Option<LineIndex>,
+    /// Start positions of the pieces, to find the piece containing a position
+    pub(crate) piece_index: Mutex<PieceIndex>,
+    /// Leading bytes of a char split across `io::Write` calls, appended once completed
+    pub(crate) incomplete_write: Vec<u8>,
+    /// Offsets of short inserted strings in the addition buffer, reused by equal insertions
+    #[cfg(feature = "intern-additions")]
+    pub(crate) interned: HashMap<String, usize>,
+    /// Whether short insertions are stored in their piece, see `PieceTable::set_inline_pieces`
+    pub(crate) inline_pieces: bool,
+    /// Offset of the next inline text, see `Piece::offset`
+    pub(crate) inline_offset: usize,
 }
 
 impl PieceTable {
@@ -39,31 +133,229 @@ impl PieceTable {
     /// assert_eq!(table.to_string(), "Hello, World!");
     /// ```
     pub fn from<S: AsRef<str>>(string: S) -> Self {
-        let string = String::from(string.as_ref());
-        let string_len = string.len();
-        let pieces = if string.is_empty().not() {
-            vec![Piece::new(PieceSource::Original, 0, string_len)]
+        PieceTable::from_source(String::from(string.as_ref()))
+    }
+
+    /// Creates a Piece Table from lines joined with `\n`
+    ///
+    /// No trailing newline is added after the last line.
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let table = PieceTable::from_lines(vec![String::from("Hello,"), String::from("World!")]);
+    /// assert_eq!(table.to_string(), "Hello,\nWorld!");
+    /// ```
+    pub fn from_lines<I: IntoIterator<Item = S>, S: AsRef<str>>(lines: I) -> Self {
+        let lines: Vec<S> = lines.into_iter().collect();
+        let capacity = lines.iter().map(|line| line.as_ref().len()).sum::<usize>()
+            + lines.len().saturating_sub(1);
+
+        let mut string = String::with_capacity(capacity);
+        for (i, line) in lines.iter().enumerate() {
+            if i != 0 {
+                string.push('\n');
+            }
+            string.push_str(line.as_ref());
+        }
+
+        PieceTable::from_source(string)
+    }
+
+    /// Creates a Piece Table that reads its original text from `source`
+    ///
+    /// The source is never modified, edits are stored separately.
+    ///
+    /// # Example
+    /// ```
+    /// use std::sync::Arc;
+    /// use piece_table::PieceTable;
+    ///
+    /// let text: Arc<str> = Arc::from("Hello");
+    /// let mut table = PieceTable::from_source(Arc::clone(&text));
+    /// table.append(", World!");
+    /// assert_eq!(table.to_string(), "Hello, World!");
+    /// assert_eq!(&*text, "Hello");
+    /// ```
+    pub fn from_source<T: TextSource + 'static>(source: T) -> Self {
+        let string_len = source.as_str().len();
+        let pieces = if string_len > 0 {
+            VecDeque::from([Piece::new(PieceSource::Original, 0, string_len)])
         } else {
-            vec![]
+            VecDeque::new()
         };
 
         PieceTable {
-            original: string,
+            original: Arc::new(source),
             addition: String::new(),
             pieces,
             total_length: string_len,
             history: History::new(Commit::new()),
+            history_enabled: true,
+            history_epoch: 0,
+            last_edit: None,
+            last_edit_coalesced: false,
+            max_len: None,
+            bounds_policy: BoundsPolicy::Panic,
+            structural_observer: None,
+            commit_strategy: None,
+            max_append_run: None,
+            line_ending: LineEnding::Lf,
+            slice_cache: None,
+            line_index: None,
+            piece_index: Mutex::new(PieceIndex::default()),
+            incomplete_write: Vec::new(),
+            #[cfg(feature = "intern-additions")]
+            interned: HashMap::new(),
+            inline_pieces: false,
+            inline_offset: 0,
+        }
+    }
+
+    /// Creates a Piece Table from a string with one piece per line (including its line ending)
+    ///
+    /// Trades more pieces up front for piece boundaries at line starts, which line based
+    /// navigation can use directly. Removals may merge neighbouring line pieces again.
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let table = PieceTable::from_line_split("Hello,\r\nWorld!\n");
+    /// let lines: Vec<&str> = table.pieces_in(0..table.len()).map(|(info, _)| info.text).collect();
+    /// assert_eq!(lines, vec!["Hello,\r\n", "World!\n"]);
+    /// ```
+    pub fn from_line_split<S: AsRef<str>>(string: S) -> Self {
+        let mut table = PieceTable::from(string);
+
+        let mut line_start = 0;
+        let mut pieces: VecDeque<Piece> = table
+            .line_breaks()
+            .map(|line_break| {
+                let piece = Piece::new(
+                    PieceSource::Original,
+                    line_start,
+                    line_break.end - line_start,
+                );
+                line_start = line_break.end;
+                piece
+            })
+            .collect();
+        if line_start < table.total_length {
+            let length = table.total_length - line_start;
+            pieces.push_back(Piece::new(PieceSource::Original, line_start, length));
+        }
+
+        table.pieces = pieces;
+        table
+    }
+
+    /// Creates a Piece Table from its parts: the original text, a prefilled addition
+    /// buffer and the pieces (as byte ranges into their buffer) making up the text
+    ///
+    /// Empty pieces are dropped. The history starts at the resulting text.
+    ///
+    /// # Errors
+    /// Returns `EditError::InvalidPiece` if a range is reversed, reaches past its buffer,
+    /// or starts or ends inside a character
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::{PieceSource, PieceTable};
+    ///
+    /// let table = PieceTable::from_parts(
+    ///     "HelloWorld!",
+    ///     String::from(", "),
+    ///     [
+    ///         (PieceSource::Original, 0..5),
+    ///         (PieceSource::Addition, 0..2),
+    ///         (PieceSource::Original, 5..11),
+    ///     ],
+    /// )
+    /// .unwrap();
+    /// assert_eq!(table.to_string(), "Hello, World!");
+    /// ```
+    pub fn from_parts<S: AsRef<str>, I: IntoIterator<Item = (PieceSource, Range<usize>)>>(
+        original: S,
+        addition: String,
+        pieces: I,
+    ) -> Result<Self, EditError> {
+        let mut table = PieceTable::from(original);
+        table.addition = addition;
+
+        table.pieces.clear();
+        table.total_length = 0;
+        for (index, (source, range)) in pieces.into_iter().enumerate() {
+            // Inline pieces hold their own text, there is none to point them at
+            let piece = Piece::new(source, range.start, range.len());
+            if range.start > range.end
+                || source == PieceSource::Inline
+                || table.piece_fits_buffer(&piece).not()
+            {
+                return Err(EditError::InvalidPiece { index });
+            }
+            if piece.length > 0 {
+                table.pieces.push_back(piece);
+                table.total_length += piece.length;
+            }
         }
+
+        Ok(table)
+    }
+
+    /// Creates a Piece Table whose text is `original` followed by the prefilled `addition`
+    /// buffer, see `PieceTable::from_parts` to arrange the buffers freely
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::with_addition("Hello", String::from(", World!"));
+    /// assert_eq!(table.to_string(), "Hello, World!");
+    /// assert!(!table.undo());
+    /// ```
+    pub fn with_addition<S: AsRef<str>>(original: S, addition: String) -> Self {
+        let original = original.as_ref();
+        let pieces = [
+            (PieceSource::Original, 0..original.len()),
+            (PieceSource::Addition, 0..addition.len()),
+        ];
+        PieceTable::from_parts(original, addition, pieces).expect("Pieces span their whole buffers")
+    }
+
+    /// Creates a Piece Table from a string that does not record an edit history
+    ///
+    /// Meant for tables that are built once and mostly read, see `PieceTable::disable_history`.
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from_readonly("Hello");
+    /// table.append(", World!");
+    /// table.undo();
+    /// assert_eq!(table.to_string(), "Hello, World!");
+    /// ```
+    pub fn from_readonly<S: AsRef<str>>(string: S) -> Self {
+        let mut table = PieceTable::from(string);
+        table.disable_history();
+        table
     }
 
     /// Inserts `string` at `pos`.
     ///
     /// If `pos == PieceTable::len`, it appends the str (see `PieceTable::append`),
+    /// see `PieceTable::try_insert` to handle invalid input without panicking.
+    /// Text inserted right behind the previously inserted text (e.g. typing) extends
+    /// its piece instead of adding a new one, but is still a separate undo step.
     ///
     /// # Panic
     /// Panics when either:
-    /// - `pos > PieceTable::len`
+    /// - `pos > PieceTable::len` (unless positions are clamped, see `PieceTable::set_bounds_policy`)
     /// - `string` is empty
+    /// - `pos` falls inside a character
+    /// - the text would grow beyond the maximum length (see `PieceTable::set_max_len`)
     ///
     /// # Example
     /// ```
@@ -73,61 +365,252 @@ impl PieceTable {
     /// table.insert(5, ", ");
     /// assert_eq!(table.to_string(), "Hello, World!");
     /// ```
-    pub fn insert<S: AsRef<str>>(&mut self, mut pos: usize, string: S) {
-        assert!(
-            pos <= self.total_length,
-            "Insert position must be within or at the end of the text"
-        );
+    pub fn insert<S: AsRef<str>>(&mut self, pos: usize, string: S) {
+        if let Err(err) = self.try_insert(pos, string) {
+            self.panic_on("insert", err);
+        }
+    }
 
+    /// Inserts `string` at `pos`, returning an error instead of panicking on invalid input
+    ///
+    /// The table and its history are left untouched if the insertion is rejected.
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::{EditError, PieceTable};
+    ///
+    /// let mut table = PieceTable::from("Hello");
+    /// table.set_max_len(8);
+    /// assert_eq!(table.try_insert(5, ", W"), Ok(()));
+    /// assert_eq!(
+    ///     table.try_insert(8, "orld!"),
+    ///     Err(EditError::TooLarge { len: 13, max_len: 8 })
+    /// );
+    /// assert_eq!(table.to_string(), "Hello, W");
+    /// ```
+    pub fn try_insert<S: AsRef<str>>(&mut self, pos: usize, string: S) -> Result<(), EditError> {
         let string = string.as_ref();
-        assert!(string.is_empty().not(), "Inserted string must not be empty");
+        let pos = self.clamp_pos(pos);
+        self.check_insert(pos, string)?;
+        if self.extend_append_run(pos, string) {
+            return Ok(());
+        }
 
-        let special_case = pos == 0 || pos == self.total_length;
+        let mut commit = self.new_commit();
+        self.insert_into(&mut commit, pos, string);
+        self.save_commit(commit);
 
-        let piece = Piece::new(PieceSource::Addition, self.addition.len(), string.len());
-        let mut commit = Commit::new();
+        Ok(())
+    }
 
-        self.total_length += string.len();
-        self.addition.push_str(string);
+    /// Inserts the UTF-8 encoded `bytes` at `pos`
+    ///
+    /// Returns the `Utf8Error` and leaves the table untouched if `bytes` is not valid UTF-8.
+    ///
+    /// # Panic
+    /// Panics like `PieceTable::insert`
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("HelloWorld!");
+    /// assert!(table.insert_bytes(5, b", ").is_ok());
+    /// assert!(table.insert_bytes(0, &[0xff]).is_err());
+    /// assert_eq!(table.to_string(), "Hello, World!");
+    /// ```
+    pub fn insert_bytes(&mut self, pos: usize, bytes: &[u8]) -> Result<(), Utf8Error> {
+        let string = str::from_utf8(bytes)?;
+        self.insert(pos, string);
+        Ok(())
+    }
+
+    /// Inserts `chars` at `pos` as a single edit
+    ///
+    /// Nothing is recorded if `chars` is empty.
+    ///
+    /// # Panic
+    /// Panics like `PieceTable::insert`, except for empty input
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("Hello!");
+    /// table.insert_chars(5, ", World".chars());
+    /// assert_eq!(table.to_string(), "Hello, World!");
+    ///
+    /// table.undo();
+    /// assert_eq!(table.to_string(), "Hello!");
+    /// ```
+    pub fn insert_chars<I: IntoIterator<Item = char>>(&mut self, pos: usize, chars: I) {
+        let string: String = chars.into_iter().collect();
+        if string.is_empty() {
+            return;
+        }
+
+        self.insert(pos, string);
+    }
 
-        if special_case {
-            // FIXME: insertion at the very front is slow, can this be done faster?
-            let idx = if pos == 0 { 0 } else { self.pieces.len() };
+    /// Appends a string at the end, returning an error instead of panicking on invalid input
+    ///
+    /// See `PieceTable::try_insert`
+    pub fn try_append<S: AsRef<str>>(&mut self, string: S) -> Result<(), EditError> {
+        self.try_insert(self.total_length, string)
+    }
 
-            self.pieces.insert(idx, piece);
-            commit.add_change(idx, piece, ChangeType::Insertion);
+    /// Limits the length insertions may grow the text to
+    ///
+    /// `insert`/`append` panic and `try_insert`/`try_append` return `EditError::TooLarge`
+    /// when exceeding the limit. Text that already exceeds the limit is kept.
+    /// Composite edits (e.g. `PieceTable::replace_all_with`) are not limited.
+    pub fn set_max_len(&mut self, max_len: usize) {
+        self.max_len = Some(max_len);
+    }
 
-            self.history.save(commit);
-            return;
+    /// Sets how out-of-range positions passed to `insert`, `remove` and slicing are handled
+    ///
+    /// With `BoundsPolicy::Clamp` positions are saturated to `0..=PieceTable::len`,
+    /// removals are shortened to the available text and removals that end up empty are ignored.
+    /// Defaults to `BoundsPolicy::Panic`.
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::{BoundsPolicy, PieceTable};
+    ///
+    /// let mut table = PieceTable::from("Hello");
+    /// table.set_bounds_policy(BoundsPolicy::Clamp);
+    /// table.insert(42, ", World!");
+    /// assert_eq!(table.to_string(), "Hello, World!");
+    /// table.remove(5, 42);
+    /// assert_eq!(table.to_string(), "Hello");
+    /// assert_eq!(table.range_slice(3..42), "lo");
+    /// ```
+    pub fn set_bounds_policy(&mut self, policy: BoundsPolicy) {
+        self.bounds_policy = policy;
+    }
+
+    /// Saturates `pos` to the length of the text if positions are clamped
+    pub(crate) fn clamp_pos(&self, pos: usize) -> usize {
+        match self.bounds_policy {
+            BoundsPolicy::Panic => pos,
+            BoundsPolicy::Clamp => pos.min(self.total_length),
         }
+    }
 
-        let mut len = 0;
-        let mut idx = 0;
-        for (i, piece) in self.pieces.iter().enumerate() {
-            if len + piece.length <= pos {
-                len += piece.length;
-                continue;
-            }
+    /// Returns the range of `n` bytes from `pos`, shortened to the text if positions are clamped
+    ///
+    /// The end saturates at `usize::MAX`, which the bounds checks of the edits still reject.
+    pub(crate) fn clamp_range(&self, pos: usize, n: usize) -> Range<usize> {
+        let pos = self.clamp_pos(pos);
+        match self.bounds_policy {
+            BoundsPolicy::Panic => pos..pos.saturating_add(n),
+            BoundsPolicy::Clamp => pos..pos + n.min(self.total_length - pos),
+        }
+    }
+
+    /// Checks whether `string` can be inserted at `pos`
+    fn check_insert(&self, pos: usize, string: &str) -> Result<(), EditError> {
+        if pos > self.total_length {
+            return Err(EditError::PositionOutOfBounds {
+                pos,
+                len: self.total_length,
+            });
+        }
+        if string.is_empty() {
+            return Err(EditError::EmptyString);
+        }
+        if self.is_char_boundary(pos).not() {
+            return Err(EditError::NotCharBoundary { byte: pos });
+        }
+
+        let len = self.total_length + string.len();
+        match self.max_len {
+            Some(max_len) if len > max_len => Err(EditError::TooLarge { len, max_len }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Panics with `err`, naming `method` and the buffer offset for a position inside a character
+    fn panic_on(&self, method: &str, err: EditError) -> ! {
+        let EditError::NotCharBoundary { byte } = err else {
+            panic!("{err}");
+        };
+
+        let (idx, start) = self.piece_at(byte);
+        let piece = self.pieces[idx];
+        panic!(
+            "{method}: position {byte} (offset {} in the {:?} buffer) is not a char boundary",
+            piece.offset + byte - start,
+            piece.source
+        );
+    }
+
+    /// Inserts `string` at `pos` and records the changes in `commit`
+    pub(crate) fn insert_into(&mut self, commit: &mut Commit, pos: usize, string: &str) {
+        assert!(
+            pos <= self.total_length,
+            "Insert position {pos} out of bounds for length {}",
+            self.total_length
+        );
 
-            idx = i;
-            pos = pos - len;
-            break;
+        assert!(string.is_empty().not(), "Inserted string must not be empty");
+
+        let special_case = pos == 0 || pos == self.total_length;
+
+        // Piece containing `pos` and the position relative to the piece start
+        let mut idx = 0;
+        let mut offset = 0;
+        if special_case.not() {
+            let start;
+            (idx, start) = self.piece_at(pos);
+            offset = pos - start;
         }
 
+        // Text inserted right behind the latest addition (e.g. typing) extends its piece
+        let previous = match (pos, offset) {
+            (0, _) => None,
+            _ if pos == self.total_length => self.pieces.len().checked_sub(1),
+            (_, 0) => Some(idx - 1),
+            _ => None,
+        };
+
+        let piece = match self.store_inline(string, previous) {
+            Some(piece) => piece,
+            None => self.store_addition(string),
+        };
+        commit.add_edit(EditDelta::insertion(pos, string.len()));
+
+        self.total_length += string.len();
+
         use ChangeType::*;
-        if pos == 0 {
+        if let Some(previous) =
+            previous.filter(|previous| self.pieces[*previous].is_continued_by(&piece))
+        {
+            let old_piece = self.pieces[previous];
+            let extended = old_piece.joined(&piece);
+            self.pieces[previous] = extended;
+            commit.add_change(previous, old_piece, Deletion);
+            commit.add_change(previous, extended, Insertion);
+
+            idx = previous;
+        } else if special_case {
+            idx = if pos == 0 { 0 } else { self.pieces.len() };
+
+            self.pieces.insert(idx, piece);
+            commit.add_change(idx, piece, Insertion);
+        } else if offset == 0 {
             self.pieces.insert(idx, piece);
             commit.add_change(idx, piece, Insertion);
         } else {
             // Split existing piece into two and insert new piece between
-            let trailing = Piece::new(
-                self.pieces[idx].source,
-                self.pieces[idx].offset + pos,
-                self.pieces[idx].length - pos,
+            let trailing = self.pieces[idx].with_span(
+                self.pieces[idx].offset + offset,
+                self.pieces[idx].length - offset,
             );
 
             let old_piece = self.pieces[idx];
-            self.pieces[idx].length = pos;
+            self.pieces[idx] = old_piece.with_span(old_piece.offset, offset);
             commit.add_change(idx, old_piece, Deletion);
             commit.add_change(idx, self.pieces[idx], Insertion);
 
@@ -137,14 +620,13 @@ impl PieceTable {
             self.pieces.insert(idx + 2, trailing);
             commit.add_change(idx + 2, trailing, Insertion);
         }
-
-        self.history.save(commit);
+        self.invalidate_piece_index(idx);
     }
 
     /// Appends a string at the end
     ///
     /// # Panic
-    /// Panics if the string is empty
+    /// Panics if the string is empty or the text would grow beyond the maximum length
     ///
     /// # Example
     /// ```
@@ -158,13 +640,63 @@ impl PieceTable {
         self.insert(self.total_length, string);
     }
 
+    /// Appends all `strings` at the end as a single piece and a single undo step
+    ///
+    /// Empty strings are skipped, nothing is recorded if all strings are empty.
+    ///
+    /// # Panic
+    /// Panics if the text would grow beyond the maximum length (see `PieceTable::set_max_len`)
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("Log:\n");
+    /// table.append_all(["started\n", "", "stopped\n"]);
+    /// assert_eq!(table.to_string(), "Log:\nstarted\nstopped\n");
+    ///
+    /// table.undo();
+    /// assert_eq!(table.to_string(), "Log:\n");
+    /// ```
+    pub fn append_all<I: IntoIterator<Item = S>, S: AsRef<str>>(&mut self, strings: I) {
+        let offset = self.addition.len();
+        for string in strings {
+            self.addition.push_str(string.as_ref());
+        }
+
+        let string_len = self.addition.len() - offset;
+        if string_len == 0 {
+            return;
+        }
+
+        let len = self.total_length + string_len;
+        if let Some(max_len) = self.max_len.filter(|max_len| len > *max_len) {
+            self.addition.truncate(offset);
+            panic!("{}", EditError::TooLarge { len, max_len });
+        }
+
+        let piece = Piece::new(PieceSource::Addition, offset, string_len);
+        let idx = self.pieces.len();
+        let mut commit = self.new_commit();
+        commit.add_edit(EditDelta::insertion(self.total_length, string_len));
+        commit.add_change(idx, piece, ChangeType::Insertion);
+
+        self.pieces.push_back(piece);
+        self.total_length = len;
+        self.save_commit(commit);
+    }
+
     /// Removes a string from `pos` of length `n`
     ///
+    /// See `PieceTable::try_remove` to handle invalid input without panicking.
+    ///
     /// # Panic
-    /// Panics if:
+    /// Panics if (unless positions are clamped, see `PieceTable::set_bounds_policy`):
     /// - `pos + n > PieceTable::len`
     /// - `n == 0`
     ///
+    /// Panics as well if `pos` or `pos + n` falls inside a character.
+    ///
     /// # Example
     /// ```
     /// use piece_table::PieceTable;
@@ -174,72 +706,229 @@ impl PieceTable {
     /// assert_eq!(table.to_string(), "Hello");
     /// ```
     pub fn remove(&mut self, pos: usize, n: usize) {
-        let end = pos + n;
-        assert!(
-            end <= self.total_length,
-            "Removed string must be within the text"
-        );
-        assert!(n != 0, "Must remove at least 1 character");
+        let range = self.clamp_range(pos, n);
+        if range.is_empty() && self.bounds_policy == BoundsPolicy::Clamp {
+            return;
+        }
 
-        type Index = usize;
-        type Length = usize;
-        type Offset = usize;
-        enum Remove {
-            End(Index, Length),
-            Full(Index),
-            Start(Index, Length),
-            Slice(Index, Offset),
+        let mut commit = self.new_commit();
+        self.remove_into(&mut commit, range.start, range.len());
+        self.save_commit(commit);
+    }
+
+    /// Removes a string from `pos` of length `n`, returning an error instead of panicking
+    /// on invalid input
+    ///
+    /// The table and its history are left untouched if the removal is rejected.
+    /// With clamped positions (see `PieceTable::set_bounds_policy`) out-of-range removals
+    /// are shortened like in `PieceTable::remove` instead.
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::{EditError, PieceTable};
+    ///
+    /// let mut table = PieceTable::from("Hello, World!");
+    /// assert_eq!(
+    ///     table.try_remove(5, 10),
+    ///     Err(EditError::RangeOutOfBounds { start: 5, end: 15, len: 13 })
+    /// );
+    /// assert_eq!(table.try_remove(5, 0), Err(EditError::EmptyRange));
+    /// assert_eq!(table.try_remove(5, 8), Ok(()));
+    /// assert_eq!(table.to_string(), "Hello");
+    /// ```
+    pub fn try_remove(&mut self, pos: usize, n: usize) -> Result<(), EditError> {
+        let range = self.clamp_range(pos, n);
+        if range.is_empty() && self.bounds_policy == BoundsPolicy::Clamp {
+            return Ok(());
         }
-        use Remove::*;
+        self.check_remove(range.start, range.len())?;
 
-        let mut remove = Vec::new();
-        let mut len = 0;
-        for (idx, piece) in self.pieces.iter().enumerate() {
-            if len >= end {
-                break;
-            }
-            let prev_len = len;
-            len += piece.length;
+        let mut commit = self.new_commit();
+        self.remove_into(&mut commit, range.start, range.len());
+        self.save_commit(commit);
 
-            let remove_start = pos <= prev_len;
-            let remove_end = end >= len && len > pos;
-            let remove_slice = prev_len < pos && end < len;
+        Ok(())
+    }
 
-            match (remove_start, remove_end) {
-                (false, true) => remove.push(End(idx, len - pos)),
-                (true, true) => remove.push(Full(idx)),
-                (true, false) => remove.push(Start(idx, end - prev_len)),
-                _ if remove_slice => remove.push(Slice(idx, pos - prev_len)),
-                _ => continue,
+    /// Checks whether `n` bytes can be removed from `pos`
+    fn check_remove(&self, pos: usize, n: usize) -> Result<(), EditError> {
+        match pos.checked_add(n) {
+            Some(end) if end <= self.total_length => {}
+            end => {
+                return Err(EditError::RangeOutOfBounds {
+                    start: pos,
+                    end: end.unwrap_or(usize::MAX),
+                    len: self.total_length,
+                })
+            }
+        }
+        if n == 0 {
+            return Err(EditError::EmptyRange);
+        }
+        for byte in [pos, pos + n] {
+            if self.is_char_boundary(byte).not() {
+                return Err(EditError::NotCharBoundary { byte });
             }
         }
 
-        use ChangeType::*;
-        let mut commit = Commit::new();
-        self.total_length -= n;
-        for remove_piece in remove.iter().rev() {
-            match remove_piece {
-                End(idx, len) => {
-                    let old_piece = self.pieces[*idx];
-                    self.pieces[*idx].length -= *len;
-                    commit.add_change(*idx, old_piece, Deletion);
-                    commit.add_change(*idx, self.pieces[*idx], Insertion);
-                }
-                Full(idx) => {
-                    commit.add_change(*idx, self.pieces.remove(*idx), Deletion);
-                }
-                Start(idx, len) => {
-                    let old_piece = self.pieces[*idx];
-                    self.pieces[*idx].length -= *len;
-                    self.pieces[*idx].offset += *len;
-                    commit.add_change(*idx, old_piece, Deletion);
+        Ok(())
+    }
+
+    /// Inserts `string` at `pos` without recording an undo step
+    ///
+    /// Meant for bulk programmatic edits that must not end up in the user's undo history
+    /// (e.g. formatting a file after loading it). Unlike `PieceTable::disable_history`
+    /// the history is kept, and unlike a regular edit no changes are collected.
+    /// The history is unaware of the edit: undoing or redoing steps recorded before it
+    /// restores wrong text or panics, so reset the history (see `PieceTable::reset_history`)
+    /// before undoing past it.
+    ///
+    /// # Panic
+    /// Panics like `PieceTable::insert`
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("fn main(){}");
+    /// table.insert_no_history(9, " ");
+    /// assert_eq!(table.to_string(), "fn main() {}");
+    /// assert!(!table.undo());
+    /// ```
+    pub fn insert_no_history<S: AsRef<str>>(&mut self, pos: usize, string: S) {
+        let string = string.as_ref();
+        let pos = self.clamp_pos(pos);
+        if let Err(err) = self.check_insert(pos, string) {
+            self.panic_on("insert", err);
+        }
+
+        let mut commit = self.unrecorded_commit();
+        self.insert_into(&mut commit, pos, string);
+        self.finish_edit(commit, false);
+    }
+
+    /// Removes a string from `pos` of length `n` without recording an undo step
+    ///
+    /// See `PieceTable::insert_no_history` for how this interacts with the history.
+    ///
+    /// # Panic
+    /// Panics like `PieceTable::remove`
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("Hello, World!  ");
+    /// table.remove_no_history(13, 2);
+    /// assert_eq!(table.to_string(), "Hello, World!");
+    /// assert!(!table.undo());
+    /// ```
+    pub fn remove_no_history(&mut self, pos: usize, n: usize) {
+        let range = self.clamp_range(pos, n);
+        if range.is_empty() && self.bounds_policy == BoundsPolicy::Clamp {
+            return;
+        }
+
+        let mut commit = self.unrecorded_commit();
+        self.remove_into(&mut commit, range.start, range.len());
+        self.finish_edit(commit, false);
+    }
+
+    /// Removes a string from `pos` of length `n` and records the changes in `commit`
+    pub(crate) fn remove_into(&mut self, commit: &mut Commit, pos: usize, n: usize) {
+        let end = match pos.checked_add(n) {
+            Some(end) if end <= self.total_length => end,
+            end => panic!(
+                "Remove range {pos}..{} out of bounds for length {}",
+                end.unwrap_or(usize::MAX),
+                self.total_length
+            ),
+        };
+        assert!(n != 0, "Must remove at least 1 character");
+
+        type Index = usize;
+        type Length = usize;
+        type Offset = usize;
+        enum Remove {
+            End(Index, Length),
+            Full(Index),
+            Start(Index, Length),
+            Slice(Index, Offset),
+        }
+        use Remove::*;
+
+        // Start at the first piece ending at or behind `pos`, empty pieces at `pos` are removed
+        let (first, mut len) = match pos {
+            0 => (0, 0),
+            _ => self.piece_at(pos - 1),
+        };
+        let mut remove = Vec::new();
+        for (idx, piece) in self.pieces.range(first..).enumerate() {
+            let idx = first + idx;
+            if len >= end {
+                break;
+            }
+            let prev_len = len;
+            len += piece.length;
+
+            // Empty pieces carry no text, drop those touching the range instead of trimming them
+            if piece.length == 0 {
+                if pos <= prev_len {
+                    remove.push(Full(idx));
+                }
+                continue;
+            }
+
+            let remove_start = pos <= prev_len;
+            let remove_end = end >= len && len > pos;
+            let remove_slice = prev_len < pos && end < len;
+
+            // Only the pieces cut by the range can split a character
+            let text = self.piece_str(piece);
+            for byte in [pos, end] {
+                if byte > prev_len && byte < len && text.is_char_boundary(byte - prev_len).not() {
+                    self.panic_on("remove", EditError::NotCharBoundary { byte });
+                }
+            }
+
+            match (remove_start, remove_end) {
+                (false, true) => remove.push(End(idx, len - pos)),
+                (true, true) => remove.push(Full(idx)),
+                (true, false) => remove.push(Start(idx, end - prev_len)),
+                _ if remove_slice => remove.push(Slice(idx, pos - prev_len)),
+                _ => continue,
+            }
+        }
+
+        use ChangeType::*;
+        commit.add_edit(EditDelta::removal(pos, n));
+        self.total_length -= n;
+        for remove_piece in remove.iter().rev() {
+            match remove_piece {
+                End(idx, len) => {
+                    let old_piece = self.pieces[*idx];
+                    self.pieces[*idx] =
+                        old_piece.with_span(old_piece.offset, old_piece.length - len);
+                    commit.add_change(*idx, old_piece, Deletion);
+                    commit.add_change(*idx, self.pieces[*idx], Insertion);
+                }
+                Full(idx) => {
+                    let old_piece = self.pieces.remove(*idx).expect("Piece index is in bounds");
+                    commit.add_change(*idx, old_piece, Deletion);
+                }
+                Start(idx, len) => {
+                    let old_piece = self.pieces[*idx];
+                    self.pieces[*idx] =
+                        old_piece.with_span(old_piece.offset + len, old_piece.length - len);
+                    commit.add_change(*idx, old_piece, Deletion);
                     commit.add_change(*idx, self.pieces[*idx], Insertion);
                 }
                 Slice(idx, offset) => {
                     let old_piece = self.pieces[*idx];
-                    let leading = Piece::new(old_piece.source, old_piece.offset, *offset);
+                    let leading = old_piece.with_span(old_piece.offset, *offset);
                     let trailing_len = old_piece.length - *offset - n;
-                    let trailing = Piece::new(old_piece.source, *offset + n, trailing_len);
+                    let trailing_offset = old_piece.offset + *offset + n;
+                    let trailing = old_piece.with_span(trailing_offset, trailing_len);
 
                     self.pieces[*idx] = leading;
                     commit.add_change(*idx, old_piece, Deletion);
@@ -251,7 +940,384 @@ impl PieceTable {
             }
         }
 
-        self.history.save(commit);
+        // Pieces around the removed text may now be contiguous, only the
+        // neighbourhood of the first touched piece has to be checked
+        if let Some(End(first, _) | Full(first) | Start(first, _) | Slice(first, _)) =
+            remove.first()
+        {
+            self.invalidate_piece_index(*first);
+            let mut idx = first.saturating_sub(1);
+            let mut last = first + 1;
+            while idx <= last {
+                if self.merge_with_next(commit, idx) {
+                    last = last.saturating_sub(1);
+                } else {
+                    idx += 1;
+                }
+            }
+        }
+    }
+
+    /// Merges the pieces at `idx` and `idx + 1` if they are contiguous in the same source
+    /// and records the merge in `commit`
+    pub(crate) fn merge_with_next(&mut self, commit: &mut Commit, idx: usize) -> bool {
+        let (Some(&piece), Some(&next)) = (self.pieces.get(idx), self.pieces.get(idx + 1)) else {
+            return false;
+        };
+        if piece.is_continued_by(&next).not() {
+            return false;
+        }
+
+        let merged = piece.joined(&next);
+        self.pieces.remove(idx + 1);
+        self.pieces[idx] = merged;
+        self.invalidate_piece_index(idx);
+
+        use ChangeType::*;
+        commit.add_change(idx + 1, next, Deletion);
+        commit.add_change(idx, piece, Deletion);
+        commit.add_change(idx, merged, Insertion);
+
+        true
+    }
+
+    /// Merges all neighbouring pieces that are contiguous in the same buffer
+    ///
+    /// Returns the number of merges. The text doesn't change, so no undo step is added.
+    /// Instead the merges are recorded in the latest undo step, undoing it restores the
+    /// pieces from before the merges. If the latest undo step was undone and other steps
+    /// continue from the current state, the history is discarded (see
+    /// `PieceTable::reset_history`), as merging would invalidate the piece positions recorded
+    /// by the redo steps.
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("");
+    /// table.append_all(["Hello"]);
+    /// table.append_all([", World!"]);
+    /// assert_eq!(table.coalesce_pieces(), 1);
+    /// assert_eq!(table.stats().piece_count, 1);
+    ///
+    /// table.undo();
+    /// assert_eq!(table.to_string(), "Hello");
+    /// ```
+    pub fn coalesce_pieces(&mut self) -> usize {
+        let mut commit = self.new_commit();
+        let mut merges = 0;
+        let mut idx = 0;
+        while idx + 1 < self.pieces.len() {
+            if self.merge_with_next(&mut commit, idx) {
+                merges += 1;
+            } else {
+                idx += 1;
+            }
+        }
+        if merges == 0 {
+            return 0;
+        }
+
+        if self.tracks_structure() {
+            let changes = commit.changes.iter().map(|change| (change.typ, change.pos));
+            self.notify_structural_change(&StructuralDelta::from_changes(changes));
+        }
+        // A root without redo steps needs no record, there is nothing to undo
+        if self.history_enabled {
+            let head = &self.history.changes[self.history.head];
+            if self.history.can_amend() {
+                self.history.extend_head(commit);
+            } else if head.next.is_empty().not() {
+                self.reset_history();
+            }
+        }
+
+        self.debug_validate_length();
+        merges
+    }
+
+    /// Replaces the text in `range` with `string` as a single undo step
+    ///
+    /// An empty `range` only inserts, an empty `string` only removes.
+    /// Nothing is recorded if `range` already contains `string`.
+    /// Anchors inside `range` are invalidated, see `PieceTable::replace_range_keep_anchors`
+    /// to keep them.
+    ///
+    /// # Panic
+    /// Panics if
+    /// - `range.end > PieceTable::len`
+    /// - `range.end < range.start`
+    /// - `range.start` or `range.end` falls inside a character
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("Hello, World!");
+    /// table.replace_range(7..12, "Rust");
+    /// assert_eq!(table.to_string(), "Hello, Rust!");
+    ///
+    /// table.undo();
+    /// assert_eq!(table.to_string(), "Hello, World!");
+    /// ```
+    pub fn replace_range<S: AsRef<str>>(&mut self, range: Range<usize>, string: S) {
+        let string = string.as_ref();
+        self.assert_replace_range(&range);
+
+        // Replacing text with itself would only fragment the pieces and add an undo step
+        if self.range_eq(range.clone(), string) {
+            return;
+        }
+
+        let mut commit = self.new_commit();
+        if range.is_empty().not() {
+            self.remove_into(&mut commit, range.start, range.len());
+        }
+        if string.is_empty().not() {
+            self.insert_into(&mut commit, range.start, string);
+        }
+        self.save_commit(commit);
+    }
+
+    /// Asserts that `range` can be replaced (see `PieceTable::replace_range`)
+    fn assert_replace_range(&self, range: &Range<usize>) {
+        assert!(
+            range.start <= range.end,
+            "Replace range starts at {} but ends at {}",
+            range.start,
+            range.end
+        );
+        assert!(
+            range.end <= self.total_length,
+            "Replace range {}..{} out of bounds for length {}",
+            range.start,
+            range.end,
+            self.total_length
+        );
+        for byte in [range.start, range.end] {
+            assert!(
+                self.is_char_boundary(byte),
+                "{}",
+                EditError::NotCharBoundary { byte }
+            );
+        }
+    }
+
+    /// Replaces `n` bytes from `pos` with `string` as a single undo step
+    ///
+    /// Works like `PieceTable::remove` followed by `PieceTable::insert`, but both edits are
+    /// recorded in one commit (see `PieceTable::replace_range`). An `n` of 0 only inserts,
+    /// an empty `string` only removes.
+    ///
+    /// # Panic
+    /// Panics if `pos + n > PieceTable::len` (unless positions are clamped,
+    /// see `PieceTable::set_bounds_policy`) or either end falls inside a character
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("Hello, World!");
+    /// table.replace(7, 5, "Rust");
+    /// assert_eq!(table.to_string(), "Hello, Rust!");
+    ///
+    /// table.undo();
+    /// assert_eq!(table.to_string(), "Hello, World!");
+    /// ```
+    pub fn replace<S: AsRef<str>>(&mut self, pos: usize, n: usize, string: S) {
+        self.replace_range(self.clamp_range(pos, n), string);
+    }
+
+    /// Replaces the text in `range` with `f` applied to it as a single undo step
+    ///
+    /// Nothing is recorded if `f` returns the text unchanged.
+    ///
+    /// # Panic
+    /// Panics if
+    /// - `range.end > PieceTable::len`
+    /// - `range.end < range.start`
+    /// - `range.start` or `range.end` falls inside a character
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("Hello, World!");
+    /// table.map_range(7..12, str::to_uppercase);
+    /// assert_eq!(table.to_string(), "Hello, WORLD!");
+    /// ```
+    pub fn map_range<F: Fn(&str) -> String>(&mut self, range: Range<usize>, f: F) {
+        self.assert_replace_range(&range);
+
+        let text = self._slice(range.start, range.end);
+        let mapped = f(&text);
+        if mapped != text {
+            self.replace_range(range, mapped);
+        }
+    }
+
+    /// Stops recording edits in the history and discards the existing history
+    ///
+    /// Subsequent edits skip all history bookkeeping and `undo`/`hot_redo` become no-ops.
+    /// Enabling the history again is not supported.
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("Hello, ");
+    /// table.disable_history();
+    /// table.append("World!");
+    /// table.undo();
+    /// assert_eq!(table.to_string(), "Hello, World!");
+    /// ```
+    pub fn disable_history(&mut self) {
+        self.history = History::new(Commit::new());
+        self.history_enabled = false;
+        self.history_epoch += 1;
+    }
+
+    /// Discards the history, the current text becomes the new root that can't be undone
+    ///
+    /// Recovers a table whose history was corrupted (see `PieceTable::validate`).
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("Hello");
+    /// table.append(", World!");
+    /// table.reset_history();
+    /// assert!(!table.undo());
+    /// assert_eq!(table.to_string(), "Hello, World!");
+    /// ```
+    pub fn reset_history(&mut self) {
+        self.history = History::new(Commit::new());
+        self.history_epoch += 1;
+    }
+
+    /// Checks the internal consistency of the Piece Table
+    ///
+    /// Verifies that every piece points into its source buffer without splitting a character,
+    /// that the length matches the pieces and that the history is a consistent tree.
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("Hello");
+    /// table.append(", World!");
+    /// table.undo();
+    /// assert_eq!(table.validate(), Ok(()));
+    /// ```
+    pub fn validate(&self) -> Result<(), EditError> {
+        for (index, piece) in self.pieces.iter().enumerate() {
+            if self.piece_fits_buffer(piece).not() {
+                return Err(EditError::InvalidPiece { index });
+            }
+        }
+
+        let actual = self.pieces.iter().map(|piece| piece.length).sum();
+        if self.total_length != actual {
+            return Err(EditError::LengthMismatch {
+                len: self.total_length,
+                actual,
+            });
+        }
+
+        if self.history.is_valid().not() {
+            return Err(EditError::InvalidHistory);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the read only buffer the table was created from
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("Hello");
+    /// table.remove(0, 5);
+    /// assert_eq!(table.original_text(), "Hello");
+    /// ```
+    pub fn original_text(&self) -> &str {
+        self.original.as_str()
+    }
+
+    /// Returns an owned copy of the text the table was created from
+    ///
+    /// See `PieceTable::original_text` to borrow it instead.
+    pub fn original_content(&self) -> String {
+        String::from(self.original.as_str())
+    }
+
+    /// Replaces the text with the text the table was created from as a single edit
+    ///
+    /// Nothing is recorded if the text already equals it. The original buffer is
+    /// referenced again, no text is copied.
+    ///
+    /// # Panic
+    /// Panics if the original text exceeds the maximum length (see `PieceTable::set_max_len`)
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("Hello");
+    /// table.append(", World!");
+    /// table.remove(0, 1);
+    /// table.revert_to_original();
+    /// assert_eq!(table.to_string(), "Hello");
+    ///
+    /// table.undo();
+    /// assert_eq!(table.to_string(), "ello, World!");
+    /// ```
+    pub fn revert_to_original(&mut self) {
+        let original_len = self.original.as_str().len();
+        if self.text_eq(self.original.as_str()) {
+            return;
+        }
+        if let Some(max_len) = self.max_len.filter(|max_len| original_len > *max_len) {
+            panic!(
+                "{}",
+                EditError::TooLarge {
+                    len: original_len,
+                    max_len
+                }
+            );
+        }
+
+        let mut commit = self.new_commit();
+        if self.total_length > 0 {
+            self.remove_into(&mut commit, 0, self.total_length);
+        }
+        if original_len > 0 {
+            let piece = Piece::new(PieceSource::Original, 0, original_len);
+            commit.add_edit(EditDelta::insertion(0, original_len));
+            commit.add_change(0, piece, ChangeType::Insertion);
+            self.pieces.push_back(piece);
+            self.total_length = original_len;
+        }
+        self.save_commit(commit);
+    }
+
+    /// Returns the buffer holding all text added while editing
+    ///
+    /// The buffer only grows, removed or undone additions stay in it.
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("Hello");
+    /// table.append(", World!");
+    /// table.undo();
+    /// assert_eq!(table.addition_text(), ", World!");
+    /// ```
+    pub fn addition_text(&self) -> &str {
+        &self.addition
     }
 
     /// Returns the length of the text stored in the Piece Table
@@ -259,8 +1325,46 @@ impl PieceTable {
         self.total_length
     }
 
+    /// Returns `true` if the Piece Table contains no text
+    pub fn is_empty(&self) -> bool {
+        self.total_length == 0
+    }
+
+    /// Returns whether there are changes that `PieceTable::undo` would revert
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("Hello");
+    /// assert!(!table.can_undo());
+    /// table.append("!");
+    /// assert!(table.can_undo());
+    /// ```
+    pub fn can_undo(&self) -> bool {
+        self.history.can_undo()
+    }
+
+    /// Returns whether there are changes that `PieceTable::redo` would restore
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("Hello");
+    /// table.append("!");
+    /// assert!(!table.can_redo());
+    /// table.undo();
+    /// assert!(table.can_redo());
+    /// ```
+    pub fn can_redo(&self) -> bool {
+        self.history.can_redo()
+    }
+
     /// Reverts the Piece Table to the state *before* the last changes
     ///
+    /// Returns `false` if there was nothing to undo.
+    ///
     /// # Example
     /// ```
     /// use piece_table::PieceTable;
@@ -269,51 +1373,48 @@ impl PieceTable {
     /// table.append("World!");
     /// table.append("World!");
     /// assert_eq!(table.to_string(), "Hello, World!World!World!");
-    /// table.undo();
+    /// assert!(table.undo());
     /// assert_eq!(table.to_string(), "Hello, World!World!");
-    /// table.undo();
+    /// assert!(table.undo());
     /// assert_eq!(table.to_string(), "Hello, World!");
+    /// assert!(!table.undo());
     /// ```
-    pub fn undo(&mut self) {
+    pub fn undo(&mut self) -> bool {
+        let tracks_structure = self.tracks_structure();
         let commit = match self.history.undo() {
             Some(commit) => commit,
-            None => return,
+            None => return false,
         };
+        self.last_edit = commit.edit.map(EditDelta::inverse);
+        self.last_edit_coalesced = false;
 
-        // FIXME: lots of duplication with hot_redo
-        for change in commit.changes.iter().rev() {
-            match change.piece.source {
-                PieceSource::Original => assert!(
-                    change.piece.offset + change.piece.length <= self.original.len(),
-                    "Piece change is out of original text bounds"
-                ),
-                PieceSource::Addition => assert!(
-                    change.piece.offset + change.piece.length <= self.addition.len(),
-                    "Piece change is out of addition text bounds"
-                ),
-            }
-
-            match change.typ {
-                ChangeType::Deletion => {
-                    assert!(
-                        change.pos <= self.pieces.len(),
-                        "Undo delete position is out of bounds"
-                    );
-
-                    self.pieces.insert(change.pos, change.piece);
-                    self.total_length += change.piece.length;
-                }
-                ChangeType::Insertion => {
-                    assert!(
-                        change.pos < self.pieces.len(),
-                        "Undo insert position is out of bounds"
-                    );
-
-                    let removed_piece = self.pieces.remove(change.pos);
-                    self.total_length -= removed_piece.length;
-                }
-            }
+        let changes = commit.changes.iter().rev().map(|change| {
+            let typ = match change.typ {
+                ChangeType::Deletion => ChangeType::Insertion,
+                ChangeType::Insertion => ChangeType::Deletion,
+            };
+            (typ, change.pos, change.piece)
+        });
+        let delta = tracks_structure.then(|| {
+            StructuralDelta::from_changes(changes.clone().map(|(typ, pos, _)| (typ, pos)))
+        });
+        let first_change = changes.clone().map(|(_, pos, _)| pos).min();
+        apply_changes(
+            &mut self.pieces,
+            &mut self.total_length,
+            (self.original.as_str().len(), self.addition.len()),
+            changes,
+        );
+        if let Some(delta) = delta {
+            self.notify_structural_change(&delta);
+        }
+        if let Some(first_change) = first_change {
+            self.invalidate_piece_index(first_change);
         }
+        self.invalidate_caches();
+
+        self.debug_validate_length();
+        true
     }
 
     /// Restores the Piece Table to the "hot" state *after* the last undo.
@@ -321,6 +1422,8 @@ impl PieceTable {
     /// Hot state means the state the head was last at (e.g. at a fork in the history
     ///     it can quickly be redone to the last head position without having to select it).
     ///
+    /// Returns `false` if there was nothing to redo.
+    ///
     /// # Example
     /// ```
     /// use piece_table::PieceTable;
@@ -331,68 +1434,616 @@ impl PieceTable {
     /// table.undo();
     /// table.undo();
     /// assert_eq!(table.to_string(), "Hello, World!");
-    /// table.hot_redo();
+    /// assert!(table.hot_redo());
     /// assert_eq!(table.to_string(), "Hello, World!World!");
-    /// table.hot_redo();
+    /// assert!(table.hot_redo());
     /// assert_eq!(table.to_string(), "Hello, World!World!World!");
+    /// assert!(!table.hot_redo());
     /// ```
-    pub fn hot_redo(&mut self) {
-        let commit = match self.history.hot_redo() {
-            Some(commit) => commit,
-            None => return,
-        };
+    pub fn hot_redo(&mut self) -> bool {
+        if self.history.hot_redo().is_none() {
+            return false;
+        }
+
+        self.reapply_head();
+        true
+    }
+
+    /// Restores the Piece Table to the state *after* the last undo
+    ///
+    /// Follows the hot path like `PieceTable::hot_redo`, but also redoes if no hot path
+    /// was recorded for the current state by picking the newest edit made after it.
+    ///
+    /// Returns `false` if there was nothing to redo.
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("Hello");
+    /// table.append(", World!");
+    /// table.undo();
+    /// assert!(table.redo());
+    /// assert_eq!(table.to_string(), "Hello, World!");
+    /// assert!(!table.redo());
+    /// ```
+    pub fn redo(&mut self) -> bool {
+        if self.history.redo().is_none() {
+            return false;
+        }
+
+        self.reapply_head();
+        true
+    }
+
+    /// Redos the `branch`th edit made after the current state, counted from the oldest
+    ///
+    /// The branch becomes the hot path (see `PieceTable::hot_redo`).
+    /// Returns `false` if there is no such branch.
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("Hello");
+    /// table.append(", World!");
+    /// table.undo();
+    /// table.append(", Moon!");
+    /// table.undo();
+    ///
+    /// assert!(table.redo_branch(0));
+    /// assert_eq!(table.to_string(), "Hello, World!");
+    /// table.undo();
+    /// assert!(!table.redo_branch(2));
+    /// ```
+    pub fn redo_branch(&mut self, branch: usize) -> bool {
+        if self.history.redo_branch(branch).is_none() {
+            return false;
+        }
+
+        self.reapply_head();
+        true
+    }
+
+    /// Applies the commit at the history head after the head moved forward onto it
+    fn reapply_head(&mut self) {
+        let tracks_structure = self.tracks_structure();
+        let commit = &self.history.changes[self.history.head].commit;
+        self.last_edit = commit.edit;
+        self.last_edit_coalesced = false;
+
+        let changes = commit
+            .changes
+            .iter()
+            .map(|change| (change.typ, change.pos, change.piece));
+        let delta = tracks_structure.then(|| {
+            StructuralDelta::from_changes(changes.clone().map(|(typ, pos, _)| (typ, pos)))
+        });
+        let first_change = changes.clone().map(|(_, pos, _)| pos).min();
+        apply_changes(
+            &mut self.pieces,
+            &mut self.total_length,
+            (self.original.as_str().len(), self.addition.len()),
+            changes,
+        );
+        if let Some(delta) = delta {
+            self.notify_structural_change(&delta);
+        }
+        if let Some(first_change) = first_change {
+            self.invalidate_piece_index(first_change);
+        }
+        self.invalidate_caches();
+
+        self.debug_validate_length();
+    }
+
+    /// Creates a commit that is only tracked if the history is enabled or
+    /// structural changes are tracked
+    pub(crate) fn new_commit(&self) -> Commit {
+        if self.history_enabled || self.tracks_structure() {
+            Commit::new()
+        } else {
+            Commit::untracked()
+        }
+    }
+
+    /// Creates a commit for an edit that bypasses the history, only tracked if
+    /// structural changes are tracked
+    fn unrecorded_commit(&self) -> Commit {
+        if self.tracks_structure() {
+            Commit::new()
+        } else {
+            Commit::untracked()
+        }
+    }
+
+    /// Whether the changes of the piece list are needed, by the structural change
+    /// callback or the line index
+    fn tracks_structure(&self) -> bool {
+        self.structural_observer.is_some() || self.line_index.is_some()
+    }
+
+    /// Drops all data cached for the current text, called on every change of the text
+    pub(crate) fn invalidate_caches(&mut self) {
+        self.invalidate_slice_cache();
+    }
+
+    /// Finishes an edit by saving its commit to the history (if the history is enabled),
+    /// possibly merged into the latest undo step (see `PieceTable::set_commit_strategy`)
+    pub(crate) fn save_commit(&mut self, commit: Commit) {
+        self.finish_edit(commit, self.history_enabled);
+    }
+
+    /// Finishes an edit, saving its commit to the history only if `record` is set
+    fn finish_edit(&mut self, commit: Commit, record: bool) {
+        self.last_edit = commit.edit;
+        self.last_edit_coalesced = false;
+        self.invalidate_caches();
+        if self.tracks_structure() {
+            let changes = commit.changes.iter().map(|change| (change.typ, change.pos));
+            self.notify_structural_change(&StructuralDelta::from_changes(changes));
+        }
+        if record {
+            match commit.edit {
+                Some(edit) if self.coalesce_with_head(edit) => {
+                    self.history.amend(commit);
+                    self.last_edit_coalesced = true;
+                }
+                _ => self.history.save(commit),
+            }
+        } else {
+            self.history_epoch += 1;
+        }
+
+        self.debug_validate_length();
+    }
+
+    /// Sums the piece lengths, the value `total_length` must always equal
+    #[cfg(test)]
+    pub(crate) fn recompute_len(&self) -> usize {
+        self.pieces.iter().map(|piece| piece.length).sum()
+    }
+
+    /// Asserts that `total_length` matches the summed length of all pieces (debug builds only)
+    fn debug_validate_length(&self) {
+        debug_assert_eq!(
+            self.pieces.iter().map(|piece| piece.length).sum::<usize>(),
+            self.total_length,
+            "Total length is out of sync with the pieces"
+        );
+        self.debug_validate_piece_index();
+        self.debug_validate_line_index();
+    }
+
+    /// Returns the text in `range`, returning an error instead of panicking
+    ///
+    /// Besides the bounds of `range`, every contributing piece is verified to point
+    /// into its source buffer and to be cut at char boundaries, so corrupt
+    /// internal state is reported instead of panicking. An empty range returns an empty string.
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::{EditError, PieceTable};
+    ///
+    /// let table = PieceTable::from("café");
+    /// assert_eq!(table.checked_slice(0..3), Ok(String::from("caf")));
+    /// assert_eq!(
+    ///     table.checked_slice(0..4),
+    ///     Err(EditError::NotCharBoundary { byte: 4 })
+    /// );
+    /// assert_eq!(
+    ///     table.checked_slice(2..9),
+    ///     Err(EditError::RangeOutOfBounds { start: 2, end: 9, len: 5 })
+    /// );
+    /// ```
+    pub fn checked_slice(&self, range: Range<usize>) -> Result<String, EditError> {
+        if range.start > range.end || range.end > self.total_length {
+            return Err(EditError::RangeOutOfBounds {
+                start: range.start,
+                end: range.end,
+                len: self.total_length,
+            });
+        }
+
+        let mut out = String::new();
+        let (first, mut len) = self.piece_at(range.start);
+        for (index, piece) in self.pieces.range(first..).enumerate() {
+            let index = first + index;
+            if len >= range.end {
+                break;
+            }
+
+            let prev_len = len;
+            len += piece.length;
+            if len <= range.start {
+                continue;
+            }
+
+            let (source, piece_range) = self.piece_buffer(piece);
+            if piece_range.end > source.len() {
+                return Err(EditError::InvalidPiece { index });
+            }
+
+            let lower = range.start.max(prev_len);
+            let upper = range.end.min(len);
+            let start = piece_range.start + (lower - prev_len);
+            let end = piece_range.start + (upper - prev_len);
+            if source.is_char_boundary(start).not() {
+                return Err(EditError::NotCharBoundary { byte: lower });
+            }
+            if source.is_char_boundary(end).not() {
+                return Err(EditError::NotCharBoundary { byte: upper });
+            }
+
+            out.push_str(&source[start..end]);
+        }
+
+        Ok(out)
+    }
+
+    /// Returns the byte range affected by the most recent edit, undo or redo
+    ///
+    /// The delta has the shape incremental parsers (e.g. tree-sitter's `InputEdit`) expect.
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::{EditDelta, PieceTable};
+    ///
+    /// let mut table = PieceTable::from("HelloWorld!");
+    /// table.insert(5, ", ");
+    /// assert_eq!(
+    ///     table.last_edit(),
+    ///     Some(EditDelta { start_byte: 5, old_end_byte: 5, new_end_byte: 7 })
+    /// );
+    ///
+    /// table.undo();
+    /// assert_eq!(
+    ///     table.last_edit(),
+    ///     Some(EditDelta { start_byte: 5, old_end_byte: 7, new_end_byte: 5 })
+    /// );
+    /// ```
+    pub fn last_edit(&self) -> Option<EditDelta> {
+        self.last_edit
+    }
 
-        // FIXME: lots of duplication with undo
-        for change in &commit.changes {
-            match change.piece.source {
-                PieceSource::Original => assert!(
-                    change.piece.offset + change.piece.length <= self.original.len(),
-                    "Piece change is out of original text bounds"
-                ),
-                PieceSource::Addition => assert!(
-                    change.piece.offset + change.piece.length <= self.addition.len(),
-                    "Piece change is out of addition text bounds"
-                ),
+    /// Returns the text stored in the Piece Table for any kind of range
+    ///
+    /// All `PieceTableSlice` implementations delegate to this method.
+    ///
+    /// # Panic
+    /// Panics if (unless positions are clamped, see `PieceTable::set_bounds_policy`)
+    /// - Range is out of bounds
+    /// - Range is empty
+    /// - An inclusive or exclusive bound equals `usize::MAX`
+    ///
+    /// # Example
+    /// ```
+    /// use std::ops::Bound;
+    ///
+    /// use piece_table::PieceTable;
+    ///
+    /// let table = PieceTable::from("Hello, World!");
+    /// assert_eq!(table.range_slice(7..), "World!");
+    /// assert_eq!(table.range_slice((Bound::Excluded(6), Bound::Included(11))), "World");
+    /// ```
+    pub fn range_slice<R: RangeBounds<usize>>(&self, range: R) -> String {
+        let lower = match range.start_bound() {
+            Bound::Included(start) => *start,
+            Bound::Excluded(start) => {
+                assert!(
+                    *start != usize::MAX,
+                    "Lower exclusive index must not be usize::MAX"
+                );
+                start + 1
             }
+            Bound::Unbounded => 0,
+        };
+        let upper = match range.end_bound() {
+            Bound::Included(end) => {
+                assert!(
+                    *end != usize::MAX,
+                    "Upper inclusive index must not be usize::MAX"
+                );
+                end + 1
+            }
+            Bound::Excluded(end) => *end,
+            Bound::Unbounded => self.total_length,
+        };
+
+        if self.bounds_policy == BoundsPolicy::Clamp {
+            let upper = self.clamp_pos(upper);
+            return self._slice(lower.min(upper), upper);
+        }
+
+        self._slice(lower, upper)
+    }
+
+    /// Returns the text of several sorted, non-overlapping ranges concatenated in order
+    ///
+    /// The pieces are scanned once for all ranges.
+    ///
+    /// # Panic
+    /// Panics if a range is reversed, out of bounds, starts before the previous one ends
+    /// or a bound falls inside a character
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let table = PieceTable::from("fn main() {\n    body();\n}\n");
+    /// assert_eq!(table.slice_ranges(&[0..11, 24..26]), "fn main() {}\n");
+    /// ```
+    pub fn slice_ranges(&self, ranges: &[Range<usize>]) -> String {
+        let mut prev_end = 0;
+        for range in ranges {
+            assert!(
+                range.start <= range.end && range.end <= self.total_length,
+                "Range {}..{} out of bounds for length {}",
+                range.start,
+                range.end,
+                self.total_length
+            );
+            assert!(
+                prev_end <= range.start,
+                "Range {}..{} starts before the previous range ends at {prev_end}",
+                range.start,
+                range.end
+            );
+            self.assert_slice_boundaries(range.start, range.end);
+            prev_end = range.end;
+        }
 
-            match change.typ {
-                ChangeType::Deletion => {
-                    assert!(
-                        change.pos < self.pieces.len(),
-                        "Redo delete position is out of bounds"
-                    );
+        let mut out = String::with_capacity(ranges.iter().map(ExactSizeIterator::len).sum());
+        let (Some(first), Some(last)) = (ranges.first(), ranges.last()) else {
+            return out;
+        };
 
-                    let removed_piece = self.pieces.remove(change.pos);
-                    self.total_length -= removed_piece.length;
+        let mut ranges = ranges
+            .iter()
+            .filter(|range| range.is_empty().not())
+            .peekable();
+        for (info, _) in self.pieces_in(first.start..last.end) {
+            let piece_end = info.start + info.text.len();
+            while let Some(range) = ranges.peek() {
+                let lower = range.start.max(info.start);
+                let upper = range.end.min(piece_end);
+                if lower < upper {
+                    out.push_str(&info.text[lower - info.start..upper - info.start]);
                 }
-                ChangeType::Insertion => {
-                    assert!(
-                        change.pos <= self.pieces.len(),
-                        "Redo insert position is out of bounds"
-                    );
-
-                    self.pieces.insert(change.pos, change.piece);
-                    self.total_length += change.piece.length;
+                if range.end > piece_end {
+                    break;
                 }
+                ranges.next();
+            }
+        }
+
+        out
+    }
+
+    /// Returns a `Display`able view of the text with control characters escaped
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let table = PieceTable::from("Hello,\tWorld!\n");
+    /// assert_eq!(table.display_escaped().to_string(), "Hello,\\tWorld!\\n");
+    /// ```
+    pub fn display_escaped(&self) -> EscapedDisplay<'_> {
+        EscapedDisplay { table: self }
+    }
+
+    /// Writes the text in `range` piece by piece to `f`
+    ///
+    /// # Panic
+    /// Panics if `range` is reversed, reaches past `PieceTable::len` or a bound falls
+    /// inside a character
+    pub fn fmt_range(&self, f: &mut fmt::Formatter<'_>, range: Range<usize>) -> fmt::Result {
+        let pieces = self.pieces_in(range.clone());
+        self.assert_slice_boundaries(range.start, range.end);
+        for (info, range) in pieces {
+            f.write_str(&info.text[range])?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns a `Display`able view of the text in `range` that doesn't allocate
+    ///
+    /// # Panic
+    /// Panics when displayed if `range` is reversed, reaches past `PieceTable::len` or a
+    /// bound falls inside a character
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("HelloWorld!");
+    /// table.insert(5, ", ");
+    /// assert_eq!(format!("[{}]", table.display_range(3..9)), "[lo, Wo]");
+    /// ```
+    pub fn display_range(&self, range: Range<usize>) -> RangeDisplay<'_> {
+        RangeDisplay { table: self, range }
+    }
+
+    /// Returns the buffer `piece` points into and the byte range of the piece in it
+    ///
+    /// Inline pieces are a buffer of their own.
+    pub(crate) fn piece_buffer<'a>(&'a self, piece: &'a Piece) -> (&'a str, Range<usize>) {
+        let buffer = match piece.source {
+            PieceSource::Original => self.original.as_str(),
+            PieceSource::Addition => self.addition.as_str(),
+            PieceSource::Inline => {
+                let text = piece.inline_text().expect("Inline text is valid");
+                return (text, 0..piece.length);
+            }
+        };
+        (buffer, piece.offset..piece.offset + piece.length)
+    }
+
+    /// Returns the text a piece points at
+    pub(crate) fn piece_str<'a>(&'a self, piece: &'a Piece) -> &'a str {
+        let (buffer, range) = self.piece_buffer(piece);
+        &buffer[range]
+    }
+
+    /// Returns a piece holding the inserted `string` if inline pieces are enabled (see
+    /// `PieceTable::set_inline_pieces`) and it is short enough, `None` to store it in the
+    /// addition buffer
+    ///
+    /// `previous` is the index of the piece `string` is inserted behind. Text continuing the
+    /// latest addition is stored with it, so a typed run grows a single piece, and a run
+    /// filling its inline piece continues in the addition buffer.
+    pub(crate) fn store_inline(&mut self, string: &str, previous: Option<usize>) -> Option<Piece> {
+        if self.inline_pieces.not() || string.len() > INLINE_LEN {
+            return None;
+        }
+
+        let piece = Piece::inline(self.inline_offset, string);
+        if let Some(previous) = previous.map(|idx| &self.pieces[idx]) {
+            if self.ends_latest_addition(previous) && previous.is_continued_by(&piece).not() {
+                return None;
             }
         }
+
+        self.inline_offset += string.len();
+        Some(piece)
+    }
+
+    /// Whether `piece` ends with the text added last to its source, so that text added
+    /// next continues it
+    pub(crate) fn ends_latest_addition(&self, piece: &Piece) -> bool {
+        let end = piece.offset + piece.length;
+        match piece.source {
+            PieceSource::Original => false,
+            PieceSource::Addition => end == self.addition.len(),
+            PieceSource::Inline => end == self.inline_offset,
+        }
+    }
+
+    /// Stores the inserted `string` and returns a piece pointing at it
+    fn store_addition(&mut self, string: &str) -> Piece {
+        #[cfg(feature = "intern-additions")]
+        if string.len() <= INTERN_LEN {
+            let offset = match self.interned.get(string) {
+                Some(offset) => *offset,
+                None => {
+                    let offset = self.addition.len();
+                    self.addition.push_str(string);
+                    self.interned.insert(String::from(string), offset);
+                    offset
+                }
+            };
+            return Piece::new(PieceSource::Addition, offset, string.len());
+        }
+
+        let piece = Piece::new(PieceSource::Addition, self.addition.len(), string.len());
+        self.addition.push_str(string);
+        piece
+    }
+
+    /// Returns an iterator over all bytes of the text and their positions
+    pub(crate) fn byte_indices(&self) -> impl Iterator<Item = (usize, u8)> + '_ {
+        let mut offset = 0;
+        self.pieces.iter().flat_map(move |piece| {
+            let start = offset;
+            offset += piece.length;
+            self.piece_str(piece)
+                .bytes()
+                .enumerate()
+                .map(move |(i, byte)| (start + i, byte))
+        })
+    }
+
+    /// Returns an iterator over all chars of the text and their byte positions
+    pub(crate) fn char_indices(&self) -> impl Iterator<Item = (usize, char)> + '_ {
+        let mut offset = 0;
+        self.pieces.iter().flat_map(move |piece| {
+            let start = offset;
+            offset += piece.length;
+            self.piece_str(piece)
+                .char_indices()
+                .map(move |(i, c)| (start + i, c))
+        })
     }
 
-    /// Returns text stored in the Piece Table (`upper` is exclusive)
+    /// Returns text stored in the Piece Table (`upper` is exclusive),
+    /// an empty string if `lower == upper`
     pub(crate) fn _slice(&self, lower: usize, upper: usize) -> String {
         assert!(
-            lower < upper,
-            "Lower slice bound must be smaller than upper"
+            lower <= upper,
+            "Slice index starts at {lower} but ends at {upper}"
         );
         assert!(
             upper <= self.total_length,
-            "Slice bounds must be within the bounds of the text"
+            "Slice {lower}..{upper} out of bounds for length {}",
+            self.total_length
         );
+        self.assert_slice_boundaries(lower, upper);
+        if lower == upper {
+            return String::new();
+        }
+
+        let Some(cache) = &self.slice_cache else {
+            return self.assemble_slice(lower, upper);
+        };
+        let mut cache = cache.lock().expect("Slice cache lock is not poisoned");
+        if let Some(text) = cache.get(&(lower..upper)) {
+            return text;
+        }
+        let text = self.assemble_slice(lower, upper);
+        cache.insert(lower..upper, text.clone());
+        text
+    }
+
+    /// Asserts that the in-bounds slice `lower..upper` starts and ends on char boundaries
+    pub(crate) fn assert_slice_boundaries(&self, lower: usize, upper: usize) {
+        for pos in [lower, upper] {
+            assert!(
+                self.is_char_boundary(pos),
+                "Slice boundary {pos} falls inside a character"
+            );
+        }
+    }
+
+    /// Whether `piece` lies within its source buffer and starts and ends on char boundaries
+    fn piece_fits_buffer(&self, piece: &Piece) -> bool {
+        let buffer = match piece.source {
+            PieceSource::Original => self.original.as_str(),
+            PieceSource::Addition => self.addition.as_str(),
+            PieceSource::Inline => return piece.inline_text().is_some(),
+        };
+        let end = piece.offset + piece.length;
+        end <= buffer.len() && buffer.is_char_boundary(piece.offset) && buffer.is_char_boundary(end)
+    }
+
+    /// Whether the byte position `pos` of the text is a char boundary
+    ///
+    /// Positions past the end are not a char boundary.
+    fn is_char_boundary(&self, pos: usize) -> bool {
+        if pos == 0 || pos == self.total_length {
+            return true;
+        }
+
+        match self.piece_at(pos) {
+            (idx, start) if idx < self.pieces.len() => self
+                .piece_str(&self.pieces[idx])
+                .is_char_boundary(pos - start),
+            _ => false,
+        }
+    }
+
+    /// Collects the text between `lower` and `upper` from the pieces
+    fn assemble_slice(&self, lower: usize, upper: usize) -> String {
+        // Fast path for a freshly loaded or untouched text
+        if self.pieces.len() == 1 {
+            return String::from(&self.piece_str(&self.pieces[0])[lower..upper]);
+        }
 
         let mut out = String::new();
 
-        let mut len = 0;
-        for piece in &self.pieces {
+        let (first, mut len) = self.piece_at(lower);
+        for piece in self.pieces.range(first..) {
             if len >= upper {
                 break;
             }
@@ -400,10 +2051,7 @@ impl PieceTable {
             let prev_len = len;
             len += piece.length;
 
-            let source = match piece.source {
-                PieceSource::Original => &self.original,
-                PieceSource::Addition => &self.addition,
-            };
+            let (source, piece_range) = self.piece_buffer(piece);
 
             let capture_start = lower <= prev_len;
             let capture_end = upper >= len && len > lower;
@@ -411,19 +2059,17 @@ impl PieceTable {
 
             match (capture_start, capture_end) {
                 (false, true) => {
-                    let start = piece.offset + (lower - prev_len);
-                    let end = piece.offset + piece.length;
-                    out.push_str(&source[start..end])
+                    let start = piece_range.start + (lower - prev_len);
+                    out.push_str(&source[start..piece_range.end])
                 }
-                (true, true) => out.push_str(&source[piece.offset..piece.offset + piece.length]),
+                (true, true) => out.push_str(&source[piece_range]),
                 (true, false) => {
-                    let start = piece.offset;
-                    let end = piece.offset + (upper - prev_len);
-                    out.push_str(&source[start..end]);
+                    let end = piece_range.start + (upper - prev_len);
+                    out.push_str(&source[piece_range.start..end]);
                 }
                 _ if caputre_slice => {
-                    let start = piece.offset + (lower - prev_len);
-                    let end = piece.offset + (upper - prev_len);
+                    let start = piece_range.start + (lower - prev_len);
+                    let end = piece_range.start + (upper - prev_len);
                     out.push_str(&source[start..end]);
                 }
                 _ => continue,
@@ -433,3 +2079,75 @@ impl PieceTable {
         out
     }
 }
+
+/// Inserts (`ChangeType::Insertion`) or removes (`ChangeType::Deletion`) pieces
+/// at the given positions in order
+///
+/// Runs of insertions at ascending positions and of deletions at descending positions
+/// (e.g. reverting or redoing the removal of many whole pieces) touch a contiguous
+/// range of pieces and are applied at once instead of shifting the pieces for every change.
+fn apply_changes<I: Iterator<Item = (ChangeType, usize, Piece)>>(
+    pieces: &mut VecDeque<Piece>,
+    total_length: &mut usize,
+    (original_len, addition_len): (usize, usize),
+    changes: I,
+) {
+    let mut changes = changes
+        .inspect(|(_, _, piece)| match piece.source {
+            PieceSource::Original => assert!(
+                piece.offset + piece.length <= original_len,
+                "Piece change is out of original text bounds"
+            ),
+            PieceSource::Addition => assert!(
+                piece.offset + piece.length <= addition_len,
+                "Piece change is out of addition text bounds"
+            ),
+            PieceSource::Inline => assert!(
+                piece.length <= piece.inline.len(),
+                "Piece change is out of inline text bounds"
+            ),
+        })
+        .peekable();
+
+    while let Some((typ, pos, piece)) = changes.next() {
+        match typ {
+            ChangeType::Insertion => {
+                assert!(pos <= pieces.len(), "Insert position is out of bounds");
+
+                let mut run = vec![piece];
+                while let Some((ChangeType::Insertion, next_pos, next_piece)) = changes.peek() {
+                    if *next_pos != pos + run.len() {
+                        break;
+                    }
+                    run.push(*next_piece);
+                    changes.next();
+                }
+
+                *total_length += run.iter().map(|piece| piece.length).sum::<usize>();
+                if let [piece] = run[..] {
+                    pieces.insert(pos, piece);
+                } else {
+                    let mut trailing = pieces.split_off(pos);
+                    pieces.extend(run);
+                    pieces.append(&mut trailing);
+                }
+            }
+            ChangeType::Deletion => {
+                assert!(pos < pieces.len(), "Delete position is out of bounds");
+
+                let mut start = pos;
+                while let Some((ChangeType::Deletion, next_pos, _)) = changes.peek() {
+                    if *next_pos + 1 != start {
+                        break;
+                    }
+                    start = *next_pos;
+                    changes.next();
+                }
+
+                for removed_piece in pieces.drain(start..=pos) {
+                    *total_length -= removed_piece.length;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,220 @@
+use std::ops::Range;
+
+use super::{piece::PieceSource, PieceTable};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+/// Stable position in a Piece Table that survives edits elsewhere in the text
+///
+/// An anchor points at the byte *after* the position it was created for. Text
+/// inserted at the anchored position moves the anchor behind it, text removed
+/// in front of it moves it to the front. Removing the anchored byte itself
+/// invalidates the anchor.
+pub struct Anchor {
+    /// Source buffer and offset of the anchored byte, `None` anchors the end of the text
+    pub(crate) byte: Option<(PieceSource, usize)>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+/// Stable handle to a block of appended text (see `PieceTable::append_span`)
+///
+/// Tracks the first and last byte of the block with anchors, so text inserted
+/// inside the block becomes part of it while edits around it only move it.
+pub struct SpanId {
+    first: Anchor,
+    last: Anchor,
+}
+
+impl PieceTable {
+    /// Creates an anchor for the byte position `pos`
+    ///
+    /// # Panic
+    /// Panics if `pos > PieceTable::len`
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("Hello, World!");
+    /// let anchor = table.anchor(7);
+    /// table.insert(0, "Oh, ");
+    /// assert_eq!(table.anchor_pos(anchor), Some(11));
+    /// ```
+    pub fn anchor(&self, pos: usize) -> Anchor {
+        self.anchors_for(&[pos])[0]
+    }
+
+    /// Creates an anchor for every byte position in `positions` with a single scan
+    /// over the pieces
+    ///
+    /// The anchors are returned in the order of `positions`.
+    ///
+    /// # Panic
+    /// Panics if any position is greater than `PieceTable::len`
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("a b c");
+    /// let anchors = table.anchors_for(&[4, 0, 2]);
+    /// table.insert(1, "--");
+    /// let positions: Vec<_> = anchors.iter().map(|a| table.anchor_pos(*a)).collect();
+    /// assert_eq!(positions, vec![Some(6), Some(0), Some(4)]);
+    /// ```
+    pub fn anchors_for(&self, positions: &[usize]) -> Vec<Anchor> {
+        let mut order: Vec<usize> = (0..positions.len()).collect();
+        order.sort_unstable_by_key(|i| positions[*i]);
+
+        let mut anchors = vec![Anchor { byte: None }; positions.len()];
+        let mut pieces = self.pieces.iter();
+        let mut current = pieces.next();
+        let mut piece_start = 0;
+        for i in order {
+            let pos = positions[i];
+            assert!(
+                pos <= self.total_length,
+                "Anchor position {pos} out of bounds for length {}",
+                self.total_length
+            );
+
+            while let Some(piece) = current {
+                if pos < piece_start + piece.length {
+                    break;
+                }
+                piece_start += piece.length;
+                current = pieces.next();
+            }
+
+            if let Some(piece) = current {
+                let offset = piece.offset + (pos - piece_start);
+                anchors[i].byte = Some((piece.source, offset));
+            }
+        }
+
+        anchors
+    }
+
+    /// Returns the current byte position of `anchor`, or `None` if the anchored text was removed
+    pub fn anchor_pos(&self, anchor: Anchor) -> Option<usize> {
+        let Some((source, offset)) = anchor.byte else {
+            return Some(self.total_length);
+        };
+
+        let mut len = 0;
+        for piece in &self.pieces {
+            if piece.source == source
+                && piece.offset <= offset
+                && offset < piece.offset + piece.length
+            {
+                return Some(len + offset - piece.offset);
+            }
+            len += piece.length;
+        }
+
+        None
+    }
+
+    /// Replaces the text in `range` with `string` like `PieceTable::replace_range`, moving
+    /// the `anchors` inside `range` to the start of the new text
+    ///
+    /// `PieceTable::replace_range` invalidates anchors inside the replaced text, which loses
+    /// e.g. cursors inside a replaced selection. Anchors outside of `range` and anchors
+    /// that are already invalid are left as they are, as are all anchors if `string` equals
+    /// the text in `range`.
+    ///
+    /// # Panic
+    /// Panics like `PieceTable::replace_range`
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("Hello, World!");
+    /// let mut anchors = [table.anchor(9), table.anchor(12)];
+    /// table.replace_range_keep_anchors(7..12, "Rust", &mut anchors);
+    /// assert_eq!(table.to_string(), "Hello, Rust!");
+    /// assert_eq!(table.anchor_pos(anchors[0]), Some(7));
+    /// assert_eq!(table.anchor_pos(anchors[1]), Some(11));
+    /// ```
+    pub fn replace_range_keep_anchors<S: AsRef<str>>(
+        &mut self,
+        range: Range<usize>,
+        string: S,
+        anchors: &mut [Anchor],
+    ) {
+        let string = string.as_ref();
+        self.assert_replace_range(&range);
+
+        // Replacing text with itself leaves the anchors valid where they are
+        if self.range_eq(range.clone(), string) {
+            return;
+        }
+
+        let inside: Vec<bool> = anchors
+            .iter()
+            .map(|anchor| {
+                self.anchor_pos(*anchor)
+                    .is_some_and(|pos| range.contains(&pos))
+            })
+            .collect();
+
+        let start = range.start;
+        self.replace_range(range, string);
+
+        let start_anchor = self.anchor(start);
+        for (anchor, inside) in anchors.iter_mut().zip(inside) {
+            if inside {
+                *anchor = start_anchor;
+            }
+        }
+    }
+
+    /// Appends `text` and returns a handle to it that stays valid across later edits
+    ///
+    /// # Panic
+    /// Panics like `PieceTable::append`
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("> ");
+    /// let output = table.append_span("1 + 1 = 2\n");
+    /// table.append("> ");
+    /// table.insert(0, "REPL\n");
+    ///
+    /// assert_eq!(table.span_range(output), Some(7..17));
+    /// assert!(table.remove_span(output));
+    /// assert_eq!(table.to_string(), "REPL\n> > ");
+    /// ```
+    pub fn append_span<S: AsRef<str>>(&mut self, text: S) -> SpanId {
+        let start = self.total_length;
+        self.append(text);
+
+        let anchors = self.anchors_for(&[start, self.total_length - 1]);
+        SpanId {
+            first: anchors[0],
+            last: anchors[1],
+        }
+    }
+
+    /// Returns the current byte range of `span`, or `None` if its first or last byte was removed
+    pub fn span_range(&self, span: SpanId) -> Option<Range<usize>> {
+        let start = self.anchor_pos(span.first)?;
+        let end = self.anchor_pos(span.last)? + 1;
+        (start < end).then_some(start..end)
+    }
+
+    /// Removes the text of `span` as a single edit
+    ///
+    /// Returns `false` and leaves the text untouched if the span can't be resolved
+    /// (see `PieceTable::span_range`).
+    pub fn remove_span(&mut self, span: SpanId) -> bool {
+        let Some(range) = self.span_range(span) else {
+            return false;
+        };
+
+        self.remove(range.start, range.len());
+        true
+    }
+}
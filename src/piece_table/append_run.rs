@@ -0,0 +1,129 @@
+use std::ops::Not;
+
+use crate::history::change::ChangeType;
+
+use super::{
+    edit_delta::EditDelta,
+    piece::{Piece, PieceSource},
+    structural_delta::StructuralDelta,
+    PieceTable,
+};
+
+impl PieceTable {
+    /// Records consecutive appends as a single growing undo step of at most `max_run` appends
+    ///
+    /// Instead of a new history entry and piece per append, the appended text extends the
+    /// last piece and the commit at the history head. `None` records every append separately
+    /// (the default), `Some(usize::MAX)` undoes a whole run of appends at once.
+    /// Appends are only merged into an undo step that holds nothing but appends.
+    ///
+    /// # Panic
+    /// Panics if `max_run` is `Some(0)`
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("Log:\n");
+    /// table.set_append_runs(Some(2));
+    /// for line in ["a\n", "b\n", "c\n"] {
+    ///     table.append(line);
+    /// }
+    /// assert_eq!(table.history_stats().total_entries, 3);
+    ///
+    /// table.undo();
+    /// assert_eq!(table.to_string(), "Log:\na\nb\n");
+    /// table.undo();
+    /// assert_eq!(table.to_string(), "Log:\n");
+    /// ```
+    pub fn set_append_runs(&mut self, max_run: Option<usize>) {
+        assert!(
+            max_run != Some(0),
+            "Append runs must hold at least one append"
+        );
+        self.max_append_run = max_run;
+    }
+
+    /// Appends `string` by extending the append run at the history head
+    ///
+    /// Returns `false` without changing anything if there is no run to extend.
+    pub(crate) fn extend_append_run(&mut self, pos: usize, string: &str) -> bool {
+        let Some(max_run) = self.max_append_run else {
+            return false;
+        };
+        if pos != self.total_length || self.history_enabled.not() || self.history.can_amend().not()
+        {
+            return false;
+        }
+        let Some(&last) = self.pieces.back() else {
+            return false;
+        };
+        if self.ends_latest_addition(&last).not() {
+            return false;
+        }
+
+        // The head commit must be a run, i.e. a single append or appends merged into its last piece
+        let idx = self.pieces.len() - 1;
+        let head = &self.history.changes[self.history.head].commit;
+        let run = match head.changes.last() {
+            Some(change)
+                if matches!(change.typ, ChangeType::Insertion)
+                    && change.pos == idx
+                    && change.piece.source == last.source
+                    && change.piece.offset == last.offset
+                    && change.piece.length == last.length =>
+            {
+                let appended = head.edit.is_some_and(|edit| {
+                    edit.start_byte == edit.old_end_byte && edit.new_end_byte == self.total_length
+                });
+                match head.changes.len() {
+                    1 => head.appends.max(1),
+                    // A single append that extended the last piece (see `PieceTable::insert`)
+                    2 if appended && head.changes[0].pos == idx => head.appends.max(1),
+                    _ => head.appends,
+                }
+            }
+            _ => 0,
+        };
+        if run == 0 || run >= max_run {
+            return false;
+        }
+
+        let piece = match self.store_inline(string, Some(idx)) {
+            Some(piece) => piece,
+            None => {
+                let piece = Piece::new(PieceSource::Addition, self.addition.len(), string.len());
+                self.addition.push_str(string);
+                piece
+            }
+        };
+        self.total_length += string.len();
+
+        let edit = EditDelta::insertion(pos, string.len());
+        let head = &mut self.history.changes[self.history.head].commit;
+        let changes = if last.is_continued_by(&piece) {
+            let extended = last.joined(&piece);
+            self.pieces[idx] = extended;
+            if let Some(change) = head.changes.last_mut() {
+                change.piece = extended;
+            }
+            vec![(ChangeType::Deletion, idx), (ChangeType::Insertion, idx)]
+        } else {
+            // A full inline piece is followed by a piece in the addition buffer
+            self.pieces.push_back(piece);
+            head.add_change(idx + 1, piece, ChangeType::Insertion);
+            vec![(ChangeType::Insertion, idx + 1)]
+        };
+        head.add_edit(edit);
+        head.appends = run + 1;
+        self.history.changes[self.history.head].amends += 1;
+
+        self.last_edit = Some(edit);
+        self.last_edit_coalesced = true;
+        self.invalidate_caches();
+        self.notify_structural_change(&StructuralDelta::from_changes(changes.into_iter()));
+        self.debug_validate_length();
+
+        true
+    }
+}
@@ -0,0 +1,9 @@
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+/// How out-of-range positions passed to `insert`, `remove` and slicing are handled
+pub enum BoundsPolicy {
+    /// Panic on out-of-range positions
+    #[default]
+    Panic,
+    /// Saturate positions to `0..=PieceTable::len` and shorten over-long removals
+    Clamp,
+}
@@ -0,0 +1,68 @@
+use std::sync::Mutex;
+
+use super::PieceTable;
+
+/// Copies the text, the pieces and the history, edits of the clone don't affect the
+/// original and vice versa
+///
+/// The read only original text is shared. Callbacks can't be copied, the clone has
+/// neither the structural observer (see `PieceTable::on_structural_change`) nor the
+/// commit strategy (see `PieceTable::set_commit_strategy`) of the original.
+///
+/// # Example
+/// ```
+/// use piece_table::PieceTable;
+///
+/// let mut table = PieceTable::from("Hello");
+/// table.append(", World!");
+///
+/// let mut snapshot = table.clone();
+/// snapshot.remove(5, 8);
+/// assert_eq!(snapshot.to_string(), "Hello");
+/// assert_eq!(table.to_string(), "Hello, World!");
+///
+/// snapshot.undo();
+/// snapshot.undo();
+/// assert_eq!(snapshot.to_string(), "Hello");
+/// ```
+impl Clone for PieceTable {
+    fn clone(&self) -> Self {
+        PieceTable {
+            original: self.original.clone(),
+            addition: self.addition.clone(),
+            pieces: self.pieces.clone(),
+            total_length: self.total_length,
+            history: self.history.clone(),
+            history_enabled: self.history_enabled,
+            history_epoch: self.history_epoch,
+            last_edit: self.last_edit,
+            last_edit_coalesced: self.last_edit_coalesced,
+            max_len: self.max_len,
+            bounds_policy: self.bounds_policy,
+            structural_observer: None,
+            commit_strategy: None,
+            max_append_run: self.max_append_run,
+            line_ending: self.line_ending,
+            slice_cache: self.slice_cache.as_ref().map(|cache| {
+                Mutex::new(
+                    cache
+                        .lock()
+                        .expect("Slice cache lock is not poisoned")
+                        .clone(),
+                )
+            }),
+            line_index: self.line_index.clone(),
+            piece_index: Mutex::new(
+                self.piece_index
+                    .lock()
+                    .expect("Piece index lock is not poisoned")
+                    .clone(),
+            ),
+            incomplete_write: self.incomplete_write.clone(),
+            #[cfg(feature = "intern-additions")]
+            interned: self.interned.clone(),
+            inline_pieces: self.inline_pieces,
+            inline_offset: self.inline_offset,
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use std::{borrow::Cow, ops::Not};
+
+use super::{edit_delta::EditDelta, PieceTable};
+
+/// Decides whether an edit is merged into the previous undo step or starts a new one
+pub trait CommitStrategy: Send + Sync {
+    /// Returns whether `edit` is merged into the undo step whose combined range is `previous`
+    ///
+    /// `inserted` is the text inserted by `edit` (empty for removals).
+    fn coalesce(&mut self, previous: &EditDelta, edit: &EditDelta, inserted: &str) -> bool;
+}
+
+/// Every edit is its own undo step (the default)
+pub struct PerEdit;
+
+/// Typing and deleting within a word is a single undo step, whitespace starts a new one
+pub struct PerWord;
+
+/// Typing and deleting within a line is a single undo step, a newline starts a new one
+pub struct PerLine;
+
+/// Whether `edit` inserts text right behind the text inserted by `previous`
+fn continues_insertion(previous: &EditDelta, edit: &EditDelta) -> bool {
+    let is_insertion = |delta: &EditDelta| delta.start_byte == delta.old_end_byte;
+    is_insertion(previous) && is_insertion(edit) && edit.start_byte == previous.new_end_byte
+}
+
+/// Whether `edit` removes text right in front of (backspace) or behind (delete) the
+/// text removed by `previous`
+fn continues_removal(previous: &EditDelta, edit: &EditDelta) -> bool {
+    let is_removal = |delta: &EditDelta| delta.start_byte == delta.new_end_byte;
+    is_removal(previous)
+        && is_removal(edit)
+        && (edit.old_end_byte == previous.start_byte || edit.start_byte == previous.start_byte)
+}
+
+impl CommitStrategy for PerEdit {
+    fn coalesce(&mut self, _previous: &EditDelta, _edit: &EditDelta, _inserted: &str) -> bool {
+        false
+    }
+}
+
+impl CommitStrategy for PerWord {
+    fn coalesce(&mut self, previous: &EditDelta, edit: &EditDelta, inserted: &str) -> bool {
+        (continues_insertion(previous, edit) && inserted.chars().all(|c| !c.is_whitespace()))
+            || continues_removal(previous, edit)
+    }
+}
+
+impl CommitStrategy for PerLine {
+    fn coalesce(&mut self, previous: &EditDelta, edit: &EditDelta, inserted: &str) -> bool {
+        (continues_insertion(previous, edit) && !inserted.contains('\n'))
+            || continues_removal(previous, edit)
+    }
+}
+
+impl PieceTable {
+    /// Sets the strategy deciding how edits are grouped into undo steps
+    ///
+    /// Edits are only merged into the latest undo step, never into one that was undone
+    /// or that other undo branches continue from. Composite edits (e.g.
+    /// `PieceTable::replace_range`) are merged like single edits.
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::{PerWord, PieceTable};
+    ///
+    /// let mut table = PieceTable::from("");
+    /// table.set_commit_strategy(Box::new(PerWord));
+    /// for (pos, c) in "Hello World".chars().enumerate() {
+    ///     table.insert(pos, c.to_string());
+    /// }
+    ///
+    /// table.undo();
+    /// assert_eq!(table.to_string(), "Hello");
+    /// table.undo();
+    /// assert_eq!(table.to_string(), "");
+    /// ```
+    pub fn set_commit_strategy(&mut self, strategy: Box<dyn CommitStrategy>) {
+        self.commit_strategy = Some(strategy);
+    }
+
+    /// Returns whether the last edit was merged into the previous undo step
+    /// instead of starting a new one (see `PieceTable::set_commit_strategy`)
+    ///
+    /// Undoing and redoing reset it to `false`.
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::{PerWord, PieceTable};
+    ///
+    /// let mut table = PieceTable::from("");
+    /// table.set_commit_strategy(Box::new(PerWord));
+    /// table.append("H");
+    /// assert!(!table.last_edit_coalesced());
+    /// table.append("i");
+    /// assert!(table.last_edit_coalesced());
+    /// table.append(" ");
+    /// assert!(!table.last_edit_coalesced());
+    /// ```
+    pub fn last_edit_coalesced(&self) -> bool {
+        self.last_edit_coalesced
+    }
+
+    /// Asks the commit strategy whether `edit` is merged into the undo step at the head
+    pub(crate) fn coalesce_with_head(&mut self, edit: EditDelta) -> bool {
+        if self.commit_strategy.is_none() || self.history.can_amend().not() {
+            return false;
+        }
+        let Some(previous) = self.history.changes[self.history.head].commit.edit else {
+            return false;
+        };
+        let Some(mut strategy) = self.commit_strategy.take() else {
+            return false;
+        };
+
+        let coalesce = strategy.coalesce(&previous, &edit, &self.inserted_text(edit));
+        self.commit_strategy = Some(strategy);
+        coalesce
+    }
+
+    /// Returns the text inserted by `edit`, borrowed from its piece unless it spans
+    /// several pieces
+    fn inserted_text(&self, edit: EditDelta) -> Cow<'_, str> {
+        let range = edit.start_byte..edit.new_end_byte;
+        if range.is_empty() {
+            return Cow::Borrowed("");
+        }
+
+        let (idx, start) = self.piece_at(range.start);
+        match self.pieces.get(idx) {
+            Some(piece) if range.end <= start + piece.length => {
+                Cow::Borrowed(&self.piece_str(piece)[range.start - start..range.end - start])
+            }
+            _ => Cow::Owned(self._slice(range.start, range.end)),
+        }
+    }
+}
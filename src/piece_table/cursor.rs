@@ -0,0 +1,81 @@
+use super::{
+    anchor::Anchor,
+    position::{BytePos, CharPos},
+    PieceTable,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+/// Insertion point that follows the text it is placed in (see `PieceTable::cursor`)
+///
+/// The cursor is anchored to the char after it, so edits elsewhere in the text move it
+/// along. If that char is removed by another edit, the cursor falls back to its last
+/// known position (clamped to the text).
+pub struct Cursor {
+    anchor: Anchor,
+    last_pos: usize,
+}
+
+impl PieceTable {
+    /// Creates a cursor at the byte position `pos`
+    ///
+    /// # Panic
+    /// Panics if `pos > PieceTable::len`
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("Hello!");
+    /// let mut cursor = table.cursor(5);
+    /// cursor.insert(&mut table, ", World");
+    /// table.insert(0, ">> ");
+    /// cursor.delete_back(&mut table, 5);
+    /// assert_eq!(table.to_string(), ">> Hello, !");
+    /// assert_eq!(cursor.pos(&table), 10);
+    /// ```
+    pub fn cursor(&self, pos: usize) -> Cursor {
+        Cursor {
+            anchor: self.anchor(pos),
+            last_pos: pos,
+        }
+    }
+}
+
+impl Cursor {
+    /// Returns the current byte position of the cursor in `table`
+    pub fn pos(&self, table: &PieceTable) -> usize {
+        table
+            .anchor_pos(self.anchor)
+            .unwrap_or(self.last_pos.min(table.len()))
+    }
+
+    /// Inserts `text` at the cursor and moves the cursor behind it
+    ///
+    /// # Panic
+    /// Panics like `PieceTable::insert`
+    pub fn insert<S: AsRef<str>>(&mut self, table: &mut PieceTable, text: S) {
+        let text = text.as_ref();
+        let pos = self.pos(table);
+        table.insert(pos, text);
+        self.move_to(table, pos + text.len());
+    }
+
+    /// Removes up to `n` chars in front of the cursor, stopping at the start of the text
+    pub fn delete_back(&mut self, table: &mut PieceTable, n: usize) {
+        let pos = self.pos(table);
+        let chars = table.to_char_pos(BytePos(pos)).0;
+        let start = table.to_byte_pos(CharPos(chars.saturating_sub(n))).0;
+        if start < pos {
+            table.remove(start, pos - start);
+        }
+        self.move_to(table, start);
+    }
+
+    /// Re-anchors the cursor at the byte position `pos`
+    ///
+    /// # Panic
+    /// Panics if `pos > PieceTable::len`
+    pub fn move_to(&mut self, table: &PieceTable, pos: usize) {
+        *self = table.cursor(pos);
+    }
+}
@@ -0,0 +1,134 @@
+use super::PieceTable;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// Line level change between two texts (see `PieceTable::diff_lines`)
+pub enum LineDiff {
+    /// Line of the other text (with its index there) that is missing in this text
+    Added(usize, String),
+    /// Line of this text (with its index here) that is missing in the other text
+    Removed(usize, String),
+    /// Line present in both texts
+    Unchanged,
+}
+
+impl PieceTable {
+    /// Returns the line changes turning this text into `other`, based on the
+    /// longest common subsequence of their lines
+    ///
+    /// Uses memory linear in the number of lines, the running time grows with the
+    /// product of the line counts outside the common prefix and suffix.
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::{LineDiff, PieceTable};
+    ///
+    /// let old = PieceTable::from("a\nb\nc");
+    /// let new = PieceTable::from("a\nB\nc\nd");
+    /// assert_eq!(
+    ///     old.diff_lines(&new),
+    ///     vec![
+    ///         LineDiff::Unchanged,
+    ///         LineDiff::Removed(1, String::from("b")),
+    ///         LineDiff::Added(1, String::from("B")),
+    ///         LineDiff::Unchanged,
+    ///         LineDiff::Added(3, String::from("d")),
+    ///     ]
+    /// );
+    /// ```
+    pub fn diff_lines(&self, other: &PieceTable) -> Vec<LineDiff> {
+        let mut old = Vec::new();
+        let mut new = Vec::new();
+        self.collect_lines_into(&mut old);
+        other.collect_lines_into(&mut new);
+
+        let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+        let suffix = old[prefix..]
+            .iter()
+            .rev()
+            .zip(new[prefix..].iter().rev())
+            .take_while(|(a, b)| a == b)
+            .count();
+
+        let mut diff = vec![LineDiff::Unchanged; prefix];
+        diff_range(
+            &old[prefix..old.len() - suffix],
+            prefix,
+            &new[prefix..new.len() - suffix],
+            prefix,
+            &mut diff,
+        );
+        diff.extend(std::iter::repeat_n(LineDiff::Unchanged, suffix));
+
+        diff
+    }
+}
+
+/// Appends the diff of `old` and `new` (starting at the line indices `old_start` and
+/// `new_start`) to `out`, splitting the problem in halves (Hirschberg's algorithm)
+fn diff_range(
+    old: &[String],
+    old_start: usize,
+    new: &[String],
+    new_start: usize,
+    out: &mut Vec<LineDiff>,
+) {
+    if old.is_empty() || new.is_empty() {
+        let removed = old.iter().enumerate();
+        out.extend(removed.map(|(i, line)| LineDiff::Removed(old_start + i, line.clone())));
+        let added = new.iter().enumerate();
+        out.extend(added.map(|(i, line)| LineDiff::Added(new_start + i, line.clone())));
+        return;
+    }
+
+    if old.len() == 1 {
+        let Some(same) = new.iter().position(|line| *line == old[0]) else {
+            diff_range(old, old_start, &[], new_start, out);
+            diff_range(&[], old_start, new, new_start, out);
+            return;
+        };
+
+        diff_range(&[], old_start, &new[..same], new_start, out);
+        out.push(LineDiff::Unchanged);
+        diff_range(&[], old_start, &new[same + 1..], new_start + same + 1, out);
+        return;
+    }
+
+    let mid = old.len() / 2;
+    let forward = lcs_lengths(old[..mid].iter(), new.iter());
+    let backward = lcs_lengths(old[mid..].iter().rev(), new.iter().rev());
+    let split = (0..=new.len())
+        .max_by_key(|k| (forward[*k] + backward[new.len() - k], usize::MAX - k))
+        .expect("Split candidates are not empty");
+
+    diff_range(&old[..mid], old_start, &new[..split], new_start, out);
+    diff_range(
+        &old[mid..],
+        old_start + mid,
+        &new[split..],
+        new_start + split,
+        out,
+    );
+}
+
+/// Returns the lengths of the longest common subsequences of `old` and every prefix of `new`
+fn lcs_lengths<'a, I, J>(old: I, new: J) -> Vec<usize>
+where
+    I: Iterator<Item = &'a String>,
+    J: Iterator<Item = &'a String> + Clone,
+{
+    let mut row = vec![0; new.clone().count() + 1];
+    for old_line in old {
+        let mut diagonal = 0;
+        for (j, new_line) in new.clone().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if old_line == new_line {
+                diagonal + 1
+            } else {
+                above.max(row[j])
+            };
+            diagonal = above;
+        }
+    }
+
+    row
+}
@@ -1,13 +1,93 @@
-use std::fmt::Display;
+use std::{
+    fmt::{Debug, Display, Write},
+    ops::Range,
+};
 
-use super::PieceTable;
+use super::{piece::Piece, PieceTable};
 
 impl Display for PieceTable {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.total_length == 0 {
-            write!(f, "")
-        } else {
-            write!(f, "{}", self._slice(0, self.total_length))
+        self.fmt_range(f, 0..self.total_length)
+    }
+}
+
+/// Lists the pieces with the text they point at, piece by piece without assembling the text
+impl Debug for PieceTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PieceTable")
+            .field("len", &self.total_length)
+            .field("piece_count", &self.pieces.len())
+            .field("pieces", &PiecesDebug { table: self })
+            .finish()
+    }
+}
+
+/// Lists the pieces of a Piece Table
+struct PiecesDebug<'a> {
+    table: &'a PieceTable,
+}
+
+impl Debug for PiecesDebug<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.table.pieces.iter().map(|piece| PieceDebug {
+                piece,
+                text: self.table.piece_str(piece),
+            }))
+            .finish()
+    }
+}
+
+/// Shows a piece together with the text it points at
+struct PieceDebug<'a> {
+    piece: &'a Piece,
+    text: &'a str,
+}
+
+impl Debug for PieceDebug<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Piece")
+            .field("source", &self.piece.source)
+            .field("offset", &self.piece.offset)
+            .field("length", &self.piece.length)
+            .field("text", &self.text)
+            .finish()
+    }
+}
+
+/// Displays the text of a Piece Table with control characters escaped
+///
+/// Created by `PieceTable::display_escaped`
+pub struct EscapedDisplay<'a> {
+    pub(crate) table: &'a PieceTable,
+}
+
+impl Display for EscapedDisplay<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for piece in &self.table.pieces {
+            for c in self.table.piece_str(piece).chars() {
+                if c.is_control() {
+                    write!(f, "{}", c.escape_default())?;
+                } else {
+                    f.write_char(c)?;
+                }
+            }
         }
+
+        Ok(())
+    }
+}
+
+/// Displays a range of the text of a Piece Table without assembling it first
+///
+/// Created by `PieceTable::display_range`
+pub struct RangeDisplay<'a> {
+    pub(crate) table: &'a PieceTable,
+    pub(crate) range: Range<usize>,
+}
+
+impl Display for RangeDisplay<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.table.fmt_range(f, self.range.clone())
     }
 }
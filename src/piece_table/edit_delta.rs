@@ -0,0 +1,62 @@
+use std::cmp::{max, min};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+/// Byte range affected by an edit of the Piece Table
+///
+/// Mirrors the shape of tree-sitter's `InputEdit` (without the row/column points).
+/// All positions are byte offsets, `start_byte..old_end_byte` is the range
+/// before the edit and `start_byte..new_end_byte` the range after it.
+pub struct EditDelta {
+    pub start_byte: usize,
+    pub old_end_byte: usize,
+    pub new_end_byte: usize,
+}
+
+impl EditDelta {
+    pub(crate) fn new(start_byte: usize, old_end_byte: usize, new_end_byte: usize) -> Self {
+        EditDelta {
+            start_byte,
+            old_end_byte,
+            new_end_byte,
+        }
+    }
+
+    /// Delta of inserting `len` bytes at `pos`
+    pub(crate) fn insertion(pos: usize, len: usize) -> Self {
+        EditDelta::new(pos, pos, pos + len)
+    }
+
+    /// Delta of removing `len` bytes from `pos`
+    pub(crate) fn removal(pos: usize, len: usize) -> Self {
+        EditDelta::new(pos, pos + len, pos)
+    }
+
+    /// Delta that reverts this delta
+    pub(crate) fn inverse(self) -> Self {
+        EditDelta::new(self.start_byte, self.new_end_byte, self.old_end_byte)
+    }
+
+    /// Combines this delta with a `next` delta that was applied after it
+    /// into one delta covering both edits
+    pub(crate) fn then(self, next: EditDelta) -> Self {
+        // Maps a position after this edit to the position before it
+        let map_back = |pos: usize| {
+            if pos <= self.start_byte {
+                pos
+            } else if pos >= self.new_end_byte {
+                pos - self.new_end_byte + self.old_end_byte
+            } else {
+                self.old_end_byte
+            }
+        };
+
+        let start_byte = min(self.start_byte, next.start_byte);
+        let old_end_byte = max(self.old_end_byte, map_back(next.old_end_byte));
+        // Everything behind the old end is shifted by both edits
+        let new_end_byte = old_end_byte + self.new_end_byte + next.new_end_byte
+            - self.old_end_byte
+            - next.old_end_byte;
+
+        EditDelta::new(start_byte, old_end_byte, new_end_byte)
+    }
+}
@@ -0,0 +1,65 @@
+use std::fmt::Display;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+/// Reasons an edit of the Piece Table can be rejected
+pub enum EditError {
+    /// Position is past the end of the text
+    PositionOutOfBounds { pos: usize, len: usize },
+    /// Inserted string is empty
+    EmptyString,
+    /// Edit would grow the text beyond the configured maximum length
+    TooLarge { len: usize, max_len: usize },
+    /// Range is reversed or reaches past the end of the text
+    RangeOutOfBounds {
+        start: usize,
+        end: usize,
+        len: usize,
+    },
+    /// Removed range is empty
+    EmptyRange,
+    /// Position falls inside a multi-byte character
+    NotCharBoundary { byte: usize },
+    /// Piece points outside of its source buffer or splits a character
+    InvalidPiece { index: usize },
+    /// Cached text length differs from the summed piece lengths
+    LengthMismatch { len: usize, actual: usize },
+    /// History entries are not linked into a consistent tree
+    InvalidHistory,
+}
+
+impl Display for EditError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EditError::PositionOutOfBounds { pos, len } => {
+                write!(f, "Insert position {pos} out of bounds for length {len}")
+            }
+            EditError::EmptyString => write!(f, "Inserted string must not be empty"),
+            EditError::TooLarge { len, max_len } => write!(
+                f,
+                "Edit would grow the text to {len} bytes, exceeding the maximum of {max_len}"
+            ),
+            EditError::RangeOutOfBounds { start, end, len } => {
+                write!(f, "Range {start}..{end} out of bounds for length {len}")
+            }
+            EditError::EmptyRange => write!(f, "Removed range must not be empty"),
+            EditError::NotCharBoundary { byte } => {
+                write!(f, "Byte {byte} is not a char boundary")
+            }
+            EditError::InvalidPiece { index } => {
+                write!(
+                    f,
+                    "Piece {index} points outside of its source buffer or splits a character"
+                )
+            }
+            EditError::LengthMismatch { len, actual } => {
+                write!(
+                    f,
+                    "Length {len} differs from the piece lengths summing to {actual}"
+                )
+            }
+            EditError::InvalidHistory => write!(f, "History is corrupt"),
+        }
+    }
+}
+
+impl std::error::Error for EditError {}
@@ -0,0 +1,38 @@
+use std::ops::Not;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::PieceTable;
+
+impl PieceTable {
+    /// Returns the number of extended grapheme clusters (user-perceived characters)
+    /// in the text
+    ///
+    /// The pieces are segmented as one continuous text, so a cluster split across pieces
+    /// (e.g. an emoji sequence edited together) counts once.
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("cafe");
+    /// table.append("\u{301}!");
+    /// assert_eq!(table.char_len(), 6);
+    /// assert_eq!(table.grapheme_count(), 5);
+    /// ```
+    pub fn grapheme_count(&self) -> usize {
+        // The last cluster of a piece may continue in the next one, so it is segmented
+        // again together with the next piece. Segmenting can restart at any boundary.
+        let mut count = 0;
+        let mut text = String::new();
+        for chunk in self.chunks() {
+            text.push_str(chunk);
+            let mut graphemes = text.grapheme_indices(true);
+            let last_start = graphemes.next_back().map_or(0, |(start, _)| start);
+            count += graphemes.count();
+            text.drain(..last_start);
+        }
+
+        count + usize::from(text.is_empty().not())
+    }
+}
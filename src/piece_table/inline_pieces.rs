@@ -0,0 +1,31 @@
+use super::PieceTable;
+
+impl PieceTable {
+    /// Enables or disables storing insertions of up to 8 bytes in their piece instead of
+    /// the addition buffer
+    ///
+    /// Meant for typing heavy workloads made of many tiny insertions, their text is read
+    /// from the piece itself without a detour through the addition buffer, which then only
+    /// grows by longer insertions. Typing continues the inline piece of the previous
+    /// keystroke, a run longer than 8 bytes continues in the addition buffer. Insertions
+    /// made before a change keep where they are stored. Disabled by default.
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::{PieceSource, PieceTable};
+    ///
+    /// let mut table = PieceTable::from("fn main() {}");
+    /// table.set_inline_pieces(true);
+    /// for (i, c) in "x += 1;".char_indices() {
+    ///     table.insert(11 + i, c.to_string());
+    /// }
+    /// assert_eq!(table.to_string(), "fn main() {x += 1;}");
+    /// assert_eq!(table.addition_text(), "");
+    ///
+    /// let (piece, _) = table.pieces_in(11..18).next().unwrap();
+    /// assert_eq!((piece.source, piece.text), (PieceSource::Inline, "x += 1;"));
+    /// ```
+    pub fn set_inline_pieces(&mut self, enabled: bool) {
+        self.inline_pieces = enabled;
+    }
+}
@@ -0,0 +1,102 @@
+use super::PieceTable;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// Edit that reverts an insertion or removal, for undo stacks kept outside the Piece Table
+pub enum InverseEdit {
+    /// Remove `len` bytes at `pos`
+    Remove { pos: usize, len: usize },
+    /// Insert `text` at `pos`
+    Insert { pos: usize, text: String },
+}
+
+impl InverseEdit {
+    /// Applies the edit to `table` and returns the edit reverting it again
+    ///
+    /// Empty edits leave the table untouched.
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("Hello");
+    /// let undo = table.insert_with_inverse(5, ", World!");
+    /// let redo = undo.apply(&mut table);
+    /// assert_eq!(table.to_string(), "Hello");
+    /// redo.apply(&mut table);
+    /// assert_eq!(table.to_string(), "Hello, World!");
+    /// ```
+    pub fn apply(self, table: &mut PieceTable) -> InverseEdit {
+        match self {
+            InverseEdit::Remove { pos, len: 0 } => InverseEdit::Insert {
+                pos,
+                text: String::new(),
+            },
+            InverseEdit::Remove { pos, len } => table.remove_with_inverse(pos, len),
+            InverseEdit::Insert { pos, text } if text.is_empty() => {
+                InverseEdit::Remove { pos, len: 0 }
+            }
+            InverseEdit::Insert { pos, text } => table.insert_with_inverse(pos, text),
+        }
+    }
+}
+
+impl PieceTable {
+    /// Inserts `string` at `pos` and returns the edit that reverts it
+    ///
+    /// The insertion is recorded in the history like `PieceTable::insert`.
+    ///
+    /// # Panic
+    /// Panics under the same conditions as `PieceTable::insert`
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::{InverseEdit, PieceTable};
+    ///
+    /// let mut table = PieceTable::from("HelloWorld!");
+    /// let inverse = table.insert_with_inverse(5, ", ");
+    /// assert_eq!(inverse, InverseEdit::Remove { pos: 5, len: 2 });
+    /// ```
+    pub fn insert_with_inverse<S: AsRef<str>>(&mut self, pos: usize, string: S) -> InverseEdit {
+        let string = string.as_ref();
+        let pos = self.clamp_pos(pos);
+        self.insert(pos, string);
+
+        InverseEdit::Remove {
+            pos,
+            len: string.len(),
+        }
+    }
+
+    /// Removes `n` bytes at `pos` and returns the edit that reverts it
+    ///
+    /// The removal is recorded in the history like `PieceTable::remove`.
+    ///
+    /// # Panic
+    /// Panics under the same conditions as `PieceTable::remove`
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::{InverseEdit, PieceTable};
+    ///
+    /// let mut table = PieceTable::from("Hello, World!");
+    /// let inverse = table.remove_with_inverse(5, 7);
+    /// assert_eq!(
+    ///     inverse,
+    ///     InverseEdit::Insert { pos: 5, text: String::from(", World") }
+    /// );
+    /// ```
+    pub fn remove_with_inverse(&mut self, pos: usize, n: usize) -> InverseEdit {
+        let range = self.clamp_range(pos, n);
+        let text = if range.is_empty() || range.end > self.total_length {
+            String::new()
+        } else {
+            self._slice(range.start, range.end)
+        };
+        self.remove(range.start, range.len());
+
+        InverseEdit::Insert {
+            pos: range.start,
+            text,
+        }
+    }
+}
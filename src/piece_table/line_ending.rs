@@ -0,0 +1,168 @@
+use std::io::{self, Read, Write};
+
+use super::PieceTable;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+/// Line ending style used when writing the text with `PieceTable::write_text`
+pub enum LineEnding {
+    /// `\n`
+    #[default]
+    Lf,
+    /// `\r\n`
+    CrLf,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+/// Number of line endings of each kind in a text
+pub struct LineEndingStats {
+    /// `\n` not preceded by `\r`
+    pub lf: usize,
+    /// `\r\n`
+    pub crlf: usize,
+    /// `\r` not followed by `\n`
+    pub cr: usize,
+}
+
+impl LineEndingStats {
+    /// Returns `true` if more than one kind of line ending is used
+    pub fn is_mixed(&self) -> bool {
+        [self.lf, self.crlf, self.cr]
+            .iter()
+            .filter(|count| **count != 0)
+            .count()
+            > 1
+    }
+}
+
+impl PieceTable {
+    /// Counts the line endings of each kind in a single pass over the text
+    ///
+    /// A `\r\n` split across two pieces counts as one `\r\n`.
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::{LineEndingStats, PieceTable};
+    ///
+    /// let mut table = PieceTable::from("a\r");
+    /// table.append("\nb\nc\r");
+    /// let stats = table.line_ending_stats();
+    /// assert_eq!(stats, LineEndingStats { lf: 1, crlf: 1, cr: 1 });
+    /// assert!(stats.is_mixed());
+    /// ```
+    pub fn line_ending_stats(&self) -> LineEndingStats {
+        let mut stats = LineEndingStats::default();
+        let mut bytes = self.byte_indices().map(|(_, byte)| byte).peekable();
+        while let Some(byte) = bytes.next() {
+            match byte {
+                b'\n' => stats.lf += 1,
+                b'\r' if bytes.next_if_eq(&b'\n').is_some() => stats.crlf += 1,
+                b'\r' => stats.cr += 1,
+                _ => continue,
+            }
+        }
+
+        stats
+    }
+
+    /// Reads the whole `reader` into the original buffer of a new Piece Table
+    ///
+    /// The text is read straight into the buffer, it isn't copied again like with
+    /// `PieceTable::from`. Invalid UTF-8 is reported as `io::ErrorKind::InvalidData`.
+    /// See `PieceTable::read_from` to also detect the line ending style.
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let table = PieceTable::from_reader("Hello, World!".as_bytes()).unwrap();
+    /// assert_eq!(table.to_string(), "Hello, World!");
+    /// assert!(PieceTable::from_reader(&[0xff][..]).is_err());
+    /// ```
+    pub fn from_reader<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut string = String::new();
+        reader.read_to_string(&mut string)?;
+
+        Ok(PieceTable::from_source(string))
+    }
+
+    /// Reads the whole `reader` into a new Piece Table and records its dominant line ending
+    ///
+    /// The line ending is `LineEnding::CrLf` if the text contains more `\r\n` than
+    /// lone `\n` line endings. Invalid UTF-8 is reported as `io::ErrorKind::InvalidData`.
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::{LineEnding, PieceTable};
+    ///
+    /// let table = PieceTable::read_from("a\r\nb\r\n".as_bytes()).unwrap();
+    /// assert_eq!(table.line_ending(), LineEnding::CrLf);
+    /// ```
+    pub fn read_from<R: Read>(reader: R) -> io::Result<Self> {
+        let mut table = PieceTable::from_reader(reader)?;
+
+        let text = table.original_text();
+        let crlf = text.matches("\r\n").count();
+        let lf = text.matches('\n').count() - crlf;
+        if crlf > lf {
+            table.line_ending = LineEnding::CrLf;
+        }
+
+        Ok(table)
+    }
+
+    /// Returns the line ending style the text is written with
+    pub fn line_ending(&self) -> LineEnding {
+        self.line_ending
+    }
+
+    /// Sets the line ending style the text is written with
+    pub fn set_line_ending(&mut self, line_ending: LineEnding) {
+        self.line_ending = line_ending;
+    }
+
+    /// Writes the text to `writer` in the recorded line ending style
+    ///
+    /// With `LineEnding::CrLf` every `\n` that isn't already preceded by `\r` is written
+    /// as `\r\n`. With `LineEnding::Lf` the text is written unchanged.
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::read_from("a\r\nb\r\n".as_bytes()).unwrap();
+    /// table.append("c\n");
+    ///
+    /// let mut out = Vec::new();
+    /// table.write_text(&mut out).unwrap();
+    /// assert_eq!(out, b"a\r\nb\r\nc\r\n");
+    /// ```
+    pub fn write_text<W: Write + ?Sized>(&self, writer: &mut W) -> io::Result<()> {
+        let mut prev_cr = false;
+        for piece in &self.pieces {
+            let text = self.piece_str(piece);
+            if self.line_ending == LineEnding::Lf {
+                writer.write_all(text.as_bytes())?;
+                continue;
+            }
+
+            let mut written = 0;
+            for (pos, byte) in text.bytes().enumerate() {
+                let cr_in_front = if pos == 0 {
+                    prev_cr
+                } else {
+                    text.as_bytes()[pos - 1] == b'\r'
+                };
+                if byte == b'\n' && !cr_in_front {
+                    writer.write_all(&text.as_bytes()[written..pos])?;
+                    writer.write_all(b"\r\n")?;
+                    written = pos + 1;
+                }
+            }
+            writer.write_all(&text.as_bytes()[written..])?;
+
+            prev_cr = text.ends_with('\r');
+        }
+
+        Ok(())
+    }
+}
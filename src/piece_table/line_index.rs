@@ -0,0 +1,364 @@
+use std::{borrow::Cow, mem, ops::Range};
+
+use super::{
+    lines::LineBreaks,
+    piece::{Piece, PieceSource},
+    piece_tree::{LineSummary, PieceTree},
+    structural_delta::{PieceChange, StructuralDelta},
+    PieceTable,
+};
+
+/// Line endings of the buffers and a tree summing up the line endings per piece,
+/// kept in sync with the piece list by every change of it
+#[derive(Clone, Default)]
+pub(crate) struct LineIndex {
+    original: BufferLineBreaks,
+    addition: BufferLineBreaks,
+    pieces: PieceTree,
+}
+
+/// Byte ranges of the line endings in one of the buffers
+#[derive(Clone, Default)]
+struct BufferLineBreaks {
+    line_breaks: Vec<Range<usize>>,
+    /// Length of the scanned part of the buffer
+    scanned: usize,
+}
+
+impl BufferLineBreaks {
+    /// Scans the part of `text` added since the previous scan, the buffers only grow
+    fn scan(&mut self, text: &str) {
+        if text.len() < self.scanned {
+            *self = BufferLineBreaks::default();
+        }
+
+        // A `\r` ending the scanned part becomes a `\r\n` if the added text starts with `\n`
+        let bytes = text.as_bytes();
+        let mut from = self.scanned;
+        if let Some(last) = self.line_breaks.last_mut() {
+            if last.end == from && bytes[last.end - 1] == b'\r' && bytes.get(from) == Some(&b'\n') {
+                last.end += 1;
+                from += 1;
+            }
+        }
+
+        let added = bytes.iter().copied().enumerate().skip(from);
+        self.line_breaks.extend(LineBreaks::new(added));
+        self.scanned = text.len();
+    }
+
+    /// Returns the line endings of the bytes `range` taken on their own
+    fn within(&self, range: Range<usize>) -> PartLineBreaks<'_> {
+        let line_breaks = &self.line_breaks;
+        let first = line_breaks.partition_point(|line_break| line_break.start < range.start);
+        let last = line_breaks
+            .partition_point(|line_break| line_break.end <= range.end)
+            .max(first);
+
+        let non_empty = range.start < range.end;
+        let leading = first
+            .checked_sub(1)
+            .filter(|idx| non_empty && line_breaks[*idx].end > range.start)
+            .map(|_| range.start..range.start + 1);
+        let trailing = line_breaks
+            .get(last)
+            .filter(|line_break| non_empty && line_break.start < range.end)
+            .map(|_| range.end - 1..range.end);
+
+        PartLineBreaks {
+            leading,
+            inner: &line_breaks[first..last],
+            trailing,
+        }
+    }
+
+    /// Returns the `n`th line ending of the bytes `range` taken on their own
+    fn nth_within(&self, range: Range<usize>, n: usize) -> Option<Range<usize>> {
+        let part = self.within(range);
+        let n = match part.leading {
+            Some(_) if n == 0 => return part.leading,
+            Some(_) => n - 1,
+            None => n,
+        };
+
+        match part.inner.get(n) {
+            Some(line_break) => Some(line_break.clone()),
+            None => part.trailing.filter(|_| n == part.inner.len()),
+        }
+    }
+
+    /// Summarizes the bytes `range` of the buffer `text`
+    fn summarize(&self, text: &str, range: Range<usize>) -> LineSummary {
+        let bytes = &text.as_bytes()[range.clone()];
+        let part = self.within(range);
+        LineSummary {
+            len: bytes.len(),
+            breaks: usize::from(part.leading.is_some())
+                + part.inner.len()
+                + usize::from(part.trailing.is_some()),
+            starts_with_lf: bytes.first() == Some(&b'\n'),
+            ends_with_cr: bytes.last() == Some(&b'\r'),
+        }
+    }
+}
+
+/// Line endings of a part of a buffer taken on its own
+///
+/// A `\r\n` cut by the start of the part leaves a leading `\n`, one cut by its end a
+/// trailing `\r`, both are kept apart from the line endings in between.
+struct PartLineBreaks<'a> {
+    leading: Option<Range<usize>>,
+    inner: &'a [Range<usize>],
+    trailing: Option<Range<usize>>,
+}
+
+impl LineIndex {
+    /// Returns the line endings of the buffer `piece` points into (see
+    /// `PieceTable::piece_buffer`)
+    ///
+    /// Inline pieces are scanned on their own, they are only a few bytes long.
+    fn buffer<'a>(&'a self, table: &PieceTable, piece: &Piece) -> Cow<'a, BufferLineBreaks> {
+        match piece.source {
+            PieceSource::Original => Cow::Borrowed(&self.original),
+            PieceSource::Addition => Cow::Borrowed(&self.addition),
+            PieceSource::Inline => {
+                let mut line_breaks = BufferLineBreaks::default();
+                line_breaks.scan(table.piece_str(piece));
+                Cow::Owned(line_breaks)
+            }
+        }
+    }
+}
+
+/// Line queries answered by the line index, see `PieceTable::set_line_index`
+pub(crate) struct IndexedLines<'a> {
+    table: &'a PieceTable,
+    index: &'a LineIndex,
+}
+
+impl IndexedLines<'_> {
+    /// Returns the number of line endings
+    pub(crate) fn count(&self) -> usize {
+        self.index.pieces.total().breaks
+    }
+
+    /// Returns the byte range of the line ending `n`, `None` if there are fewer
+    pub(crate) fn line_break(&self, n: usize) -> Option<Range<usize>> {
+        if n >= self.count() {
+            return None;
+        }
+
+        let found = self
+            .index
+            .pieces
+            .find_break(n + 1)
+            .expect("Line ending is in bounds");
+        let piece = &self.table.pieces[found.idx];
+        let (_, range) = self.table.piece_buffer(piece);
+
+        // The piece's own line endings include a `\n` completing the `\r` in front of it
+        let joined = found.prefix.ends_with_cr && found.own.starts_with_lf;
+        let nth = n - found.prefix.breaks + usize::from(joined);
+        let buffer = self.index.buffer(self.table, piece);
+        let line_break = buffer
+            .nth_within(range.clone(), nth)
+            .expect("Piece contains the line ending");
+        let start = found.prefix.len + line_break.start - range.start;
+        let mut end = found.prefix.len + line_break.end - range.start;
+
+        // A `\r` ending the piece is completed by a `\n` starting the next one
+        let piece_end = found.prefix.len + piece.length;
+        if end == piece_end && found.own.ends_with_cr && line_break.len() == 1 {
+            let next = self.index.pieces.find_byte(end);
+            end += usize::from(next.is_some_and(|next| next.own.starts_with_lf));
+        }
+
+        Some(start..end)
+    }
+
+    /// Returns the number of line endings ending at or before `byte`
+    pub(crate) fn count_before(&self, byte: usize) -> usize {
+        let Some(last) = byte.checked_sub(1) else {
+            return 0;
+        };
+
+        let found = self
+            .index
+            .pieces
+            .find_byte(last)
+            .expect("Position is in bounds");
+        let piece = &self.table.pieces[found.idx];
+        let (text, range) = self.table.piece_buffer(piece);
+        let end = range.start + byte - found.prefix.len;
+        let own = self
+            .index
+            .buffer(self.table, piece)
+            .summarize(text, range.start..end);
+        let prefix = found.prefix.then(own);
+
+        // A `\r` right in front of `byte` doesn't end a line yet if a `\n` follows
+        let lf_follows = if end < range.end {
+            text.as_bytes()[end] == b'\n'
+        } else {
+            let next = self.index.pieces.find_byte(byte);
+            next.is_some_and(|next| next.own.starts_with_lf)
+        };
+        prefix.breaks - usize::from(prefix.ends_with_cr && lf_follows)
+    }
+}
+
+impl PieceTable {
+    /// Enables or disables indexing the line endings of the text
+    ///
+    /// Every piece caches the number of line endings in its text and a balanced tree over
+    /// the pieces sums them up, so `PieceTable::line_count`, `PieceTable::line_byte_range`,
+    /// `PieceTable::byte_range_of_lines`, `PieceTable::line_col_of` and
+    /// `PieceTable::byte_of_line_col` take O(log n) instead of scanning the text, right
+    /// after edits as well. Enabling the index scans the buffers once, edits only scan
+    /// the added text. The index takes 16 bytes per line ending in the buffers and a
+    /// tree node per piece. Disabled by default.
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("Hello,\nWorld!\n");
+    /// table.set_line_index(true);
+    /// assert_eq!(table.line_col_of(9), (1, 2));
+    /// table.insert(0, "Oh,\n");
+    /// assert_eq!(table.line_col_of(9), (1, 5));
+    /// ```
+    pub fn set_line_index(&mut self, enabled: bool) {
+        self.line_index = enabled.then(|| {
+            let mut index = LineIndex::default();
+            index.original.scan(self.original.as_str());
+            index.addition.scan(&self.addition);
+            for piece in &self.pieces {
+                index.pieces.push(self.summarize_piece(&index, piece));
+            }
+            index
+        });
+    }
+
+    /// Returns the line and the byte column within the line of the byte position `byte`
+    ///
+    /// Lines are terminated by `\n`, `\r\n` or a lone `\r` (see `PieceTable::line_count`).
+    ///
+    /// # Panic
+    /// Panics if `byte > PieceTable::len`
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let table = PieceTable::from("Hello,\r\nWorld!");
+    /// assert_eq!(table.line_col_of(3), (0, 3));
+    /// assert_eq!(table.line_col_of(10), (1, 2));
+    /// assert_eq!(table.line_col_of(14), (1, 6));
+    /// ```
+    pub fn line_col_of(&self, byte: usize) -> (usize, usize) {
+        assert!(
+            byte <= self.total_length,
+            "Position {byte} out of bounds for length {}",
+            self.total_length
+        );
+
+        let (line, line_start) = match self.indexed_lines() {
+            Some(lines) => match lines.count_before(byte) {
+                0 => (0, 0),
+                line => {
+                    let line_break = lines.line_break(line - 1).expect("Line ending exists");
+                    (line, line_break.end)
+                }
+            },
+            None => self
+                .line_breaks()
+                .take_while(|line_break| line_break.end <= byte)
+                .fold((0, 0), |(line, _), line_break| (line + 1, line_break.end)),
+        };
+
+        (line, byte - line_start)
+    }
+
+    /// Returns the byte position of the byte column `col` in line `line`
+    ///
+    /// # Panic
+    /// Panics if
+    /// - `line >= PieceTable::line_count`
+    /// - `col` is past the end of the line (excluding its line ending)
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let table = PieceTable::from("Hello,\r\nWorld!");
+    /// assert_eq!(table.byte_of_line_col(1, 2), 10);
+    /// assert_eq!(table.byte_of_line_col(0, 6), 6);
+    /// ```
+    pub fn byte_of_line_col(&self, line: usize, col: usize) -> usize {
+        let Some(range) = self.line_byte_range(line) else {
+            panic!(
+                "Line {line} out of bounds for line count {}",
+                self.line_count()
+            );
+        };
+        assert!(
+            col <= range.len(),
+            "Column {col} out of bounds for line {line} of length {}",
+            range.len()
+        );
+
+        range.start + col
+    }
+
+    /// Returns the line queries of the line index, `None` if it is disabled
+    pub(crate) fn indexed_lines(&self) -> Option<IndexedLines<'_>> {
+        self.line_index
+            .as_ref()
+            .map(|index| IndexedLines { table: self, index })
+    }
+
+    /// Applies the changes of the piece list to the line index, see
+    /// `PieceTable::notify_structural_change`
+    pub(crate) fn update_line_index(&mut self, delta: &StructuralDelta) {
+        let Some(mut index) = self.line_index.take() else {
+            return;
+        };
+
+        index.addition.scan(&self.addition);
+        for change in &delta.changes {
+            match *change {
+                PieceChange::Inserted { index: idx } => index.pieces.insert_stale(idx),
+                PieceChange::Removed { index: idx } => index.pieces.remove(idx),
+                PieceChange::Modified { index: idx } => index.pieces.mark_stale(idx),
+            }
+        }
+
+        let mut pieces = mem::take(&mut index.pieces);
+        pieces.refresh(|idx| self.summarize_piece(&index, &self.pieces[idx]));
+        index.pieces = pieces;
+        self.line_index = Some(index);
+    }
+
+    /// Asserts that the line index matches the pieces (debug builds only)
+    pub(crate) fn debug_validate_line_index(&self) {
+        if cfg!(debug_assertions) {
+            if let Some(index) = &self.line_index {
+                let summaries: Vec<_> = self
+                    .pieces
+                    .iter()
+                    .map(|piece| self.summarize_piece(index, piece))
+                    .collect();
+                assert!(
+                    index.pieces.summaries() == summaries,
+                    "Line index is out of sync with the pieces"
+                );
+            }
+        }
+    }
+
+    fn summarize_piece(&self, index: &LineIndex, piece: &Piece) -> LineSummary {
+        let (text, range) = self.piece_buffer(piece);
+        index.buffer(self, piece).summarize(text, range)
+    }
+}
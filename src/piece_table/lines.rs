@@ -0,0 +1,412 @@
+use std::{
+    cmp::{max, min},
+    iter::{self, Peekable},
+    ops::{Not, Range},
+};
+
+use super::PieceTable;
+
+/// Iterator over the byte ranges of line endings (`\n`, `\r\n` or a lone `\r`)
+///
+/// Works on the continuous byte stream of the Piece Table, a `\r\n` split
+/// across two pieces is treated as a single line ending.
+pub(crate) struct LineBreaks<I: Iterator<Item = (usize, u8)>> {
+    bytes: Peekable<I>,
+}
+
+impl<I: Iterator<Item = (usize, u8)>> LineBreaks<I> {
+    /// Finds the line endings in `bytes`, given with their positions
+    pub(crate) fn new(bytes: I) -> Self {
+        LineBreaks {
+            bytes: bytes.peekable(),
+        }
+    }
+}
+
+impl<I: Iterator<Item = (usize, u8)>> Iterator for LineBreaks<I> {
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (pos, byte) = self.bytes.next()?;
+            match byte {
+                b'\n' => return Some(pos..pos + 1),
+                b'\r' => {
+                    if let Some((_, b'\n')) = self.bytes.peek() {
+                        self.bytes.next();
+                        return Some(pos..pos + 2);
+                    }
+                    return Some(pos..pos + 1);
+                }
+                _ => continue,
+            }
+        }
+    }
+}
+
+impl PieceTable {
+    /// Returns the number of lines in the text
+    ///
+    /// Lines are terminated by `\n`, `\r\n` or a lone `\r`. An empty text or a
+    /// text ending in a line ending has an empty last line.
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("Hello,\r");
+    /// table.append("\nWorld!\n");
+    /// assert_eq!(table.line_count(), 3);
+    /// ```
+    pub fn line_count(&self) -> usize {
+        let line_breaks = match self.indexed_lines() {
+            Some(lines) => lines.count(),
+            None => self.line_breaks().count(),
+        };
+        line_breaks + 1
+    }
+
+    /// Returns the lines overlapping `range`, clipped to `range` and without line endings
+    ///
+    /// # Panic
+    /// Panics if
+    /// - Range is out of bounds
+    /// - `end < start`
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let table = PieceTable::from("Hello,\nWorld!\nBye!");
+    /// let lines: Vec<String> = table.lines_in(3..10).collect();
+    /// assert_eq!(lines, vec!["lo,", "Wor"]);
+    /// ```
+    pub fn lines_in(&self, range: Range<usize>) -> impl Iterator<Item = String> + '_ {
+        assert!(
+            range.start <= range.end,
+            "Range starts at {} but ends at {}",
+            range.start,
+            range.end
+        );
+        assert!(
+            range.end <= self.total_length,
+            "Range {}..{} out of bounds for length {}",
+            range.start,
+            range.end,
+            self.total_length
+        );
+
+        let Range { start, end } = range;
+        // A range starting on the line ending of a non-empty line starts with the next line
+        self.line_ranges()
+            .skip_while(move |line| line.end < start || (line.end == start && line.start < start))
+            .take_while(move |line| start < end && line.start < end)
+            .map(move |line| {
+                let lower = max(line.start, start);
+                let upper = min(line.end, end);
+                if lower < upper {
+                    self._slice(lower, upper)
+                } else {
+                    String::new()
+                }
+            })
+    }
+
+    /// Replaces the contents of `out` with all lines of the text (without line endings)
+    ///
+    /// The `Vec` and the `String`s already in it are reused, so refreshing a line cache
+    /// allocates only for lines that outgrow their reused buffer.
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut lines = vec![String::from("stale")];
+    /// let table = PieceTable::from("Hello,\nWorld!");
+    /// table.collect_lines_into(&mut lines);
+    /// assert_eq!(lines, vec!["Hello,", "World!"]);
+    /// ```
+    pub fn collect_lines_into(&self, out: &mut Vec<String>) {
+        let count = self.lines_from_into(0, usize::MAX, out);
+        out.truncate(count);
+    }
+
+    /// Returns the byte range of line `n` without its line ending,
+    /// `None` if `n >= PieceTable::line_count`
+    ///
+    /// Stops scanning at the end of the line.
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let table = PieceTable::from("Hello,\r\nWorld!\n");
+    /// assert_eq!(table.line_byte_range(1), Some(8..14));
+    /// assert_eq!(table.line_byte_range(2), Some(15..15));
+    /// assert_eq!(table.line_byte_range(3), None);
+    /// ```
+    pub fn line_byte_range(&self, n: usize) -> Option<Range<usize>> {
+        let (start, end) = match self.indexed_lines() {
+            Some(lines) => {
+                let start = match n {
+                    0 => 0,
+                    _ => lines.line_break(n - 1)?.end,
+                };
+                (
+                    start,
+                    lines.line_break(n).map(|line_break| line_break.start),
+                )
+            }
+            None => {
+                let mut line_breaks = self.line_breaks();
+                let start = match n {
+                    0 => 0,
+                    _ => line_breaks.nth(n - 1)?.end,
+                };
+                (start, line_breaks.next().map(|line_break| line_break.start))
+            }
+        };
+
+        Some(start..end.unwrap_or(self.total_length))
+    }
+
+    /// Returns the byte range spanning the lines `lines.start..lines.end`, including
+    /// their line endings
+    ///
+    /// Line ends past the last line are clipped to `PieceTable::len`. Stops scanning at
+    /// the end of the range (see `PieceTable::set_line_index` to avoid scanning).
+    ///
+    /// # Panic
+    /// Panics if
+    /// - `lines.end < lines.start`
+    /// - `lines.start >= PieceTable::line_count`
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let table = PieceTable::from("one\ntwo\r\nthree\nfour");
+    /// assert_eq!(table.byte_range_of_lines(1..3), 4..15);
+    /// assert_eq!(table.byte_range_of_lines(2..10), 9..19);
+    /// assert_eq!(table.byte_range_of_lines(1..1), 4..4);
+    /// ```
+    pub fn byte_range_of_lines(&self, lines: Range<usize>) -> Range<usize> {
+        assert!(
+            lines.start <= lines.end,
+            "Line range starts at {} but ends at {}",
+            lines.start,
+            lines.end
+        );
+
+        // Line ends are the ends of the line breaks, `None` past the last line break
+        let (start, end) = match self.indexed_lines() {
+            Some(indexed) => {
+                let line_end = |n: usize| indexed.line_break(n).map(|line_break| line_break.end);
+                let start = match lines.start {
+                    0 => Some(0),
+                    n => line_end(n - 1),
+                };
+                let end = match lines.end {
+                    0 => Some(0),
+                    n => line_end(n - 1),
+                };
+                (start, end)
+            }
+            None => {
+                let mut line_breaks = self.line_breaks();
+                let start = match lines.start {
+                    0 => Some(0),
+                    n => line_breaks.nth(n - 1).map(|line_break| line_break.end),
+                };
+                let end = match lines.len() {
+                    0 => start,
+                    n => line_breaks.nth(n - 1).map(|line_break| line_break.end),
+                };
+                (start, end)
+            }
+        };
+        let Some(start) = start else {
+            panic!(
+                "Line {} out of bounds for line count {}",
+                lines.start,
+                self.line_count()
+            );
+        };
+
+        start..end.unwrap_or(self.total_length)
+    }
+
+    /// Returns up to `height` lines (without line endings) starting at line `top_line`
+    ///
+    /// Meant for rendering the visible part of a buffer, the pieces are walked once from
+    /// the start of `top_line` (found in O(log n) with `PieceTable::set_line_index`) and
+    /// the text after the last returned line is not scanned. A `top_line` past the last
+    /// line returns no lines.
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let table = PieceTable::from("one\ntwo\r\nthree\nfour");
+    /// assert_eq!(table.window(1, 2), vec!["two", "three"]);
+    /// assert_eq!(table.window(3, 5), vec!["four"]);
+    /// assert!(table.window(4, 5).is_empty());
+    /// ```
+    pub fn window(&self, top_line: usize, height: usize) -> Vec<String> {
+        let mut lines = Vec::new();
+        if let Some(top) = self.line_byte_range(top_line) {
+            self.lines_from_into(top.start, height, &mut lines);
+        }
+
+        lines
+    }
+
+    /// Returns the on-screen column of the byte position `byte` within its line
+    ///
+    /// Every char counts as one column, except `\t` which advances to the next
+    /// multiple of `tab_width`. The `\r` of a `\r\n` line ending takes no column.
+    ///
+    /// # Panic
+    /// Panics if
+    /// - `byte > PieceTable::len`
+    /// - `tab_width == 0`
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let table = PieceTable::from("fn main() {\n\tlet x = 1;\n}");
+    /// assert_eq!(table.visual_column(13, 4), 4);
+    /// assert_eq!(table.visual_column(17, 4), 8);
+    /// ```
+    pub fn visual_column(&self, byte: usize, tab_width: usize) -> usize {
+        assert!(
+            byte <= self.total_length,
+            "Position {byte} out of bounds for length {}",
+            self.total_length
+        );
+        assert!(tab_width != 0, "Tab width must not be 0");
+
+        let line_start = self.line_start_of(byte);
+        self.pieces_in(line_start..byte)
+            .flat_map(|(info, range)| {
+                info.text[range.start..]
+                    .char_indices()
+                    .take_while(move |(pos, _)| range.start + pos < range.end)
+            })
+            .fold(0, |column, (_, c)| match c {
+                '\t' => (column / tab_width + 1) * tab_width,
+                // Only the `\r` of a `\r\n` ending the line can lie in front of `byte`
+                '\r' => column,
+                _ => column + 1,
+            })
+    }
+
+    /// Returns the start of the line containing the byte position `byte`
+    ///
+    /// Looks the line up in the line index if enabled, otherwise walks back from `byte`
+    /// to the previous line ending.
+    fn line_start_of(&self, byte: usize) -> usize {
+        if let Some(lines) = self.indexed_lines() {
+            return match lines.count_before(byte) {
+                0 => 0,
+                line => lines.line_break(line - 1).expect("Line ending exists").end,
+            };
+        }
+
+        // No piece contains `byte` at the end of the text
+        let (idx, start) = self.piece_at(byte);
+        let text = self
+            .pieces
+            .get(idx)
+            .map_or(&[][..], |piece| self.piece_str(piece).as_bytes());
+        // A `\r` right in front of `byte` doesn't end the line if a `\n` follows
+        let mut lf_follows = text.get(byte - start) == Some(&b'\n');
+
+        let mut piece_start = start;
+        let earlier = self.pieces.range(..idx).rev().map(|piece| {
+            piece_start -= piece.length;
+            (piece_start, self.piece_str(piece).as_bytes())
+        });
+        let current = (start, &text[..byte - start]);
+        for (piece_start, text) in iter::once(current).chain(earlier) {
+            for (pos, byte) in text.iter().enumerate().rev() {
+                match byte {
+                    b'\n' => return piece_start + pos + 1,
+                    b'\r' if lf_follows.not() => return piece_start + pos + 1,
+                    _ => lf_follows = false,
+                }
+            }
+        }
+
+        0
+    }
+
+    /// Writes up to `max_lines` lines (without line endings) starting at the line start
+    /// `start` into the first entries of `out`, reusing its `String`s, and returns the
+    /// number of lines written
+    ///
+    /// Walks the pieces once, splitting them at the line endings.
+    fn lines_from_into(&self, start: usize, max_lines: usize, out: &mut Vec<String>) -> usize {
+        if max_lines == 0 {
+            return 0;
+        }
+
+        let mut line = 0;
+        reuse_line(out, line);
+        // A `\r` ending the previous piece, whose `\n` may start the next one
+        let mut after_cr = false;
+        for (info, range) in self.pieces_in(start..self.total_length) {
+            let mut rest = &info.text[range];
+            if after_cr {
+                rest = rest.strip_prefix('\n').unwrap_or(rest);
+            }
+            after_cr = false;
+
+            while let Some(pos) = rest.find(['\n', '\r']) {
+                out[line].push_str(&rest[..pos]);
+                if line + 1 == max_lines {
+                    return max_lines;
+                }
+
+                let line_end = if rest[pos..].starts_with("\r\n") {
+                    pos + 2
+                } else {
+                    pos + 1
+                };
+                after_cr = rest.as_bytes()[pos] == b'\r' && line_end == rest.len();
+                rest = &rest[line_end..];
+                line += 1;
+                reuse_line(out, line);
+            }
+            out[line].push_str(rest);
+        }
+
+        line + 1
+    }
+
+    /// Returns an iterator over the byte ranges of all lines (without line endings)
+    pub(crate) fn line_ranges(&self) -> impl Iterator<Item = Range<usize>> + '_ {
+        let end = iter::once(self.total_length..self.total_length);
+
+        let mut line_start = 0;
+        self.line_breaks().chain(end).map(move |line_break| {
+            let line = line_start..line_break.start;
+            line_start = line_break.end;
+            line
+        })
+    }
+
+    /// Returns an iterator over the byte ranges of all line endings
+    pub(crate) fn line_breaks(&self) -> LineBreaks<impl Iterator<Item = (usize, u8)> + '_> {
+        LineBreaks::new(self.byte_indices())
+    }
+}
+
+/// Clears the `String` at `idx` of `out` for reuse, pushing a new one at the end of `out`
+fn reuse_line(out: &mut Vec<String>, idx: usize) {
+    match out.get_mut(idx) {
+        Some(line) => line.clear(),
+        None => out.push(String::new()),
+    }
+}
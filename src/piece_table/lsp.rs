@@ -0,0 +1,60 @@
+use super::PieceTable;
+
+impl PieceTable {
+    /// Applies an LSP `TextDocumentContentChangeEvent` as a single undo step
+    ///
+    /// The range is given in LSP coordinates: zero-based lines and UTF-16 code
+    /// unit offsets within the line. Offsets past the end of a line default to
+    /// the end of the line.
+    ///
+    /// # Panic
+    /// Panics if
+    /// - a line is out of bounds
+    /// - the end position lies before the start position
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("let 😀 = 1;\nlet b = 2;");
+    /// table.apply_lsp_change(0, 4, 0, 6, "a");
+    /// assert_eq!(table.to_string(), "let a = 1;\nlet b = 2;");
+    /// ```
+    pub fn apply_lsp_change(
+        &mut self,
+        start_line: u32,
+        start_char: u32,
+        end_line: u32,
+        end_char: u32,
+        text: &str,
+    ) {
+        let start = self.utf16_position_to_byte(start_line as usize, start_char as usize);
+        let end = self.utf16_position_to_byte(end_line as usize, end_char as usize);
+
+        self.replace_range(start..end, text);
+    }
+
+    /// Converts a line and UTF-16 code unit offset within the line to a byte position
+    pub(crate) fn utf16_position_to_byte(&self, line: usize, utf16_offset: usize) -> usize {
+        let line_range = self.line_ranges().nth(line).unwrap_or_else(|| {
+            panic!(
+                "Line {line} out of bounds for line count {}",
+                self.line_count()
+            )
+        });
+
+        let mut units = 0;
+        for (pos, c) in self
+            .char_indices()
+            .skip_while(|(pos, _)| *pos < line_range.start)
+            .take_while(|(pos, _)| *pos < line_range.end)
+        {
+            units += c.len_utf16();
+            if units > utf16_offset {
+                return pos;
+            }
+        }
+
+        line_range.end
+    }
+}
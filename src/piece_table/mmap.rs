@@ -0,0 +1,86 @@
+use std::{
+    fs::File,
+    io::{self, ErrorKind},
+    str,
+};
+
+use memmap2::Mmap;
+
+use super::{text_source::TextSource, PieceTable};
+
+/// Memory-mapped file holding UTF-8 text, usable as the original buffer of a Piece Table
+///
+/// Created by `MappedText::map`, see `PieceTable::from_mmap`.
+pub struct MappedText {
+    /// The mapped file, `None` if it is empty (empty files can't be mapped everywhere)
+    map: Option<Mmap>,
+}
+
+impl MappedText {
+    /// Maps `file` into memory, reporting invalid UTF-8 as `io::ErrorKind::InvalidData`
+    ///
+    /// The file is checked to be UTF-8 once, afterwards its text is handed out without
+    /// checks.
+    ///
+    /// # Safety
+    /// The file must not be modified or truncated while it is mapped, neither by this nor
+    /// by another process (see `memmap2::Mmap::map`). Otherwise the text changes under
+    /// the Piece Table and may stop being valid UTF-8.
+    pub unsafe fn map(file: &File) -> io::Result<Self> {
+        if file.metadata()?.len() == 0 {
+            return Ok(MappedText { map: None });
+        }
+
+        // SAFETY: the caller guarantees that the file isn't modified while mapped
+        let map = unsafe { Mmap::map(file)? };
+        str::from_utf8(&map).map_err(|err| io::Error::new(ErrorKind::InvalidData, err))?;
+
+        Ok(MappedText { map: Some(map) })
+    }
+}
+
+impl TextSource for MappedText {
+    fn as_str(&self) -> &str {
+        match &self.map {
+            // SAFETY: checked to be UTF-8 by `MappedText::map`, the file doesn't change
+            Some(map) => unsafe { str::from_utf8_unchecked(map) },
+            None => "",
+        }
+    }
+}
+
+impl PieceTable {
+    /// Creates a Piece Table whose original text is the memory-mapped `file`
+    ///
+    /// The file is neither read into memory nor written, pieces point into the mapping
+    /// and added text is stored in the addition buffer. Invalid UTF-8 is reported as
+    /// `io::ErrorKind::InvalidData`.
+    ///
+    /// # Safety
+    /// The file must not be modified or truncated while the Piece Table (or a clone of it)
+    /// lives, see `MappedText::map`.
+    ///
+    /// # Example
+    /// ```
+    /// use std::fs::{self, File};
+    ///
+    /// use piece_table::PieceTable;
+    ///
+    /// let path = std::env::temp_dir().join("piece_table_from_mmap_example.txt");
+    /// fs::write(&path, "Hello").unwrap();
+    ///
+    /// let file = File::open(&path).unwrap();
+    /// // SAFETY: the file isn't modified while the table lives
+    /// let mut table = unsafe { PieceTable::from_mmap(&file) }.unwrap();
+    /// table.append(", World!");
+    /// assert_eq!(table.to_string(), "Hello, World!");
+    /// assert_eq!(table.original_text(), "Hello");
+    /// # drop(table);
+    /// # fs::remove_file(&path).unwrap();
+    /// ```
+    pub unsafe fn from_mmap(file: &File) -> io::Result<Self> {
+        // SAFETY: guaranteed by the caller
+        let text = unsafe { MappedText::map(file)? };
+        Ok(PieceTable::from_source(text))
+    }
+}
@@ -1,8 +1,24 @@
-#[cfg_attr(test, derive(PartialEq, Debug))]
-#[derive(Clone, Copy)]
-pub(crate) enum PieceSource {
+use std::str;
+
+/// Maximum length of an insertion stored in its piece instead of the addition buffer
+/// (see `PieceTable::set_inline_pieces`), no piece holds text without the feature
+pub(crate) const INLINE_LEN: usize = if cfg!(feature = "inline-pieces") {
+    8
+} else {
+    0
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+/// Buffer a piece points into
+pub enum PieceSource {
+    /// Read only input data
     Original,
+    /// Data added while editing
     Addition,
+    /// Short data added while editing, stored in the piece itself
+    ///
+    /// Only used with the `inline-pieces` feature, see `PieceTable::set_inline_pieces`.
+    Inline,
 }
 
 #[cfg_attr(test, derive(PartialEq, Debug))]
@@ -11,9 +27,14 @@ pub(crate) enum PieceSource {
 pub(crate) struct Piece {
     pub(crate) source: PieceSource,
     /// Offset in the data source
+    ///
+    /// Inline pieces get offsets as if their text was appended to a buffer of its own,
+    /// so anchors and contiguous pieces work like for the other sources.
     pub(crate) offset: usize,
     /// Length of pointed at content
     pub(crate) length: usize,
+    /// Text of a `PieceSource::Inline` piece, zero padded
+    pub(crate) inline: [u8; INLINE_LEN],
 }
 
 impl Piece {
@@ -22,6 +43,71 @@ impl Piece {
             source,
             offset,
             length,
+            inline: [0; INLINE_LEN],
+        }
+    }
+
+    /// Creates a `PieceSource::Inline` piece holding `string` at `offset`
+    ///
+    /// # Panic
+    /// Panics if `string` is longer than `INLINE_LEN`
+    pub(crate) fn inline(offset: usize, string: &str) -> Self {
+        let mut piece = Piece::new(PieceSource::Inline, offset, string.len());
+        assert!(
+            string.len() <= piece.inline.len(),
+            "Inline string of length {} exceeds {INLINE_LEN} bytes",
+            string.len()
+        );
+
+        piece.inline[..string.len()].copy_from_slice(string.as_bytes());
+        piece
+    }
+
+    /// Returns the text of a `PieceSource::Inline` piece, `None` if it isn't valid
+    pub(crate) fn inline_text(&self) -> Option<&str> {
+        let bytes = self.inline.get(..self.length)?;
+        str::from_utf8(bytes).ok()
+    }
+
+    /// Returns a piece pointing at `length` bytes from `offset` in the same source
+    ///
+    /// The span has to lie within this piece for inline pieces, see `Piece::joined` to
+    /// extend them.
+    pub(crate) fn with_span(&self, offset: usize, length: usize) -> Self {
+        let mut piece = Piece {
+            offset,
+            length,
+            ..*self
+        };
+
+        // The inline text starts at the offset of the piece
+        if self.source == PieceSource::Inline {
+            let start = offset - self.offset;
+            piece.inline = [0; INLINE_LEN];
+            piece.inline[..length].copy_from_slice(&self.inline[start..start + length]);
+        }
+        piece
+    }
+
+    /// Returns whether `next` continues this piece in the same source
+    pub(crate) fn is_continued_by(&self, next: &Piece) -> bool {
+        self.source == next.source
+            && self.offset + self.length == next.offset
+            && (self.source != PieceSource::Inline
+                || self.length + next.length <= self.inline.len())
+    }
+
+    /// Returns this piece followed by `next`, which has to continue it
+    /// (see `Piece::is_continued_by`)
+    pub(crate) fn joined(&self, next: &Piece) -> Self {
+        let mut piece = Piece {
+            length: self.length + next.length,
+            ..*self
+        };
+
+        if self.source == PieceSource::Inline {
+            piece.inline[self.length..piece.length].copy_from_slice(&next.inline[..next.length]);
         }
+        piece
     }
 }
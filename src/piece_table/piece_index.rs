@@ -0,0 +1,87 @@
+use super::PieceTable;
+
+/// Start positions of the leading pieces, extended by lookups and cut back by changes
+/// of the piece list
+///
+/// Edits only invalidate the starts behind the changed piece, so after an edit the
+/// next lookup near it extends the index by a few pieces instead of rescanning the text.
+#[derive(Clone, Default)]
+pub(crate) struct PieceIndex {
+    /// `starts[i]`: byte position of piece `i` in the text
+    starts: Vec<usize>,
+}
+
+impl PieceTable {
+    /// Returns the index of the first piece ending behind `pos` and the byte position
+    /// it starts at
+    ///
+    /// Pieces ending at `pos` (including empty pieces) are skipped, positions at or past
+    /// the end map to the number of pieces and `PieceTable::len`.
+    /// Binary searches the start positions, only extending them as far as `pos`.
+    pub(crate) fn piece_at(&self, pos: usize) -> (usize, usize) {
+        if self.pieces.is_empty() {
+            return (0, 0);
+        }
+
+        let mut index = self
+            .piece_index
+            .lock()
+            .expect("Piece index lock is not poisoned");
+        let starts = &mut index.starts;
+        if starts.is_empty() {
+            starts.push(0);
+        }
+        while starts.len() < self.pieces.len() {
+            let last = starts.len() - 1;
+            let end = starts[last] + self.pieces[last].length;
+            if end > pos {
+                break;
+            }
+            starts.push(end);
+        }
+
+        let idx = starts[1..].partition_point(|start| *start <= pos);
+        let start = starts[idx];
+        if start + self.pieces[idx].length > pos {
+            (idx, start)
+        } else {
+            (self.pieces.len(), self.total_length)
+        }
+    }
+
+    /// Drops the start positions of the pieces behind `idx`, called after the pieces
+    /// from `idx` on changed
+    ///
+    /// The start of piece `idx` itself only depends on the pieces in front of it.
+    /// Must be called after the change, as removed pieces take their starts with them.
+    pub(crate) fn invalidate_piece_index(&mut self, idx: usize) {
+        let len = self.pieces.len().min(idx + 1);
+        self.piece_index
+            .get_mut()
+            .expect("Piece index lock is not poisoned")
+            .starts
+            .truncate(len);
+    }
+
+    /// Asserts that the indexed start positions match the pieces (debug builds only)
+    pub(crate) fn debug_validate_piece_index(&self) {
+        if cfg!(debug_assertions) {
+            let index = self
+                .piece_index
+                .lock()
+                .expect("Piece index lock is not poisoned");
+            let starts = self.pieces.iter().scan(0, |start, piece| {
+                let piece_start = *start;
+                *start += piece.length;
+                Some(piece_start)
+            });
+            assert!(
+                index.starts.len() <= self.pieces.len()
+                    && starts
+                        .take(index.starts.len())
+                        .eq(index.starts.iter().copied()),
+                "Piece index is out of sync with the pieces"
+            );
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use std::{
+    io::{self, Write},
+    ops::Range,
+};
+
+use super::{piece::PieceSource, PieceTable};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+/// Read only view of a single piece of a Piece Table
+pub struct PieceInfo<'a> {
+    /// Index of the piece in the piece list
+    pub index: usize,
+    /// Buffer the piece points into
+    pub source: PieceSource,
+    /// Offset of the piece in its source buffer
+    pub offset: usize,
+    /// Byte position of the piece in the text
+    pub start: usize,
+    /// Text the piece points at
+    pub text: &'a str,
+}
+
+impl PieceTable {
+    /// Returns every piece overlapping `range` together with the part of the piece
+    /// covered by `range` (relative to the start of the piece)
+    ///
+    /// An empty range doesn't overlap any piece.
+    ///
+    /// # Panic
+    /// Panics if `range` is reversed or reaches past `PieceTable::len`
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::{PieceSource, PieceTable};
+    ///
+    /// let mut table = PieceTable::from("HelloWorld!");
+    /// table.insert(5, ", ");
+    ///
+    /// let pieces: Vec<_> = table.pieces_in(3..8).collect();
+    /// assert_eq!(pieces.len(), 3);
+    /// assert_eq!(pieces[0].0.text, "Hello");
+    /// assert_eq!(pieces[0].1, 3..5);
+    /// assert_eq!(pieces[1].0.source, PieceSource::Addition);
+    /// assert_eq!(pieces[2].0.start, 7);
+    /// assert_eq!(pieces[2].1, 0..1);
+    /// ```
+    pub fn pieces_in(
+        &self,
+        range: Range<usize>,
+    ) -> impl Iterator<Item = (PieceInfo<'_>, Range<usize>)> + '_ {
+        assert!(
+            range.start <= range.end && range.end <= self.total_length,
+            "Range {}..{} out of bounds for length {}",
+            range.start,
+            range.end,
+            self.total_length
+        );
+
+        // The piece index finds the first piece ending behind `range.start`
+        let (first, mut start) = self.piece_at(range.start);
+        (first..)
+            .zip(self.pieces.range(first..))
+            .map(move |(index, piece)| {
+                let info = PieceInfo {
+                    index,
+                    source: piece.source,
+                    offset: piece.offset,
+                    start,
+                    text: self.piece_str(piece),
+                };
+                start += piece.length;
+                info
+            })
+            .take_while(move |info| info.start < range.end)
+            .map(move |info| {
+                let lower = range.start.saturating_sub(info.start);
+                let upper = (range.end - info.start).min(info.text.len());
+                (info, lower..upper)
+            })
+    }
+
+    /// Returns the text of every piece in text order, without copying it
+    ///
+    /// # Example
+    /// ```
+    /// use std::fmt::Write;
+    ///
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("HelloWorld!");
+    /// table.insert(5, ", ");
+    /// assert_eq!(table.chunks().collect::<Vec<_>>(), vec!["Hello", ", ", "World!"]);
+    ///
+    /// let mut out = String::new();
+    /// for chunk in table.chunks() {
+    ///     out.write_str(chunk).unwrap();
+    /// }
+    /// assert_eq!(out, "Hello, World!");
+    /// ```
+    pub fn chunks(&self) -> impl Iterator<Item = &str> + '_ {
+        self.pieces.iter().map(|piece| self.piece_str(piece))
+    }
+
+    /// Writes the text to `writer` piece by piece, without assembling it first
+    ///
+    /// Writes exactly the text of `to_string`, see `PieceTable::write_text` to convert
+    /// the line endings. Stops at the first failing write.
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("HelloWorld!");
+    /// table.insert(5, ", ");
+    ///
+    /// let mut out = Vec::new();
+    /// table.write_to(&mut out).unwrap();
+    /// assert_eq!(out, b"Hello, World!");
+    /// ```
+    pub fn write_to<W: Write + ?Sized>(&self, writer: &mut W) -> io::Result<()> {
+        for chunk in self.chunks() {
+            writer.write_all(chunk.as_bytes())?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the text as one owned `String` per piece, in text order
+    ///
+    /// Unlike `PieceTable::chunks` the chunks don't borrow the table, so they can be
+    /// handed to another thread. Copies all bytes like `to_string`, but in many small
+    /// allocations instead of a single one.
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("HelloWorld!");
+    /// table.insert(5, ", ");
+    ///
+    /// let chunks = table.to_chunks();
+    /// let len = std::thread::spawn(move || chunks.iter().map(String::len).sum::<usize>())
+    ///     .join()
+    ///     .unwrap();
+    /// assert_eq!(len, 13);
+    /// ```
+    pub fn to_chunks(&self) -> Vec<String> {
+        self.chunks().map(String::from).collect()
+    }
+}
@@ -0,0 +1,330 @@
+use std::{cmp::Ordering, ops::Not};
+
+/// Length and line endings of a run of text, combined piece by piece
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub(crate) struct LineSummary {
+    pub(crate) len: usize,
+    /// Number of line endings (`\n`, `\r\n` or a lone `\r`) in the run on its own
+    pub(crate) breaks: usize,
+    pub(crate) starts_with_lf: bool,
+    pub(crate) ends_with_cr: bool,
+}
+
+impl LineSummary {
+    /// Returns the summary of `self` followed by `next`
+    ///
+    /// A `\r` ending `self` and a `\n` starting `next` are a single line ending.
+    pub(crate) fn then(self, next: LineSummary) -> LineSummary {
+        if self.len == 0 {
+            return next;
+        }
+        if next.len == 0 {
+            return self;
+        }
+
+        LineSummary {
+            len: self.len + next.len,
+            breaks: self.breaks + next.breaks
+                - usize::from(self.ends_with_cr && next.starts_with_lf),
+            starts_with_lf: self.starts_with_lf,
+            ends_with_cr: next.ends_with_cr,
+        }
+    }
+}
+
+/// Piece found by a search of a `PieceTree`
+pub(crate) struct FoundPiece {
+    /// Index of the piece in the piece list
+    pub(crate) idx: usize,
+    /// Summary of the pieces in front of it, `prefix.len` is the start of the piece
+    pub(crate) prefix: LineSummary,
+    /// Summary of the piece itself
+    pub(crate) own: LineSummary,
+}
+
+#[derive(Clone)]
+struct Node {
+    own: LineSummary,
+    /// Summary of the subtree, in piece order
+    total: LineSummary,
+    /// Number of pieces in the subtree
+    size: usize,
+    /// `own` no longer matches the piece
+    stale: bool,
+    /// The subtree contains a stale node
+    stale_below: bool,
+    priority: u64,
+    left: Option<usize>,
+    right: Option<usize>,
+}
+
+/// Balanced tree (a treap) over the pieces, ordered like the piece list
+///
+/// Every node holds the summary of its piece and of its subtree, so the piece containing
+/// a byte or ending a given number of lines is found in O(log n). Changes of the piece
+/// list are mirrored by inserting, removing or marking nodes stale, stale nodes are
+/// summarized again by `PieceTree::refresh`.
+#[derive(Clone)]
+pub(crate) struct PieceTree {
+    nodes: Vec<Node>,
+    /// Nodes of removed pieces, reused by later insertions
+    free: Vec<usize>,
+    root: Option<usize>,
+    /// State of the xorshift generator of the node priorities
+    seed: u64,
+}
+
+impl Default for PieceTree {
+    fn default() -> Self {
+        PieceTree {
+            nodes: Vec::new(),
+            free: Vec::new(),
+            root: None,
+            seed: 0x2545_f491_4f6c_dd1d,
+        }
+    }
+}
+
+impl PieceTree {
+    /// Returns the summary of all pieces
+    pub(crate) fn total(&self) -> LineSummary {
+        self.root
+            .map(|root| self.nodes[root].total)
+            .unwrap_or_default()
+    }
+
+    /// Returns the number of pieces
+    pub(crate) fn len(&self) -> usize {
+        self.size(self.root)
+    }
+
+    /// Appends a piece summarized by `own`
+    pub(crate) fn push(&mut self, own: LineSummary) {
+        let node = self.new_node(own, false);
+        self.root = self.merge(self.root, Some(node));
+    }
+
+    /// Inserts a stale piece at `idx`
+    pub(crate) fn insert_stale(&mut self, idx: usize) {
+        let node = self.new_node(LineSummary::default(), true);
+        let (leading, trailing) = self.split(self.root, idx);
+        let leading = self.merge(leading, Some(node));
+        self.root = self.merge(leading, trailing);
+    }
+
+    /// Removes the piece at `idx`
+    pub(crate) fn remove(&mut self, idx: usize) {
+        let (leading, rest) = self.split(self.root, idx);
+        let (removed, trailing) = self.split(rest, 1);
+        self.free.extend(removed);
+        self.root = self.merge(leading, trailing);
+    }
+
+    /// Marks the piece at `idx` stale
+    pub(crate) fn mark_stale(&mut self, idx: usize) {
+        if let Some(root) = self.root {
+            self.mark_stale_in(root, idx);
+        }
+    }
+
+    /// Summarizes all stale pieces again, `summarize` is called with their indices
+    pub(crate) fn refresh(&mut self, mut summarize: impl FnMut(usize) -> LineSummary) {
+        if let Some(root) = self.root {
+            self.refresh_in(root, 0, &mut summarize);
+        }
+    }
+
+    /// Returns the piece containing byte `pos`, `None` if `pos` is past the end
+    pub(crate) fn find_byte(&self, pos: usize) -> Option<FoundPiece> {
+        self.find(|summary| summary.len > pos)
+    }
+
+    /// Returns the piece completing the first `n` line endings (counting a `\r` ending
+    /// a piece as a line ending), `None` if there are fewer
+    pub(crate) fn find_break(&self, n: usize) -> Option<FoundPiece> {
+        self.find(|summary| summary.breaks >= n)
+    }
+
+    /// Returns the summaries of all pieces in order (for validation)
+    pub(crate) fn summaries(&self) -> Vec<LineSummary> {
+        let mut summaries = Vec::with_capacity(self.len());
+        let mut stack = Vec::new();
+        let mut node = self.root;
+        while node.is_some() || stack.is_empty().not() {
+            while let Some(id) = node {
+                stack.push(id);
+                node = self.nodes[id].left;
+            }
+            let id = stack.pop().expect("Stack is not empty");
+            summaries.push(self.nodes[id].own);
+            node = self.nodes[id].right;
+        }
+
+        summaries
+    }
+
+    /// Returns the first piece whose prefix up to and including itself is `reached`,
+    /// which has to stay reached for longer prefixes
+    fn find(&self, reached: impl Fn(LineSummary) -> bool) -> Option<FoundPiece> {
+        let mut node = self.root;
+        let mut prefix = LineSummary::default();
+        let mut idx = 0;
+        while let Some(id) = node {
+            let Node {
+                own, left, right, ..
+            } = self.nodes[id];
+            let with_left = prefix.then(self.total_of(left));
+            if reached(with_left) {
+                node = left;
+                continue;
+            }
+
+            idx += self.size(left);
+            if reached(with_left.then(own)) {
+                return Some(FoundPiece {
+                    idx,
+                    prefix: with_left,
+                    own,
+                });
+            }
+            prefix = with_left.then(own);
+            idx += 1;
+            node = right;
+        }
+
+        None
+    }
+
+    fn new_node(&mut self, own: LineSummary, stale: bool) -> usize {
+        // xorshift64
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+
+        let node = Node {
+            own,
+            total: own,
+            size: 1,
+            stale,
+            stale_below: stale,
+            priority: self.seed,
+            left: None,
+            right: None,
+        };
+        match self.free.pop() {
+            Some(id) => {
+                self.nodes[id] = node;
+                id
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    fn size(&self, node: Option<usize>) -> usize {
+        node.map_or(0, |id| self.nodes[id].size)
+    }
+
+    fn total_of(&self, node: Option<usize>) -> LineSummary {
+        node.map(|id| self.nodes[id].total).unwrap_or_default()
+    }
+
+    fn stale_below(&self, node: Option<usize>) -> bool {
+        node.is_some_and(|id| self.nodes[id].stale_below)
+    }
+
+    /// Recomputes the subtree data of `id` from its children
+    fn update(&mut self, id: usize) {
+        let Node {
+            own, left, right, ..
+        } = self.nodes[id];
+        let total = self.total_of(left).then(own).then(self.total_of(right));
+        let size = self.size(left) + 1 + self.size(right);
+        let stale_below = self.nodes[id].stale || self.stale_below(left) || self.stale_below(right);
+
+        let node = &mut self.nodes[id];
+        node.total = total;
+        node.size = size;
+        node.stale_below = stale_below;
+    }
+
+    /// Splits the tree `node` into its first `count` pieces and the rest
+    fn split(&mut self, node: Option<usize>, count: usize) -> (Option<usize>, Option<usize>) {
+        let Some(id) = node else {
+            return (None, None);
+        };
+
+        let left_size = self.size(self.nodes[id].left);
+        if count <= left_size {
+            let (leading, trailing) = self.split(self.nodes[id].left, count);
+            self.nodes[id].left = trailing;
+            self.update(id);
+            (leading, Some(id))
+        } else {
+            let (leading, trailing) = self.split(self.nodes[id].right, count - left_size - 1);
+            self.nodes[id].right = leading;
+            self.update(id);
+            (Some(id), trailing)
+        }
+    }
+
+    /// Joins the trees `a` and `b`, with the pieces of `a` in front
+    fn merge(&mut self, a: Option<usize>, b: Option<usize>) -> Option<usize> {
+        let (a_id, b_id) = match (a, b) {
+            (None, tree) | (tree, None) => return tree,
+            (Some(a_id), Some(b_id)) => (a_id, b_id),
+        };
+
+        if self.nodes[a_id].priority > self.nodes[b_id].priority {
+            let right = self.merge(self.nodes[a_id].right, b);
+            self.nodes[a_id].right = right;
+            self.update(a_id);
+            a
+        } else {
+            let left = self.merge(a, self.nodes[b_id].left);
+            self.nodes[b_id].left = left;
+            self.update(b_id);
+            b
+        }
+    }
+
+    fn mark_stale_in(&mut self, id: usize, idx: usize) {
+        let Node { left, right, .. } = self.nodes[id];
+        let left_size = self.size(left);
+        match idx.cmp(&left_size) {
+            Ordering::Less => self.mark_stale_in(left.expect("Index is in bounds"), idx),
+            Ordering::Equal => self.nodes[id].stale = true,
+            Ordering::Greater => {
+                self.mark_stale_in(right.expect("Index is in bounds"), idx - left_size - 1)
+            }
+        }
+        self.update(id);
+    }
+
+    fn refresh_in(
+        &mut self,
+        id: usize,
+        first_idx: usize,
+        summarize: &mut impl FnMut(usize) -> LineSummary,
+    ) {
+        if self.nodes[id].stale_below.not() {
+            return;
+        }
+
+        let Node { left, right, .. } = self.nodes[id];
+        if let Some(left) = left {
+            self.refresh_in(left, first_idx, summarize);
+        }
+        let idx = first_idx + self.size(left);
+        if self.nodes[id].stale {
+            self.nodes[id].own = summarize(idx);
+            self.nodes[id].stale = false;
+        }
+        if let Some(right) = right {
+            self.refresh_in(right, idx + 1, summarize);
+        }
+        self.update(id);
+    }
+}
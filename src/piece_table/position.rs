@@ -0,0 +1,326 @@
+use std::ops::Range;
+
+use super::PieceTable;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+/// Position in the text counted in bytes
+pub struct BytePos(pub usize);
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+/// Position in the text counted in chars
+pub struct CharPos(pub usize);
+
+impl From<usize> for BytePos {
+    fn from(pos: usize) -> Self {
+        BytePos(pos)
+    }
+}
+
+impl From<BytePos> for usize {
+    fn from(pos: BytePos) -> Self {
+        pos.0
+    }
+}
+
+impl From<usize> for CharPos {
+    fn from(pos: usize) -> Self {
+        CharPos(pos)
+    }
+}
+
+impl From<CharPos> for usize {
+    fn from(pos: CharPos) -> Self {
+        pos.0
+    }
+}
+
+impl PieceTable {
+    /// Inserts `string` at the byte position `pos`
+    ///
+    /// See `PieceTable::insert`
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::{CharPos, PieceTable};
+    ///
+    /// let mut table = PieceTable::from("Grüß!");
+    /// let pos = table.to_byte_pos(CharPos(4));
+    /// table.insert_at(pos, " Gott");
+    /// assert_eq!(table.to_string(), "Grüß Gott!");
+    /// ```
+    pub fn insert_at<S: AsRef<str>>(&mut self, pos: BytePos, string: S) {
+        self.insert(pos.0, string);
+    }
+
+    /// Removes `n` bytes from the byte position `pos`
+    ///
+    /// See `PieceTable::remove`
+    pub fn remove_at(&mut self, pos: BytePos, n: usize) {
+        self.remove(pos.0, n);
+    }
+
+    /// Inserts `string` at the char position `char_pos`
+    ///
+    /// See `PieceTable::insert`
+    ///
+    /// # Panic
+    /// Panics if `char_pos > PieceTable::char_len` or like `PieceTable::insert`
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("café!");
+    /// table.insert_char_pos(4, " au lait");
+    /// assert_eq!(table.to_string(), "café au lait!");
+    /// ```
+    pub fn insert_char_pos<S: AsRef<str>>(&mut self, char_pos: usize, string: S) {
+        let pos = self.to_byte_pos(CharPos(char_pos));
+        self.insert(pos.0, string);
+    }
+
+    /// Returns the number of chars in the text
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("café");
+    /// table.append("!");
+    /// assert_eq!(table.char_len(), 5);
+    /// assert_eq!(table.len(), 6);
+    /// ```
+    pub fn char_len(&self) -> usize {
+        self.pieces
+            .iter()
+            .map(|piece| self.piece_str(piece).chars().count())
+            .sum()
+    }
+
+    /// Removes `char_count` chars starting at the char position `char_pos`
+    ///
+    /// See `PieceTable::remove`
+    ///
+    /// # Panic
+    /// Panics if
+    /// - the char range reaches past the last char
+    /// - `char_count == 0`
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("Grüß Gott!");
+    /// table.remove_chars(2, 3);
+    /// assert_eq!(table.to_string(), "GrGott!");
+    /// ```
+    pub fn remove_chars(&mut self, char_pos: usize, char_count: usize) {
+        let end = char_pos.saturating_add(char_count);
+        let (Some(start), Some(end)) = (
+            self.checked_byte_pos(CharPos(char_pos)),
+            self.checked_byte_pos(CharPos(end)),
+        ) else {
+            panic!(
+                "Char range {char_pos}..{end} out of bounds for char count {}",
+                self.char_len()
+            );
+        };
+
+        self.remove(start.0, end.0 - start.0);
+    }
+
+    /// Returns the text between two byte positions
+    ///
+    /// See `PieceTable::range_slice`
+    pub fn slice_at(&self, range: Range<BytePos>) -> String {
+        self.range_slice(range.start.0..range.end.0)
+    }
+
+    /// Converts a char position to the byte position of that char
+    ///
+    /// The position after the last char converts to `PieceTable::len`.
+    ///
+    /// # Panic
+    /// Panics if `pos` is greater than the number of chars
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::{BytePos, CharPos, PieceTable};
+    ///
+    /// let table = PieceTable::from("Grüß!");
+    /// assert_eq!(table.to_byte_pos(CharPos(3)), BytePos(4));
+    /// assert_eq!(table.to_byte_pos(CharPos(5)), BytePos(7));
+    /// ```
+    pub fn to_byte_pos(&self, pos: CharPos) -> BytePos {
+        match self.checked_byte_pos(pos) {
+            Some(byte_pos) => byte_pos,
+            None => panic!(
+                "Char position {} out of bounds for char count {}",
+                pos.0,
+                self.char_len()
+            ),
+        }
+    }
+
+    /// Converts a char position to a byte position, `None` if it is out of bounds
+    pub(crate) fn checked_byte_pos(&self, pos: CharPos) -> Option<BytePos> {
+        let mut chars = 0;
+        let mut bytes = 0;
+        for piece in &self.pieces {
+            let text = self.piece_str(piece);
+            let piece_chars = text.chars().count();
+            if pos.0 < chars + piece_chars {
+                let (offset, _) = text
+                    .char_indices()
+                    .nth(pos.0 - chars)
+                    .expect("Char is inside of the piece");
+                return Some(BytePos(bytes + offset));
+            }
+
+            chars += piece_chars;
+            bytes += piece.length;
+        }
+
+        (pos.0 == chars).then_some(BytePos(bytes))
+    }
+
+    /// Returns the text of the char range `chars`, `None` if the range is reversed
+    /// or out of bounds
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("Grüß");
+    /// table.append(" Gott!");
+    /// assert_eq!(table.get_chars(2..7), Some(String::from("üß Go")));
+    /// assert_eq!(table.get_chars(8..11), None);
+    /// ```
+    pub fn get_chars(&self, chars: Range<usize>) -> Option<String> {
+        if chars.start > chars.end {
+            return None;
+        }
+
+        let start = self.checked_byte_pos(CharPos(chars.start))?;
+        let end = self.checked_byte_pos(CharPos(chars.end))?;
+        if start == end {
+            return Some(String::new());
+        }
+
+        Some(self._slice(start.0, end.0))
+    }
+
+    /// Converts a byte position to the number of chars in front of it
+    ///
+    /// # Panic
+    /// Panics if
+    /// - `pos > PieceTable::len`
+    /// - `pos` is not a char boundary
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::{BytePos, CharPos, PieceTable};
+    ///
+    /// let table = PieceTable::from("Grüß!");
+    /// assert_eq!(table.to_char_pos(BytePos(4)), CharPos(3));
+    /// ```
+    pub fn to_char_pos(&self, pos: BytePos) -> CharPos {
+        assert!(
+            pos.0 <= self.total_length,
+            "Byte position {} out of bounds for length {}",
+            pos.0,
+            self.total_length
+        );
+
+        let mut chars = 0;
+        let mut bytes = 0;
+        for piece in &self.pieces {
+            if bytes + piece.length > pos.0 {
+                let text = self.piece_str(piece);
+                let offset = pos.0 - bytes;
+                assert!(
+                    text.is_char_boundary(offset),
+                    "Byte {} is not a char boundary",
+                    pos.0
+                );
+                return CharPos(chars + text[..offset].chars().count());
+            }
+
+            chars += self.piece_str(piece).chars().count();
+            bytes += piece.length;
+        }
+
+        CharPos(chars)
+    }
+
+    /// Returns the byte positions of all char boundaries in `range` (`range.end` excluded)
+    ///
+    /// Only the pieces overlapping `range` are scanned.
+    ///
+    /// # Panic
+    /// Panics if `range` is reversed or reaches past `PieceTable::len`
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("añ");
+    /// table.append("€b");
+    /// let boundaries: Vec<usize> = table.char_boundaries_in(2..7).collect();
+    /// assert_eq!(boundaries, vec![3, 6]);
+    /// ```
+    pub fn char_boundaries_in(&self, range: Range<usize>) -> impl Iterator<Item = usize> + '_ {
+        self.pieces_in(range).flat_map(|(info, range)| {
+            info.text
+                .char_indices()
+                .map(|(pos, _)| pos)
+                .skip_while(move |pos| *pos < range.start)
+                .take_while(move |pos| *pos < range.end)
+                .map(move |pos| info.start + pos)
+        })
+    }
+
+    /// Returns the last `n` bytes of the text
+    ///
+    /// `n` is clamped to `PieceTable::len`, a cut inside a char moves forward to
+    /// the next char boundary, so fewer than `n` bytes may be returned.
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let table = PieceTable::from("Price: 5€");
+    /// assert_eq!(table.slice_from_end(4), "5€");
+    /// assert_eq!(table.slice_from_end(2), "");
+    /// assert_eq!(table.slice_from_end(100), "Price: 5€");
+    /// ```
+    pub fn slice_from_end(&self, n: usize) -> String {
+        self._slice(self.tail_start(n), self.total_length)
+    }
+
+    /// Returns the text without its last `n` bytes
+    ///
+    /// Complements `PieceTable::slice_from_end`: the cut is placed the same way,
+    /// so fewer than `n` bytes may be dropped when it falls inside a char.
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let table = PieceTable::from("Price: 5€");
+    /// assert_eq!(table.slice_to_end_minus(4), "Price: ");
+    /// assert_eq!(table.slice_to_end_minus(2), "Price: 5€");
+    /// assert_eq!(table.slice_to_end_minus(100), "");
+    /// ```
+    pub fn slice_to_end_minus(&self, n: usize) -> String {
+        self._slice(0, self.tail_start(n))
+    }
+
+    /// Returns the first char boundary at or after `PieceTable::len - n`
+    fn tail_start(&self, n: usize) -> usize {
+        let cut = self.total_length.saturating_sub(n);
+        self.char_boundaries_in(cut..self.total_length)
+            .next()
+            .unwrap_or(self.total_length)
+    }
+}
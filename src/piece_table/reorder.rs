@@ -0,0 +1,196 @@
+use std::ops::Range;
+
+use crate::history::{change::ChangeType, commit::Commit};
+
+use super::{edit_delta::EditDelta, PieceTable};
+
+impl PieceTable {
+    /// Swaps the text of the non-overlapping ranges `a` and `b` as a single undo step
+    ///
+    /// Only the pieces are reordered, no text is copied into the addition buffer.
+    /// Swapping a range with an empty range moves its text to the empty range.
+    ///
+    /// # Panic
+    /// Panics if
+    /// - `a` or `b` is reversed or reaches past `PieceTable::len`
+    /// - `a` and `b` overlap
+    /// - a bound of `a` or `b` falls inside a character
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("first\nsecond\n");
+    /// table.swap_ranges(0..6, 6..13);
+    /// assert_eq!(table.to_string(), "second\nfirst\n");
+    /// assert_eq!(table.addition_text(), "");
+    ///
+    /// table.undo();
+    /// assert_eq!(table.to_string(), "first\nsecond\n");
+    /// ```
+    pub fn swap_ranges(&mut self, a: Range<usize>, b: Range<usize>) {
+        for range in [&a, &b] {
+            assert!(
+                range.start <= range.end,
+                "Swap range starts at {} but ends at {}",
+                range.start,
+                range.end
+            );
+            assert!(
+                range.end <= self.total_length,
+                "Swap range {}..{} out of bounds for length {}",
+                range.start,
+                range.end,
+                self.total_length
+            );
+        }
+        assert!(
+            a.start >= b.end || b.start >= a.end,
+            "Swap ranges {}..{} and {}..{} overlap",
+            a.start,
+            a.end,
+            b.start,
+            b.end
+        );
+
+        let (first, second) = if a.start <= b.start { (a, b) } else { (b, a) };
+        let adjacent = first.end == second.start;
+        if (first.is_empty() || second.is_empty()) && (adjacent || first.len() == second.len()) {
+            return;
+        }
+
+        self.reorder([first.start, first.end, second.start, second.end]);
+    }
+
+    /// Moves the `len` bytes starting at `from` to `to` as a single undo step
+    ///
+    /// `to` is a position in the text before the move. Only the pieces are reordered,
+    /// no text is copied into the addition buffer. Moving text into itself (`to` inside
+    /// `from..=from + len`) does nothing.
+    ///
+    /// # Panic
+    /// Panics if
+    /// - `from + len > PieceTable::len`
+    /// - `to > PieceTable::len`
+    /// - `from`, `from + len` or `to` falls inside a character
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("fn b() {}\nfn a() {}\n");
+    /// table.move_range(10, 10, 0);
+    /// assert_eq!(table.to_string(), "fn a() {}\nfn b() {}\n");
+    /// assert_eq!(table.addition_text(), "");
+    ///
+    /// table.undo();
+    /// assert_eq!(table.to_string(), "fn b() {}\nfn a() {}\n");
+    /// ```
+    pub fn move_range(&mut self, from: usize, len: usize, to: usize) {
+        let end = match from.checked_add(len) {
+            Some(end) if end <= self.total_length => end,
+            end => panic!(
+                "Move range {from}..{} out of bounds for length {}",
+                end.unwrap_or(usize::MAX),
+                self.total_length
+            ),
+        };
+        assert!(
+            to <= self.total_length,
+            "Move target {to} out of bounds for length {}",
+            self.total_length
+        );
+
+        if len == 0 || (from..=end).contains(&to) {
+            return;
+        }
+
+        if to < from {
+            self.reorder([to, to, from, end]);
+        } else {
+            self.reorder([from, end, to, to]);
+        }
+    }
+
+    /// Reorders the text between the ascending `bounds` from `a, between, b` to
+    /// `b, between, a` by moving pieces
+    fn reorder(&mut self, bounds: [usize; 4]) {
+        for pos in bounds {
+            assert!(
+                self.is_char_boundary(pos),
+                "Position {pos} falls inside a character"
+            );
+        }
+
+        let mut commit = self.new_commit();
+        let [start, a_end, b_start, end] = bounds.map(|pos| self.split_at(&mut commit, pos));
+
+        // Only `a` and `b` are detached, the pieces between them stay in place
+        use ChangeType::*;
+        let mut detach = |table: &mut PieceTable, idx: usize, count: usize| -> Vec<_> {
+            (0..count)
+                .map(|_| {
+                    let piece = table.pieces.remove(idx).expect("Piece index is in bounds");
+                    commit.add_change(idx, piece, Deletion);
+                    piece
+                })
+                .collect()
+        };
+        let a = detach(self, start, a_end - start);
+        let b = detach(self, b_start - a.len(), end - b_start);
+
+        let between = b_start - a_end;
+        let b_inserts = (start..).zip(&b);
+        let a_inserts = (start + b.len() + between..).zip(&a);
+        for (idx, piece) in b_inserts.chain(a_inserts) {
+            self.pieces.insert(idx, *piece);
+            commit.add_change(idx, *piece, Insertion);
+        }
+        self.invalidate_piece_index(start);
+
+        // Merge pieces that became contiguous at the seams, back to front to keep the
+        // indices of the remaining seams valid
+        let seams = [end, start + b.len() + between, start + b.len(), start];
+        let mut previous = None;
+        for seam in seams {
+            if seam == 0 || previous == Some(seam) {
+                continue;
+            }
+            previous = Some(seam);
+            while self.merge_with_next(&mut commit, seam - 1) {}
+        }
+
+        commit.add_edit(EditDelta::new(bounds[0], bounds[3], bounds[3]));
+        self.save_commit(commit);
+    }
+
+    /// Splits the piece containing `pos` so that a piece starts at `pos` and returns its
+    /// index (`PieceTable::len` maps to the number of pieces), recording the split in `commit`
+    pub(crate) fn split_at(&mut self, commit: &mut Commit, pos: usize) -> usize {
+        let mut len = 0;
+        for (idx, piece) in self.pieces.iter().enumerate() {
+            if len == pos {
+                return idx;
+            }
+            if len + piece.length > pos {
+                let piece = *piece;
+                let offset = pos - len;
+                let leading = piece.with_span(piece.offset, offset);
+                let trailing = piece.with_span(piece.offset + offset, piece.length - offset);
+
+                self.pieces[idx] = leading;
+                self.pieces.insert(idx + 1, trailing);
+                self.invalidate_piece_index(idx);
+
+                use ChangeType::*;
+                commit.add_change(idx, piece, Deletion);
+                commit.add_change(idx, leading, Insertion);
+                commit.add_change(idx + 1, trailing, Insertion);
+                return idx + 1;
+            }
+            len += piece.length;
+        }
+
+        self.pieces.len()
+    }
+}
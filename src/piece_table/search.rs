@@ -0,0 +1,269 @@
+use std::ops::{Not, Range};
+
+use super::PieceTable;
+
+impl PieceTable {
+    /// Returns the byte position of the first occurrence of `pattern`
+    ///
+    /// # Panic
+    /// Panics if `pattern` is empty
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("Hello!");
+    /// table.insert(5, ", World");
+    /// assert_eq!(table.find("o, W"), Some(4));
+    /// assert_eq!(table.find("Moon"), None);
+    /// ```
+    pub fn find<P: AsRef<str>>(&self, pattern: P) -> Option<usize> {
+        self.match_starts(pattern.as_ref()).next()
+    }
+
+    /// Returns the byte positions of all non-overlapping occurrences of `pattern`
+    ///
+    /// # Panic
+    /// Panics if `pattern` is empty
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("aaa");
+    /// table.append("a");
+    /// assert_eq!(table.find_all("aa"), vec![0, 2]);
+    /// ```
+    pub fn find_all<P: AsRef<str>>(&self, pattern: P) -> Vec<usize> {
+        self.match_starts(pattern.as_ref()).collect()
+    }
+
+    /// Returns the byte positions of all non-overlapping occurrences of `pattern`
+    ///
+    /// Feeds the chars of all pieces through a Knuth-Morris-Pratt automaton, so matches
+    /// across pieces are found in a single pass without assembling the text.
+    ///
+    /// # Panic
+    /// Panics if `pattern` is empty
+    fn match_starts<'a>(&'a self, pattern: &str) -> impl Iterator<Item = usize> + 'a {
+        assert!(pattern.is_empty().not(), "Pattern must not be empty");
+
+        let byte_len = pattern.len();
+        let pattern: Vec<char> = pattern.chars().collect();
+
+        // `fallback[i]`: length of the longest proper prefix of `pattern[..=i]` that is also its suffix
+        let mut fallback = vec![0; pattern.len()];
+        let mut len = 0;
+        for i in 1..pattern.len() {
+            while len > 0 && pattern[i] != pattern[len] {
+                len = fallback[len - 1];
+            }
+            if pattern[i] == pattern[len] {
+                len += 1;
+            }
+            fallback[i] = len;
+        }
+
+        let mut matched = 0;
+        self.char_indices().filter_map(move |(pos, c)| {
+            while matched > 0 && pattern[matched] != c {
+                matched = fallback[matched - 1];
+            }
+            if pattern[matched] == c {
+                matched += 1;
+            }
+            if matched < pattern.len() {
+                return None;
+            }
+
+            // Start over to only report non-overlapping matches
+            matched = 0;
+            Some(pos + c.len_utf8() - byte_len)
+        })
+    }
+
+    /// Replaces every non-overlapping occurrence of `pattern` with the text computed by `f`
+    ///
+    /// `f` is called with the byte position of each match (before any replacement)
+    /// in text order. All replacements are recorded as a single commit.
+    /// Returns the number of replaced matches.
+    ///
+    /// # Panic
+    /// Panics if `pattern` is empty
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("item, item, item");
+    /// let mut n = 0;
+    /// let count = table.replace_all_with("item", |_| {
+    ///     n += 1;
+    ///     format!("item{n}")
+    /// });
+    /// assert_eq!(count, 3);
+    /// assert_eq!(table.to_string(), "item1, item2, item3");
+    ///
+    /// table.undo();
+    /// assert_eq!(table.to_string(), "item, item, item");
+    /// ```
+    pub fn replace_all_with<P: AsRef<str>, F: FnMut(usize) -> String>(
+        &mut self,
+        pattern: P,
+        mut f: F,
+    ) -> usize {
+        let pattern = pattern.as_ref();
+        let replacements: Vec<(usize, String)> = self
+            .find_all(pattern)
+            .into_iter()
+            .map(|pos| (pos, f(pos)))
+            .collect();
+
+        if replacements.is_empty() {
+            return 0;
+        }
+
+        // Replacing back to front keeps the positions of the remaining matches valid
+        let mut commit = self.new_commit();
+        for (pos, replacement) in replacements.iter().rev() {
+            self.remove_into(&mut commit, *pos, pattern.len());
+            if replacement.is_empty().not() {
+                self.insert_into(&mut commit, *pos, replacement);
+            }
+        }
+        self.save_commit(commit);
+
+        replacements.len()
+    }
+
+    /// Removes every non-overlapping occurrence of `pattern`
+    ///
+    /// All removals are recorded as a single commit.
+    /// Returns the number of removed matches.
+    ///
+    /// # Panic
+    /// Panics if `pattern` is empty
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("Hello,  World!");
+    /// assert_eq!(table.remove_matches(" "), 2);
+    /// assert_eq!(table.to_string(), "Hello,World!");
+    ///
+    /// table.undo();
+    /// assert_eq!(table.to_string(), "Hello,  World!");
+    /// ```
+    pub fn remove_matches<P: AsRef<str>>(&mut self, pattern: P) -> usize {
+        let pattern = pattern.as_ref();
+        let matches = self.find_all(pattern);
+        if matches.is_empty() {
+            return 0;
+        }
+
+        // Removing back to front keeps the positions of the remaining matches valid
+        let mut commit = self.new_commit();
+        for pos in matches.iter().rev() {
+            self.remove_into(&mut commit, *pos, pattern.len());
+        }
+        self.save_commit(commit);
+
+        matches.len()
+    }
+
+    /// Returns whether the text starts with `prefix`
+    ///
+    /// Compares piece by piece, so prefixes spanning several pieces don't assemble the text.
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("World!");
+    /// table.insert(0, "Hello, ");
+    /// assert!(table.starts_with("Hello, Wo"));
+    /// assert!(!table.starts_with("World"));
+    /// ```
+    pub fn starts_with<P: AsRef<str>>(&self, prefix: P) -> bool {
+        let mut rest = prefix.as_ref().as_bytes();
+        for piece in &self.pieces {
+            if rest.is_empty() {
+                break;
+            }
+
+            let bytes = self.piece_str(piece).as_bytes();
+            let n = rest.len().min(bytes.len());
+            if bytes[..n] != rest[..n] {
+                return false;
+            }
+            rest = &rest[n..];
+        }
+
+        rest.is_empty()
+    }
+
+    /// Returns whether the text equals `other`, without assembling it
+    ///
+    /// Stops at the first differing piece, different lengths are rejected up front.
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("World!");
+    /// table.insert(0, "Hello, ");
+    /// assert!(table.text_eq("Hello, World!"));
+    /// assert!(!table.text_eq("Hello, World"));
+    /// ```
+    pub fn text_eq<S: AsRef<str>>(&self, other: S) -> bool {
+        self.range_eq(0..self.total_length, other.as_ref())
+    }
+
+    /// Returns whether the text in `range` equals `text`, without assembling it
+    ///
+    /// # Panic
+    /// Panics if `range` is reversed or reaches past `PieceTable::len`
+    pub(crate) fn range_eq(&self, range: Range<usize>, text: &str) -> bool {
+        if range.len() != text.len() {
+            return false;
+        }
+
+        let mut rest = text.as_bytes();
+        for (info, range) in self.pieces_in(range) {
+            let chunk = &info.text.as_bytes()[range];
+            if rest.starts_with(chunk).not() {
+                return false;
+            }
+            rest = &rest[chunk.len()..];
+        }
+
+        true
+    }
+
+    /// Inserts `prefix` at the front unless the text already starts with it
+    ///
+    /// Returns whether `prefix` was inserted.
+    ///
+    /// # Panic
+    /// Panics if the text would grow beyond the maximum length (see `PieceTable::set_max_len`)
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("fn main() {}");
+    /// assert!(table.ensure_prefix("// SPDX-License-Identifier: MIT\n"));
+    /// assert!(!table.ensure_prefix("// SPDX-License-Identifier: MIT\n"));
+    /// assert_eq!(table.to_string(), "// SPDX-License-Identifier: MIT\nfn main() {}");
+    /// ```
+    pub fn ensure_prefix<S: AsRef<str>>(&mut self, prefix: S) -> bool {
+        let prefix = prefix.as_ref();
+        if self.starts_with(prefix) {
+            return false;
+        }
+
+        self.insert(0, prefix);
+        true
+    }
+}
@@ -0,0 +1,87 @@
+use std::{collections::VecDeque, ops::Range, sync::Mutex};
+
+use super::PieceTable;
+
+/// Least recently used cache of sliced text, cleared on every change of the text
+#[derive(Clone)]
+pub(crate) struct SliceCache {
+    capacity: usize,
+    /// Most recently used entry first
+    entries: VecDeque<(Range<usize>, String)>,
+    pub(crate) hits: usize,
+}
+
+impl SliceCache {
+    pub(crate) fn new(capacity: usize) -> Self {
+        SliceCache {
+            capacity,
+            entries: VecDeque::with_capacity(capacity),
+            hits: 0,
+        }
+    }
+
+    /// Returns the cached text of `range` and marks it as most recently used
+    pub(crate) fn get(&mut self, range: &Range<usize>) -> Option<String> {
+        let idx = self
+            .entries
+            .iter()
+            .position(|(cached, _)| cached == range)?;
+        let entry = self.entries.remove(idx).expect("Entry index is in bounds");
+        let text = entry.1.clone();
+        self.entries.push_front(entry);
+        self.hits += 1;
+
+        Some(text)
+    }
+
+    /// Caches the text of `range`, evicting the least recently used entry if full
+    pub(crate) fn insert(&mut self, range: Range<usize>, text: String) {
+        if self.entries.len() == self.capacity {
+            self.entries.pop_back();
+        }
+        self.entries.push_front((range, text));
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+impl PieceTable {
+    /// Caches the text of the `capacity` most recently sliced ranges until the text changes
+    ///
+    /// Meant for repeatedly rendering the same viewport between edits.
+    /// A capacity of 0 disables the cache (the default).
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::{PieceTable, PieceTableSlice};
+    ///
+    /// let mut table = PieceTable::from("Hello, World!");
+    /// table.set_slice_cache(4);
+    /// assert_eq!(table.slice(0..5), "Hello");
+    /// assert_eq!(table.slice(0..5), "Hello");
+    /// assert_eq!(table.slice_cache_hits(), 1);
+    /// ```
+    pub fn set_slice_cache(&mut self, capacity: usize) {
+        self.slice_cache = (capacity != 0).then(|| Mutex::new(SliceCache::new(capacity)));
+    }
+
+    /// Returns how many slices were served from the slice cache since it was set
+    /// (see `PieceTable::set_slice_cache`)
+    pub fn slice_cache_hits(&self) -> usize {
+        self.slice_cache.as_ref().map_or(0, |cache| {
+            cache.lock().expect("Slice cache lock is not poisoned").hits
+        })
+    }
+
+    /// Drops all cached slices, see `PieceTable::invalidate_caches`
+    pub(crate) fn invalidate_slice_cache(&mut self) {
+        if let Some(cache) = self.slice_cache.as_mut() {
+            cache
+                .get_mut()
+                .expect("Slice cache lock is not poisoned")
+                .clear();
+        }
+    }
+}
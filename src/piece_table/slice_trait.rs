@@ -13,9 +13,10 @@ impl PieceTableSlice<Range<usize>> for PieceTable {
     /// # Panic
     /// Panics if
     /// - Range is out of bounds
-    /// - `end <= start`
+    /// - A bound falls inside a character
+    /// - `end < start`
     fn slice(&self, index: Range<usize>) -> String {
-        self._slice(index.start, index.end)
+        self.range_slice(index)
     }
 }
 
@@ -23,16 +24,16 @@ impl PieceTableSlice<RangeFrom<usize>> for PieceTable {
     /// Returns the text stored in the Piece Table from pos `start..`
     ///
     /// # Panic
-    /// Panics if range is out of bounds
+    /// Panics if range is out of bounds or a bound falls inside a character
     fn slice(&self, index: RangeFrom<usize>) -> String {
-        self._slice(index.start, self.total_length)
+        self.range_slice(index)
     }
 }
 
 impl PieceTableSlice<RangeFull> for PieceTable {
     /// Returns the entire text stored in the Piece Table (prefer `PieceTable::to_string`)
-    fn slice(&self, _index: RangeFull) -> String {
-        self._slice(0, self.total_length)
+    fn slice(&self, index: RangeFull) -> String {
+        self.range_slice(index)
     }
 }
 
@@ -42,15 +43,11 @@ impl PieceTableSlice<RangeInclusive<usize>> for PieceTable {
     /// # Panic
     /// Panics if
     /// - Range is out of bounds
-    /// - `end + 1 <= start`
+    /// - A bound falls inside a character
+    /// - `end + 1 < start`
     /// - `end == usize::MAX`
     fn slice(&self, index: RangeInclusive<usize>) -> String {
-        assert!(
-            *index.end() != usize::MAX,
-            "Upper incluse index must not be usize::MAX"
-        );
-
-        self.slice(*index.start()..*index.end() + 1)
+        self.range_slice(index)
     }
 }
 
@@ -59,9 +56,9 @@ impl PieceTableSlice<RangeTo<usize>> for PieceTable {
     ///
     ///
     /// # Panic
-    /// Panics if range is out of bounds
+    /// Panics if range is out of bounds or a bound falls inside a character
     fn slice(&self, index: RangeTo<usize>) -> String {
-        self.slice(0..index.end)
+        self.range_slice(index)
     }
 }
 
@@ -71,13 +68,9 @@ impl PieceTableSlice<RangeToInclusive<usize>> for PieceTable {
     /// # Panic
     /// Panics if
     /// - Range is out of bounds
+    /// - A bound falls inside a character
     /// - `end == usize::MAX`
     fn slice(&self, index: RangeToInclusive<usize>) -> String {
-        assert!(
-            index.end != usize::MAX,
-            "Upper incluse index must not be usize::MAX"
-        );
-
-        self.slice(0..index.end + 1)
+        self.range_slice(index)
     }
 }
@@ -0,0 +1,123 @@
+use super::{piece::PieceSource, PieceTable};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+/// Fragmentation report of a Piece Table
+pub struct Stats {
+    /// Number of pieces making up the text
+    pub piece_count: usize,
+    /// Length of the text
+    pub byte_len: usize,
+    /// Size of the addition buffer
+    pub addition_bytes: usize,
+    /// Bytes of the addition buffer not referenced by the current text
+    ///
+    /// The history may still reference them.
+    pub reclaimable_bytes: usize,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+/// Size report of the undo history of a Piece Table
+pub struct HistoryStats {
+    /// Number of stored history entries, including the root
+    pub total_entries: usize,
+    /// Number of entries reachable from the root, orphaned entries are not counted
+    pub reachable_entries: usize,
+    /// Number of undo steps from the current state back to the root
+    pub depth_from_root: usize,
+}
+
+impl PieceTable {
+    /// Returns piece and buffer statistics, e.g. to decide when to compact the table
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("Hello");
+    /// table.append(", World!");
+    /// table.remove(5, 2);
+    ///
+    /// let stats = table.stats();
+    /// assert_eq!(stats.piece_count, 2);
+    /// assert_eq!(stats.byte_len, 11);
+    /// assert_eq!(stats.addition_bytes, 8);
+    /// assert_eq!(stats.reclaimable_bytes, 2);
+    /// ```
+    pub fn stats(&self) -> Stats {
+        let mut ranges: Vec<(usize, usize)> = self
+            .pieces
+            .iter()
+            .filter(|piece| matches!(piece.source, PieceSource::Addition))
+            .map(|piece| (piece.offset, piece.offset + piece.length))
+            .collect();
+        ranges.sort_unstable();
+
+        // Merge overlapping ranges to count every referenced byte once
+        let mut referenced = 0;
+        let mut covered_end = 0;
+        for (start, end) in ranges {
+            if end > covered_end {
+                referenced += end - start.max(covered_end);
+                covered_end = end;
+            }
+        }
+
+        Stats {
+            piece_count: self.pieces.len(),
+            byte_len: self.total_length,
+            addition_bytes: self.addition.len(),
+            reclaimable_bytes: self.addition.len() - referenced,
+        }
+    }
+
+    /// Returns `(live_bytes, total_bytes)` of the addition buffer, where `live_bytes` is
+    /// the summed length of the pieces pointing into it
+    ///
+    /// A low ratio means compacting would reclaim most of the buffer. Unlike
+    /// `Stats::reclaimable_bytes`, bytes referenced by several pieces are counted repeatedly.
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("Hello");
+    /// table.append(", World!");
+    /// table.remove(5, 7);
+    /// assert_eq!(table.addition_liveness(), (1, 8));
+    /// ```
+    pub fn addition_liveness(&self) -> (usize, usize) {
+        let live = self
+            .pieces
+            .iter()
+            .filter(|piece| matches!(piece.source, PieceSource::Addition))
+            .map(|piece| piece.length)
+            .sum();
+
+        (live, self.addition.len())
+    }
+
+    /// Returns statistics about the undo history, e.g. to spot a history growing without bounds
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("Hello");
+    /// table.append(", World!");
+    /// table.append("!!");
+    /// table.undo();
+    /// table.append("?");
+    ///
+    /// let stats = table.history_stats();
+    /// assert_eq!(stats.total_entries, 4);
+    /// assert_eq!(stats.reachable_entries, 4);
+    /// assert_eq!(stats.depth_from_root, 2);
+    /// ```
+    pub fn history_stats(&self) -> HistoryStats {
+        HistoryStats {
+            total_entries: self.history.changes.len(),
+            reachable_entries: self.history.reachable_entries(),
+            depth_from_root: self.history.depth(),
+        }
+    }
+}
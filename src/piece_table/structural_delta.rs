@@ -0,0 +1,99 @@
+use std::sync::Mutex;
+
+use crate::history::change::ChangeType;
+
+use super::PieceTable;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+/// Change of a single entry of the piece list
+pub enum PieceChange {
+    /// A piece was inserted at `index`, later pieces moved back by one
+    Inserted { index: usize },
+    /// The piece at `index` was removed, later pieces moved forward by one
+    Removed { index: usize },
+    /// The piece at `index` was replaced
+    Modified { index: usize },
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+/// Changes of the piece list made by a single edit, undo or redo
+///
+/// Applying the changes in order to a list mirroring the pieces keeps it in sync.
+pub struct StructuralDelta {
+    pub changes: Vec<PieceChange>,
+}
+
+/// Callback notified about changes of the piece list
+///
+/// Only called through `&mut PieceTable`, the mutex is never locked and only keeps the
+/// table `Sync` without requiring the callback to be `Sync`.
+pub(crate) type StructuralObserver = Mutex<Box<dyn FnMut(&StructuralDelta) + Send>>;
+
+impl StructuralDelta {
+    /// Collects the changes applied to the piece list, a removal directly followed by
+    /// an insertion at the same index is reported as a modification
+    pub(crate) fn from_changes<I: Iterator<Item = (ChangeType, usize)>>(changes: I) -> Self {
+        let mut delta = StructuralDelta::default();
+        let mut changes = changes.peekable();
+        while let Some((typ, index)) = changes.next() {
+            let change = match typ {
+                ChangeType::Insertion => PieceChange::Inserted { index },
+                ChangeType::Deletion => match changes.peek() {
+                    Some((ChangeType::Insertion, next)) if *next == index => {
+                        changes.next();
+                        PieceChange::Modified { index }
+                    }
+                    _ => PieceChange::Removed { index },
+                },
+            };
+            delta.changes.push(change);
+        }
+
+        delta
+    }
+}
+
+impl PieceTable {
+    /// Registers `f` to be called with the changes of the piece list after every edit,
+    /// undo and redo
+    ///
+    /// Replaces a previously registered callback. Edits that don't change any piece
+    /// are not reported.
+    ///
+    /// # Example
+    /// ```
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// use piece_table::{PieceChange, PieceTable};
+    ///
+    /// let deltas = Arc::new(Mutex::new(Vec::new()));
+    /// let mut table = PieceTable::from("HelloWorld!");
+    /// let observed = Arc::clone(&deltas);
+    /// table.on_structural_change(move |delta| observed.lock().unwrap().push(delta.clone()));
+    ///
+    /// table.append("!");
+    /// assert_eq!(
+    ///     deltas.lock().unwrap()[0].changes,
+    ///     vec![PieceChange::Inserted { index: 1 }]
+    /// );
+    /// ```
+    pub fn on_structural_change<F: FnMut(&StructuralDelta) + Send + 'static>(&mut self, f: F) {
+        self.structural_observer = Some(Mutex::new(Box::new(f)));
+    }
+
+    /// Updates the line index and calls the registered structural change callback,
+    /// unless `delta` is empty
+    pub(crate) fn notify_structural_change(&mut self, delta: &StructuralDelta) {
+        if delta.changes.is_empty() {
+            return;
+        }
+
+        self.update_line_index(delta);
+        if let Some(observer) = self.structural_observer.as_mut() {
+            let observer = observer
+                .get_mut()
+                .expect("Structural observer lock is not poisoned");
+            observer(delta);
+        }
+    }
+}
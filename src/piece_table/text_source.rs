@@ -0,0 +1,40 @@
+use std::{borrow::Cow, sync::Arc};
+
+/// Read only text a Piece Table can use as its original buffer
+///
+/// Implemented for the common owned string types, other backends (e.g. memory-mapped files)
+/// only have to hand out their contents as a `&str`.
+pub trait TextSource: Send + Sync {
+    /// Returns the whole text of the source
+    fn as_str(&self) -> &str;
+}
+
+impl TextSource for String {
+    fn as_str(&self) -> &str {
+        self
+    }
+}
+
+impl TextSource for Box<str> {
+    fn as_str(&self) -> &str {
+        self
+    }
+}
+
+impl TextSource for Arc<str> {
+    fn as_str(&self) -> &str {
+        self
+    }
+}
+
+impl TextSource for &'static str {
+    fn as_str(&self) -> &str {
+        self
+    }
+}
+
+impl TextSource for Cow<'static, str> {
+    fn as_str(&self) -> &str {
+        self
+    }
+}
@@ -0,0 +1,65 @@
+use super::PieceTable;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+/// Checkpoint of the state of a Piece Table, see `PieceTable::version`
+pub struct VersionId {
+    /// Number of times the history was discarded or an untracked edit was made
+    epoch: usize,
+    /// Index of the history head
+    entry: usize,
+    /// Number of edits merged into the head commit
+    revision: usize,
+}
+
+impl PieceTable {
+    /// Returns a checkpoint of the current state
+    ///
+    /// Undoing or redoing back to the checkpoint restores it, see `PieceTable::is_modified_since`.
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("Hello");
+    /// let saved = table.version();
+    /// table.append(", World!");
+    /// assert_ne!(table.version(), saved);
+    /// table.undo();
+    /// assert_eq!(table.version(), saved);
+    /// ```
+    pub fn version(&self) -> VersionId {
+        let entry = self.history.head;
+        VersionId {
+            epoch: self.history_epoch,
+            entry,
+            revision: self
+                .history
+                .changes
+                .get(entry)
+                .map_or(0, |entry| entry.amends),
+        }
+    }
+
+    /// Returns whether the table was edited since `version` was taken
+    ///
+    /// Edits that were undone again don't count, so several independent checkpoints
+    /// (e.g. last save and last autosave) can be tracked at once. Edits made while the
+    /// history is disabled or bypassing it (e.g. `PieceTable::insert_no_history`) and
+    /// discarding the history always count as modifications.
+    ///
+    /// # Example
+    /// ```
+    /// use piece_table::PieceTable;
+    ///
+    /// let mut table = PieceTable::from("Hello");
+    /// let saved = table.version();
+    /// table.append(", World!");
+    /// assert!(table.is_modified_since(saved));
+    ///
+    /// table.undo();
+    /// assert!(!table.is_modified_since(saved));
+    /// ```
+    pub fn is_modified_since(&self, version: VersionId) -> bool {
+        self.version() != version
+    }
+}
@@ -0,0 +1,76 @@
+use std::{
+    io::{self, Write},
+    ops::Not,
+    str,
+};
+
+use super::PieceTable;
+
+/// Appends the written UTF-8 encoded bytes like `PieceTable::append`, every write is an
+/// undo step (unless merged, see `PieceTable::set_append_runs`)
+///
+/// A char split across writes is held back until the write completing it, flushing
+/// before that is reported as `io::ErrorKind::InvalidData`. Invalid UTF-8 is reported as
+/// `io::ErrorKind::InvalidData`, growing the text beyond the maximum length (see
+/// `PieceTable::set_max_len`) as `io::ErrorKind::Other`. Nothing is appended in both cases.
+///
+/// # Example
+/// ```
+/// use std::io::Write;
+///
+/// use piece_table::PieceTable;
+///
+/// let mut table = PieceTable::from("Hello");
+/// table.write_all(b", ").unwrap();
+/// table.write_all(b"W\xc3").unwrap();
+/// table.write_all(b"\xb6rld!").unwrap();
+/// assert_eq!(table.to_string(), "Hello, Wörld!");
+/// assert!(table.write_all(&[0xff]).is_err());
+///
+/// table.undo();
+/// assert_eq!(table.to_string(), "Hello, W");
+/// ```
+impl Write for PieceTable {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        let joined;
+        let bytes = if self.incomplete_write.is_empty() {
+            buf
+        } else {
+            joined = [self.incomplete_write.as_slice(), buf].concat();
+            joined.as_slice()
+        };
+
+        // Only an incomplete char at the very end is held back, anything else is invalid
+        let valid_len = match str::from_utf8(bytes) {
+            Ok(_) => bytes.len(),
+            Err(err) if err.error_len().is_none() => err.valid_up_to(),
+            Err(err) => return Err(io::Error::new(io::ErrorKind::InvalidData, err)),
+        };
+        let (valid, incomplete) = bytes.split_at(valid_len);
+        let string = str::from_utf8(valid).expect("Bytes up to the error are valid UTF-8");
+
+        if string.is_empty().not() {
+            self.try_append(string).map_err(io::Error::other)?;
+        }
+        self.incomplete_write = incomplete.to_vec();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.incomplete_write.is_empty() {
+            return Ok(());
+        }
+
+        Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "Written data ends inside a character ({} bytes held back)",
+                self.incomplete_write.len()
+            ),
+        ))
+    }
+}
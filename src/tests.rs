@@ -1,2 +1,11 @@
+mod anchors;
+mod commit_strategy;
+mod diff;
 mod history;
+mod lines;
+mod lsp;
 mod piece_table;
+mod position;
+mod reorder;
+mod search;
+mod structural_delta;
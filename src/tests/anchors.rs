@@ -0,0 +1,311 @@
+use crate::piece_table::PieceTable;
+use crate::tests::piece_table::get_complex_table;
+
+mod anchor {
+    use super::*;
+
+    #[test]
+    fn insert_before() {
+        let mut table = get_complex_table();
+        let anchor = table.anchor(7);
+        table.insert(0, "Oh, ");
+        assert_eq!(table.anchor_pos(anchor), Some(11));
+    }
+
+    #[test]
+    fn insert_at() {
+        let mut table = get_complex_table();
+        let anchor = table.anchor(7);
+        table.insert(7, "big ");
+        assert_eq!(table.anchor_pos(anchor), Some(11));
+    }
+
+    #[test]
+    fn insert_after() {
+        let mut table = get_complex_table();
+        let anchor = table.anchor(7);
+        table.insert(8, "!");
+        assert_eq!(table.anchor_pos(anchor), Some(7));
+    }
+
+    #[test]
+    fn remove_before() {
+        let mut table = get_complex_table();
+        let anchor = table.anchor(7);
+        table.remove(0, "Hello, ".len());
+        assert_eq!(table.anchor_pos(anchor), Some(0));
+    }
+
+    #[test]
+    fn remove_anchored() {
+        let mut table = get_complex_table();
+        let anchor = table.anchor(7);
+        table.remove(6, 2);
+        assert_eq!(table.anchor_pos(anchor), None);
+
+        table.undo();
+        assert_eq!(table.anchor_pos(anchor), Some(7));
+    }
+
+    #[test]
+    fn end() {
+        let mut table = get_complex_table();
+        let anchor = table.anchor(table.len());
+        table.append(" Bye!");
+        assert_eq!(table.anchor_pos(anchor), Some(table.len()));
+    }
+
+    #[test]
+    fn empty() {
+        let mut table = PieceTable::from("");
+        let anchor = table.anchor(0);
+        table.append("Hello");
+        assert_eq!(table.anchor_pos(anchor), Some(5));
+    }
+
+    #[test]
+    #[should_panic(expected = "Anchor position 14 out of bounds for length 13")]
+    fn out_of_bounds() {
+        let table = get_complex_table();
+        table.anchor(14);
+    }
+}
+
+mod anchors_for {
+    use super::*;
+
+    #[test]
+    fn matches_single() {
+        let table = get_complex_table();
+        let positions: Vec<usize> = (0..=table.len()).rev().collect();
+        let anchors = table.anchors_for(&positions);
+
+        for (pos, anchor) in positions.iter().zip(anchors) {
+            assert_eq!(table.anchor(*pos), anchor);
+            assert_eq!(table.anchor_pos(anchor), Some(*pos));
+        }
+    }
+
+    #[test]
+    fn duplicates() {
+        let table = get_complex_table();
+        let anchors = table.anchors_for(&[3, 3, 0]);
+        assert_eq!(anchors[0], anchors[1]);
+        assert_eq!(table.anchor_pos(anchors[2]), Some(0));
+    }
+
+    #[test]
+    fn after_edits() {
+        let mut table = get_complex_table();
+        let anchors = table.anchors_for(&[12, 2, 7]);
+        table.remove(0, 2);
+        table.insert(5, "__");
+
+        let positions: Vec<_> = anchors.iter().map(|a| table.anchor_pos(*a)).collect();
+        assert_eq!(positions, vec![Some(12), Some(0), Some(7)]);
+        assert_eq!(table.to_string(), "llo, __World!");
+    }
+
+    #[test]
+    fn empty_input() {
+        let table = get_complex_table();
+        assert!(table.anchors_for(&[]).is_empty());
+    }
+}
+
+mod replace_range_keep_anchors {
+    use super::*;
+
+    #[test]
+    fn inside_moved_to_start() {
+        let mut table = get_complex_table();
+        let mut anchors = table.anchors_for(&[7, 9, 11]);
+        table.replace_range_keep_anchors(7..12, "Moon", &mut anchors);
+        assert_eq!(table.to_string(), "Hello, Moon!");
+        let positions: Vec<_> = anchors.iter().map(|a| table.anchor_pos(*a)).collect();
+        assert_eq!(positions, vec![Some(7), Some(7), Some(7)]);
+    }
+
+    #[test]
+    fn outside_unchanged() {
+        let mut table = get_complex_table();
+        let mut anchors = table.anchors_for(&[0, 6, 12, 13]);
+        table.replace_range_keep_anchors(7..12, "Moon", &mut anchors);
+        let positions: Vec<_> = anchors.iter().map(|a| table.anchor_pos(*a)).collect();
+        assert_eq!(positions, vec![Some(0), Some(6), Some(11), Some(12)]);
+    }
+
+    #[test]
+    fn same_text_unchanged() {
+        let mut table = get_complex_table();
+        let mut anchors = table.anchors_for(&[7, 9, 11]);
+        let before = anchors.clone();
+        table.replace_range_keep_anchors(7..12, "World", &mut anchors);
+        assert_eq!(table.to_string(), "Hello, World!");
+        assert_eq!(anchors, before);
+        let positions: Vec<_> = anchors.iter().map(|a| table.anchor_pos(*a)).collect();
+        assert_eq!(positions, vec![Some(7), Some(9), Some(11)]);
+    }
+
+    #[test]
+    fn replace_range_invalidates() {
+        let mut table = get_complex_table();
+        let anchor = table.anchor(9);
+        table.replace_range(7..12, "Moon");
+        assert_eq!(table.anchor_pos(anchor), None);
+    }
+
+    #[test]
+    fn removal() {
+        let mut table = get_complex_table();
+        let mut anchors = table.anchors_for(&[9]);
+        table.replace_range_keep_anchors(5..12, "", &mut anchors);
+        assert_eq!(table.to_string(), "Hello!");
+        assert_eq!(table.anchor_pos(anchors[0]), Some(5));
+    }
+
+    #[test]
+    fn removal_at_end() {
+        let mut table = get_complex_table();
+        let mut anchors = table.anchors_for(&[12]);
+        table.replace_range_keep_anchors(5..13, "", &mut anchors);
+        assert_eq!(table.anchor_pos(anchors[0]), Some(5));
+        table.append("?");
+        assert_eq!(table.anchor_pos(anchors[0]), Some(6));
+    }
+
+    #[test]
+    fn invalid_anchor_untouched() {
+        let mut table = get_complex_table();
+        let anchor = table.anchor(8);
+        table.remove(8, 1);
+        let mut anchors = [anchor];
+        table.replace_range_keep_anchors(7..10, "W", &mut anchors);
+        assert_eq!(table.anchor_pos(anchors[0]), None);
+    }
+}
+
+mod append_span {
+    use super::*;
+
+    #[test]
+    fn survives_edits_around() {
+        let mut table = get_complex_table();
+        let span = table.append_span(" Bye!");
+        table.append(" Hi!");
+        table.insert(0, ">> ");
+        table.remove(3, 7);
+
+        assert_eq!(table.to_string(), ">> World! Bye! Hi!");
+        assert_eq!(table.span_range(span), Some(9..14));
+    }
+
+    #[test]
+    fn grows_with_inner_insertion() {
+        let mut table = PieceTable::from("a");
+        let span = table.append_span("bc");
+        table.insert(2, "-");
+
+        assert_eq!(table.span_range(span), Some(1..4));
+        assert!(table.remove_span(span));
+        assert_eq!(table.to_string(), "a");
+    }
+
+    #[test]
+    fn remove_is_single_undo_step() {
+        let mut table = PieceTable::from("a");
+        let first = table.append_span("bc");
+        let second = table.append_span("de");
+        assert!(table.remove_span(first));
+        assert_eq!(table.to_string(), "ade");
+        assert_eq!(table.span_range(second), Some(1..3));
+
+        table.undo();
+        assert_eq!(table.to_string(), "abcde");
+        assert_eq!(table.span_range(first), Some(1..3));
+    }
+
+    #[test]
+    fn removed_span() {
+        let mut table = PieceTable::from("a");
+        let span = table.append_span("bc");
+        table.remove(2, 1);
+
+        assert_eq!(table.span_range(span), None);
+        assert!(!table.remove_span(span));
+        assert_eq!(table.to_string(), "ab");
+    }
+
+    #[test]
+    fn single_byte() {
+        let mut table = PieceTable::from("");
+        let span = table.append_span("x");
+        table.append("y");
+        assert_eq!(table.span_range(span), Some(0..1));
+    }
+}
+
+mod cursor {
+    use super::*;
+
+    #[test]
+    fn typing_and_backspacing_multi_byte() {
+        let mut table = PieceTable::from("[]");
+        let mut cursor = table.cursor(1);
+        for c in ["G", "r", "ü", "ß", "€"] {
+            cursor.insert(&mut table, c);
+        }
+        assert_eq!(table.to_string(), "[Grüß€]");
+        assert_eq!(cursor.pos(&table), 10);
+
+        cursor.delete_back(&mut table, 1);
+        assert_eq!(table.to_string(), "[Grüß]");
+        cursor.delete_back(&mut table, 2);
+        assert_eq!(table.to_string(), "[Gr]");
+        assert_eq!(cursor.pos(&table), 3);
+
+        cursor.insert(&mut table, "ö");
+        assert_eq!(table.to_string(), "[Grö]");
+    }
+
+    #[test]
+    fn follows_edits_elsewhere() {
+        let mut table = get_complex_table();
+        let mut cursor = table.cursor(5);
+        table.insert(0, "Oh, ");
+        table.append(" Bye!");
+        assert_eq!(cursor.pos(&table), 9);
+
+        cursor.insert(&mut table, "!");
+        assert_eq!(table.to_string(), "Oh, Hello!, World! Bye!");
+    }
+
+    #[test]
+    fn at_end() {
+        let mut table = PieceTable::from("ab");
+        let mut cursor = table.cursor(2);
+        cursor.insert(&mut table, "c");
+        table.append("d");
+        assert_eq!(cursor.pos(&table), 4);
+    }
+
+    #[test]
+    fn delete_back_stops_at_start() {
+        let mut table = PieceTable::from("ñb");
+        let mut cursor = table.cursor(2);
+        cursor.delete_back(&mut table, 5);
+        assert_eq!(table.to_string(), "b");
+        assert_eq!(cursor.pos(&table), 0);
+
+        cursor.delete_back(&mut table, 1);
+        assert_eq!(table.to_string(), "b");
+    }
+
+    #[test]
+    fn anchored_char_removed() {
+        let mut table = PieceTable::from("Hello, World!");
+        let cursor = table.cursor(7);
+        table.remove(5, 8);
+        assert_eq!(cursor.pos(&table), 5);
+    }
+}
@@ -0,0 +1,200 @@
+use std::sync::{Arc, Mutex};
+
+use crate::piece_table::{
+    commit_strategy::{CommitStrategy, PerEdit, PerLine, PerWord},
+    edit_delta::EditDelta,
+    PieceTable,
+};
+
+fn type_text(table: &mut PieceTable, text: &str) {
+    for c in text.chars() {
+        table.append(c.to_string());
+    }
+}
+
+fn undo_steps(table: &mut PieceTable) -> Vec<String> {
+    let mut steps = vec![table.to_string()];
+    while table.undo() {
+        steps.push(table.to_string());
+    }
+    steps
+}
+
+mod per_edit {
+    use super::*;
+
+    #[test]
+    fn every_edit_is_a_step() {
+        let mut table = PieceTable::from("");
+        table.set_commit_strategy(Box::new(PerEdit));
+        type_text(&mut table, "ab c");
+        assert_eq!(undo_steps(&mut table), vec!["ab c", "ab ", "ab", "a", ""]);
+    }
+}
+
+mod per_word {
+    use super::*;
+
+    #[test]
+    fn typing() {
+        let mut table = PieceTable::from("");
+        table.set_commit_strategy(Box::new(PerWord));
+        type_text(&mut table, "Hello big World");
+        assert_eq!(
+            undo_steps(&mut table),
+            vec!["Hello big World", "Hello big", "Hello", ""]
+        );
+    }
+
+    #[test]
+    fn backspace() {
+        let mut table = PieceTable::from("Hello World");
+        table.set_commit_strategy(Box::new(PerWord));
+        for pos in (6..11).rev() {
+            table.remove(pos, 1);
+        }
+        assert_eq!(table.to_string(), "Hello ");
+        assert_eq!(undo_steps(&mut table), vec!["Hello ", "Hello World"]);
+    }
+
+    #[test]
+    fn delete_forward() {
+        let mut table = PieceTable::from("Hello World");
+        table.set_commit_strategy(Box::new(PerWord));
+        for _ in 0..5 {
+            table.remove(0, 1);
+        }
+        assert_eq!(undo_steps(&mut table), vec![" World", "Hello World"]);
+    }
+
+    #[test]
+    fn jump_starts_new_step() {
+        let mut table = PieceTable::from("ac");
+        table.set_commit_strategy(Box::new(PerWord));
+        table.insert(1, "b");
+        table.insert(0, "x");
+        assert_eq!(undo_steps(&mut table), vec!["xabc", "abc", "ac"]);
+    }
+
+    #[test]
+    fn insert_then_remove() {
+        let mut table = PieceTable::from("");
+        table.set_commit_strategy(Box::new(PerWord));
+        type_text(&mut table, "ab");
+        table.remove(1, 1);
+        assert_eq!(undo_steps(&mut table), vec!["a", "ab", ""]);
+    }
+
+    #[test]
+    fn undone_step_is_not_extended() {
+        let mut table = PieceTable::from("");
+        table.set_commit_strategy(Box::new(PerWord));
+        type_text(&mut table, "ab");
+        table.undo();
+        type_text(&mut table, "cd");
+        assert_eq!(table.to_string(), "cd");
+        assert_eq!(undo_steps(&mut table), vec!["cd", ""]);
+        table.hot_redo();
+        assert_eq!(table.to_string(), "cd");
+        assert_eq!(table.validate(), Ok(()));
+    }
+}
+
+mod per_line {
+    use super::*;
+
+    #[test]
+    fn typing() {
+        let mut table = PieceTable::from("");
+        table.set_commit_strategy(Box::new(PerLine));
+        type_text(&mut table, "fn main() {\n}\n");
+        assert_eq!(
+            undo_steps(&mut table),
+            vec!["fn main() {\n}\n", "fn main() {\n}", "fn main() {", ""]
+        );
+    }
+
+    #[test]
+    fn redo_merged_step() {
+        let mut table = PieceTable::from("");
+        table.set_commit_strategy(Box::new(PerLine));
+        type_text(&mut table, "one two");
+        table.undo();
+        assert_eq!(table.to_string(), "");
+        table.hot_redo();
+        assert_eq!(table.to_string(), "one two");
+        assert_eq!(table.last_edit().map(|edit| edit.new_end_byte), Some(7));
+    }
+}
+
+mod last_edit_coalesced {
+    use super::*;
+
+    #[test]
+    fn without_strategy() {
+        let mut table = PieceTable::from("");
+        type_text(&mut table, "ab");
+        assert!(!table.last_edit_coalesced());
+    }
+
+    #[test]
+    fn non_contiguous_edit() {
+        let mut table = PieceTable::from("");
+        table.set_commit_strategy(Box::new(PerLine));
+        type_text(&mut table, "ab");
+        assert!(table.last_edit_coalesced());
+
+        table.insert(0, "x");
+        assert!(!table.last_edit_coalesced());
+        assert_eq!(undo_steps(&mut table), vec!["xab", "ab", ""]);
+    }
+
+    #[test]
+    fn reset_by_undo_and_redo() {
+        let mut table = PieceTable::from("");
+        table.set_commit_strategy(Box::new(PerWord));
+        type_text(&mut table, "ab");
+        assert!(table.last_edit_coalesced());
+
+        table.undo();
+        assert!(!table.last_edit_coalesced());
+        table.hot_redo();
+        assert!(!table.last_edit_coalesced());
+    }
+
+    #[test]
+    fn history_disabled() {
+        let mut table = PieceTable::from_readonly("");
+        table.set_commit_strategy(Box::new(PerWord));
+        type_text(&mut table, "ab");
+        assert!(!table.last_edit_coalesced());
+    }
+}
+
+mod inserted_text {
+    use super::*;
+
+    /// Never merges edits, records the inserted text it is asked about
+    struct Recorder(Arc<Mutex<Vec<String>>>);
+
+    impl CommitStrategy for Recorder {
+        fn coalesce(&mut self, _previous: &EditDelta, _edit: &EditDelta, inserted: &str) -> bool {
+            self.0.lock().unwrap().push(String::from(inserted));
+            false
+        }
+    }
+
+    #[test]
+    fn passed_to_strategy() {
+        let inserted = Arc::new(Mutex::new(Vec::new()));
+        let mut table = PieceTable::from("Hello, World!");
+        table.set_commit_strategy(Box::new(Recorder(Arc::clone(&inserted))));
+
+        table.insert(5, "!");
+        table.insert(6, "?");
+        table.replace_range(0..5, "Bye");
+        table.remove(0, 3);
+        table.append_all(["ab", "cd"]);
+        assert_eq!(*inserted.lock().unwrap(), vec!["?", "Bye", "", "abcd"]);
+    }
+}
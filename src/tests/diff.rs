@@ -0,0 +1,123 @@
+use crate::piece_table::{diff::LineDiff, PieceTable};
+
+/// Rebuilds both line lists from `diff`, checking the line indices on the way,
+/// and returns them with the number of unchanged lines
+fn replay(diff: &[LineDiff], old: &[&str], new: &[&str]) -> (Vec<String>, Vec<String>, usize) {
+    let (mut old_lines, mut new_lines, mut unchanged) = (Vec::new(), Vec::new(), 0);
+    for change in diff {
+        match change {
+            LineDiff::Added(idx, line) => {
+                assert_eq!(*idx, new_lines.len());
+                new_lines.push(line.clone());
+            }
+            LineDiff::Removed(idx, line) => {
+                assert_eq!(*idx, old_lines.len());
+                old_lines.push(line.clone());
+            }
+            LineDiff::Unchanged => {
+                let line = old[old_lines.len()];
+                assert_eq!(line, new[new_lines.len()]);
+                old_lines.push(String::from(line));
+                new_lines.push(String::from(line));
+                unchanged += 1;
+            }
+        }
+    }
+
+    (old_lines, new_lines, unchanged)
+}
+
+/// Length of the longest common subsequence with a full table
+fn lcs_len(old: &[&str], new: &[&str]) -> usize {
+    let mut table = vec![vec![0; new.len() + 1]; old.len() + 1];
+    for i in 0..old.len() {
+        for j in 0..new.len() {
+            table[i + 1][j + 1] = if old[i] == new[j] {
+                table[i][j] + 1
+            } else {
+                table[i][j + 1].max(table[i + 1][j])
+            };
+        }
+    }
+    table[old.len()][new.len()]
+}
+
+fn check(old: &str, new: &str) -> Vec<LineDiff> {
+    let diff = PieceTable::from(old).diff_lines(&PieceTable::from(new));
+
+    let old_lines: Vec<&str> = old.split('\n').collect();
+    let new_lines: Vec<&str> = new.split('\n').collect();
+    let (replayed_old, replayed_new, unchanged) = replay(&diff, &old_lines, &new_lines);
+    assert_eq!(replayed_old, old_lines);
+    assert_eq!(replayed_new, new_lines);
+    assert_eq!(unchanged, lcs_len(&old_lines, &new_lines));
+
+    diff
+}
+
+mod diff_lines {
+    use super::*;
+
+    #[test]
+    fn identical() {
+        let diff = check("a\nb\nc", "a\nb\nc");
+        assert_eq!(diff, vec![LineDiff::Unchanged; 3]);
+    }
+
+    #[test]
+    fn added_and_removed() {
+        let diff = check("a\nb\nc", "a\nc\nd");
+        assert_eq!(
+            diff,
+            vec![
+                LineDiff::Unchanged,
+                LineDiff::Removed(1, String::from("b")),
+                LineDiff::Unchanged,
+                LineDiff::Added(2, String::from("d")),
+            ]
+        );
+    }
+
+    #[test]
+    fn completely_different() {
+        let diff = check("a\nb", "c");
+        assert_eq!(
+            diff,
+            vec![
+                LineDiff::Removed(0, String::from("a")),
+                LineDiff::Removed(1, String::from("b")),
+                LineDiff::Added(0, String::from("c")),
+            ]
+        );
+    }
+
+    #[test]
+    fn edited_table() {
+        let old = PieceTable::from("fn main() {\n}");
+        let mut new = PieceTable::from("fn main() {\n}");
+        new.insert(12, "    run();\n");
+
+        assert_eq!(
+            old.diff_lines(&new),
+            vec![
+                LineDiff::Unchanged,
+                LineDiff::Added(1, String::from("    run();")),
+                LineDiff::Unchanged,
+            ]
+        );
+    }
+
+    #[test]
+    fn minimal() {
+        check("a\nb\nc\na\nb\nb\na", "c\nb\na\nb\na\nc");
+        check("x\ny\nx\ny\nx", "y\nx\ny\nx\ny");
+        check("1\n2\n3\n4\n5\n6\n7\n8", "8\n1\n3\n2\n5\n4\n7\n6");
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(check("", ""), vec![LineDiff::Unchanged]);
+        check("", "a\nb");
+        check("a\nb", "");
+    }
+}
@@ -1,3 +1,5 @@
+use std::ops::Not;
+
 use crate::history::{
     change::{Change, ChangeType},
     commit::Commit,
@@ -5,6 +7,7 @@ use crate::history::{
     History,
 };
 use crate::piece_table::{
+    edit_delta::EditDelta,
     piece::{Piece, PieceSource},
     PieceTable,
 };
@@ -28,6 +31,7 @@ fn create_entry(
         next,
         hot_path,
         commit,
+        amends: 0,
     }
 }
 
@@ -36,13 +40,31 @@ mod save {
     use super::*;
 
     #[test]
-    #[should_panic]
     fn empty_list() {
+        let changes = vec![
+            create_entry(None, vec![1], None, Commit::new()),
+            create_entry(Some(0), Vec::new(), None, Commit::new()),
+        ];
+
         let mut history = History {
             changes: Vec::new(),
             head: 0,
         };
         history.save(Commit::new());
+        validate_history(&history, &changes, 1);
+        assert!(history.is_valid());
+    }
+
+    #[test]
+    fn empty_list_table() {
+        let mut table = PieceTable::from("Hello");
+        table.history.changes.clear();
+        table.append(", World!");
+        assert_eq!(table.validate(), Ok(()));
+
+        assert!(table.undo());
+        assert_eq!(table.to_string(), "Hello");
+        assert!(!table.undo());
     }
 
     #[test]
@@ -56,6 +78,7 @@ mod save {
     #[test]
     fn append() {
         let mut commit = Commit::new();
+        commit.add_edit(EditDelta::insertion(0, "Hello, World!".len()));
         commit.add_change(
             0,
             Piece::new(PieceSource::Addition, 0, "Hello, World!".len()),
@@ -75,6 +98,7 @@ mod save {
     #[test]
     fn front() {
         let mut commit = Commit::new();
+        commit.add_edit(EditDelta::insertion(0, "Hello, ".len()));
         commit.add_change(
             0,
             Piece::new(PieceSource::Addition, 0, "Hello, ".len()),
@@ -94,6 +118,7 @@ mod save {
     #[test]
     fn middle() {
         let mut commit = Commit::new();
+        commit.add_edit(EditDelta::insertion("Hello".len(), ", ".len()));
         commit.add_change(
             0,
             Piece::new(PieceSource::Original, 0, "HelloWorld!".len()),
@@ -128,12 +153,14 @@ mod save {
     #[test]
     fn between_pieces() {
         let mut commit1 = Commit::new();
+        commit1.add_edit(EditDelta::insertion("Hello".len(), "World!".len()));
         commit1.add_change(
             1,
             Piece::new(PieceSource::Addition, 0, "World!".len()),
             ChangeType::Insertion,
         );
         let mut commit2 = Commit::new();
+        commit2.add_edit(EditDelta::insertion("Hello".len(), ", ".len()));
         commit2.add_change(
             1,
             Piece::new(PieceSource::Addition, "World!".len(), ", ".len()),
@@ -155,6 +182,7 @@ mod save {
     #[test]
     fn complex() {
         let mut commit1 = Commit::new();
+        commit1.add_edit(EditDelta::insertion(2, "llor".len()));
         commit1.add_change(
             0,
             Piece::new(PieceSource::Original, 0, "Held!".len()),
@@ -176,6 +204,7 @@ mod save {
             ChangeType::Insertion,
         );
         let mut commit2 = Commit::new();
+        commit2.add_edit(EditDelta::insertion(4, "o, W".len()));
         commit2.add_change(
             1,
             Piece::new(PieceSource::Addition, 0, "llor".len()),
@@ -269,6 +298,9 @@ mod undo {
                 Piece::new(PieceSource::Original, 10, 17),
                 ChangeType::Insertion,
             )],
+            edit: None,
+            tracked: true,
+            appends: 0,
         });
 
         let mut table = PieceTable::from("Hello, World!");
@@ -286,6 +318,9 @@ mod undo {
                 Piece::new(PieceSource::Addition, 20, 22),
                 ChangeType::Insertion,
             )],
+            edit: None,
+            tracked: true,
+            appends: 0,
         });
 
         let mut table = PieceTable::from("Hello, World!");
@@ -303,6 +338,9 @@ mod undo {
                 Piece::new(PieceSource::Original, 0, 0),
                 ChangeType::Deletion,
             )],
+            edit: None,
+            tracked: true,
+            appends: 0,
         });
 
         let mut table = PieceTable::from("");
@@ -320,6 +358,9 @@ mod undo {
                 Piece::new(PieceSource::Addition, 0, 0),
                 ChangeType::Insertion,
             )],
+            edit: None,
+            tracked: true,
+            appends: 0,
         });
 
         let mut table = PieceTable::from("");
@@ -331,12 +372,12 @@ mod undo {
     fn empty() {
         let history = History::new(Commit::new());
         let mut table = PieceTable::from("");
-        table.undo();
+        assert!(!table.undo());
 
         validate_history(&table.history, &history.changes, 0);
         validate_table(&table, "", String::new(), &Vec::new(), String::new());
 
-        table.undo();
+        assert!(!table.undo());
 
         validate_history(&table.history, &history.changes, 0);
         validate_table(&table, "", String::new(), &Vec::new(), String::new());
@@ -348,7 +389,7 @@ mod undo {
         let pieces = vec![Piece::new(PieceSource::Original, 0, "Hello, World!".len())];
 
         let mut table = PieceTable::from("Hello, World!");
-        table.undo();
+        assert!(!table.undo());
 
         validate_history(&table.history, &history.changes, 0);
         validate_table(
@@ -359,7 +400,7 @@ mod undo {
             "Hello, World!",
         );
 
-        table.undo();
+        assert!(!table.undo());
 
         validate_history(&table.history, &history.changes, 0);
         validate_table(
@@ -374,6 +415,7 @@ mod undo {
     #[test]
     fn middle_once() {
         let mut commit = Commit::new();
+        commit.add_edit(EditDelta::insertion("Hello".len(), ", ".len()));
         commit.add_change(
             0,
             Piece::new(PieceSource::Original, 0, "HelloWorld!".len()),
@@ -403,7 +445,7 @@ mod undo {
 
         let mut table = PieceTable::from("HelloWorld!");
         table.insert("Hello".len(), ", ");
-        table.undo();
+        assert!(table.undo());
 
         validate_history(&table.history, &changes, 0);
         validate_table(&table, "HelloWorld!", ", ", &pieces, "HelloWorld!");
@@ -412,12 +454,14 @@ mod undo {
     #[test]
     fn between_pieces() {
         let mut commit1 = Commit::new();
+        commit1.add_edit(EditDelta::insertion("Hello".len(), "World!".len()));
         commit1.add_change(
             1,
             Piece::new(PieceSource::Addition, 0, "World!".len()),
             ChangeType::Insertion,
         );
         let mut commit2 = Commit::new();
+        commit2.add_edit(EditDelta::insertion("Hello".len(), ", ".len()));
         commit2.add_change(
             1,
             Piece::new(PieceSource::Addition, "World!".len(), ", ".len()),
@@ -511,6 +555,9 @@ mod hot_redo {
                 Piece::new(PieceSource::Original, 10, 17),
                 ChangeType::Insertion,
             )],
+            edit: None,
+            tracked: true,
+            appends: 0,
         });
         history.head = 0;
 
@@ -531,6 +578,9 @@ mod hot_redo {
                 Piece::new(PieceSource::Addition, 20, 22),
                 ChangeType::Insertion,
             )],
+            edit: None,
+            tracked: true,
+            appends: 0,
         });
         history.head = 0;
 
@@ -551,6 +601,9 @@ mod hot_redo {
                 Piece::new(PieceSource::Original, 0, 0),
                 ChangeType::Deletion,
             )],
+            edit: None,
+            tracked: true,
+            appends: 0,
         });
         history.head = 0;
 
@@ -571,6 +624,9 @@ mod hot_redo {
                 Piece::new(PieceSource::Addition, 0, 0),
                 ChangeType::Insertion,
             )],
+            edit: None,
+            tracked: true,
+            appends: 0,
         });
         history.head = 0;
 
@@ -583,13 +639,13 @@ mod hot_redo {
     fn empty() {
         let history = History::new(Commit::new());
         let mut table = PieceTable::from("");
-        table.hot_redo();
+        assert!(!table.hot_redo());
 
         validate_history(&table.history, &history.changes, 0);
         validate_table(&table, "", String::new(), &Vec::new(), String::new());
 
-        table.undo();
-        table.hot_redo();
+        assert!(!table.undo());
+        assert!(!table.hot_redo());
 
         validate_history(&table.history, &history.changes, 0);
         validate_table(&table, "", String::new(), &Vec::new(), String::new());
@@ -601,7 +657,7 @@ mod hot_redo {
         let pieces = vec![Piece::new(PieceSource::Original, 0, "Hello, World!".len())];
 
         let mut table = PieceTable::from("Hello, World!");
-        table.hot_redo();
+        assert!(!table.hot_redo());
 
         validate_history(&table.history, &history.changes, 0);
         validate_table(
@@ -612,8 +668,8 @@ mod hot_redo {
             "Hello, World!",
         );
 
-        table.undo();
-        table.hot_redo();
+        assert!(!table.undo());
+        assert!(!table.hot_redo());
 
         validate_history(&table.history, &history.changes, 0);
         validate_table(
@@ -628,6 +684,7 @@ mod hot_redo {
     #[test]
     fn middle_once() {
         let mut commit = Commit::new();
+        commit.add_edit(EditDelta::insertion("Hello".len(), ", ".len()));
         commit.add_change(
             0,
             Piece::new(PieceSource::Original, 0, "HelloWorld!".len()),
@@ -661,8 +718,8 @@ mod hot_redo {
 
         let mut table = PieceTable::from("HelloWorld!");
         table.insert("Hello".len(), ", ");
-        table.undo();
-        table.hot_redo();
+        assert!(table.undo());
+        assert!(table.hot_redo());
 
         validate_history(&table.history, &changes, 1);
         validate_table(&table, "HelloWorld!", ", ", &pieces, "Hello, World!");
@@ -671,12 +728,14 @@ mod hot_redo {
     #[test]
     fn between_pieces() {
         let mut commit1 = Commit::new();
+        commit1.add_edit(EditDelta::insertion("Hello".len(), "World!".len()));
         commit1.add_change(
             1,
             Piece::new(PieceSource::Addition, 0, "World!".len()),
             ChangeType::Insertion,
         );
         let mut commit2 = Commit::new();
+        commit2.add_edit(EditDelta::insertion("Hello".len(), ", ".len()));
         commit2.add_change(
             1,
             Piece::new(PieceSource::Addition, "World!".len(), ", ".len()),
@@ -718,3 +777,739 @@ mod hot_redo {
         validate_table(&table, "Hello", "World!, ", &pieces, "Hello, World!");
     }
 }
+
+mod redo {
+    use super::*;
+
+    #[test]
+    fn follows_hot_path() {
+        let mut history = History::new(Commit::new());
+        history.save(Commit::new());
+        history.head = 0;
+        history.save(Commit::new());
+        history.undo();
+        history.changes[0].hot_path = Some(1);
+
+        assert!(history.redo().is_some());
+        assert_eq!(history.head, 1);
+    }
+
+    #[test]
+    fn newest_branch_without_hot_path() {
+        let mut history = History::new(Commit::new());
+        history.save(Commit::new());
+        history.head = 0;
+        history.save(Commit::new());
+        history.head = 0;
+        assert_eq!(history.changes[0].hot_path, None);
+
+        assert!(history.redo().is_some());
+        assert_eq!(history.head, 2);
+        assert_eq!(history.changes[0].hot_path, Some(2));
+    }
+
+    #[test]
+    fn at_tip() {
+        let mut history = History::new(Commit::new());
+        history.save(Commit::new());
+        assert!(history.redo().is_none());
+        assert_eq!(history.head, 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn head_out_of_bounds() {
+        let mut history = History::new(Commit::new());
+        history.head = 5;
+        history.redo();
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut table = PieceTable::from("Held!");
+        table.insert(2, "llor");
+        table.insert(4, "o, W");
+        table.remove(0, 3);
+        let pieces = table.pieces.iter().copied().collect::<Vec<_>>();
+
+        while table.undo() {}
+        assert_eq!(table.to_string(), "Held!");
+
+        while table.redo() {}
+        validate_table(&table, "Held!", "lloro, W", &pieces, "lo, World!");
+    }
+
+    #[test]
+    fn undone_edit_without_hot_path() {
+        let mut table = PieceTable::from("Hello");
+        table.append(", World!");
+        table.undo();
+        table.history.changes[0].hot_path = None;
+
+        assert!(table.redo());
+        assert_eq!(table.to_string(), "Hello, World!");
+        assert_eq!(table.last_edit(), Some(EditDelta::new(5, 5, 13)));
+    }
+
+    #[test]
+    fn nothing_to_redo() {
+        let mut table = PieceTable::from("Hello");
+        assert!(table.redo().not());
+        table.append("!");
+        assert!(table.redo().not());
+        assert_eq!(table.to_string(), "Hello!");
+    }
+}
+
+mod can_undo_redo {
+    use super::*;
+
+    #[test]
+    fn fresh_table() {
+        let table = PieceTable::from("Hello");
+        assert!(table.can_undo().not());
+        assert!(table.can_redo().not());
+    }
+
+    #[test]
+    fn follows_head() {
+        let mut table = PieceTable::from("Hello");
+        table.append(", World");
+        table.append("!");
+        assert!(table.can_undo());
+        assert!(table.can_redo().not());
+
+        table.undo();
+        assert!(table.can_undo());
+        assert!(table.can_redo());
+
+        table.undo();
+        assert!(table.can_undo().not());
+        assert!(table.can_redo());
+
+        table.redo();
+        table.redo();
+        assert!(table.can_redo().not());
+        assert_eq!(table.len(), "Hello, World!".len());
+    }
+
+    #[test]
+    fn branch_without_hot_path() {
+        let mut table = PieceTable::from("Hello");
+        table.append("!");
+        table.undo();
+        table.history.changes[0].hot_path = None;
+        assert!(table.can_redo());
+        assert!(table.redo());
+    }
+
+    #[test]
+    fn history_reset() {
+        let mut table = PieceTable::from("Hello");
+        table.append("!");
+        table.undo();
+        table.reset_history();
+        assert!(table.can_undo().not());
+        assert!(table.can_redo().not());
+    }
+
+    #[test]
+    fn empty_history() {
+        let mut table = PieceTable::from("Hello");
+        table.history = History {
+            changes: Vec::new(),
+            head: 0,
+        };
+        assert!(table.can_undo().not());
+        assert!(table.can_redo().not());
+    }
+}
+
+mod is_valid {
+    use super::*;
+
+    #[test]
+    fn new() {
+        assert!(History::new(Commit::new()).is_valid());
+    }
+
+    #[test]
+    fn after_edits() {
+        let mut table = PieceTable::from("Hello");
+        table.append(", World!");
+        table.undo();
+        table.insert(0, "Oh, ");
+        assert!(table.history.is_valid());
+    }
+
+    #[test]
+    fn empty_list() {
+        let mut history = History::new(Commit::new());
+        history.changes.clear();
+        assert!(!history.is_valid());
+    }
+
+    #[test]
+    fn head_out_of_bounds() {
+        let mut history = History::new(Commit::new());
+        history.head = 5;
+        assert!(!history.is_valid());
+    }
+
+    #[test]
+    fn dangling_next() {
+        let mut history = History::new(Commit::new());
+        history.save(Commit::new());
+        history.changes[0].next.push(7);
+        assert!(!history.is_valid());
+    }
+
+    #[test]
+    fn unlinked_previous() {
+        let mut history = History::new(Commit::new());
+        history.save(Commit::new());
+        history.changes[0].next.clear();
+        assert!(!history.is_valid());
+    }
+
+    #[test]
+    fn hot_path_not_in_next() {
+        let mut history = History::new(Commit::new());
+        history.changes[0].hot_path = Some(0);
+        assert!(!history.is_valid());
+    }
+}
+
+mod batch_changes {
+    use super::*;
+
+    fn get_fragmented_table() -> PieceTable {
+        let mut table = PieceTable::from("abcdefgh");
+        for pos in (1..8).rev() {
+            table.insert(pos, "-");
+        }
+        table
+    }
+
+    /// Addition buffer of `get_fragmented_table`, interned insertions share one `-`
+    fn fragment_addition() -> &'static str {
+        if cfg!(feature = "intern-additions") {
+            "-"
+        } else {
+            "-------"
+        }
+    }
+
+    #[test]
+    fn undo_removal_of_many_pieces() {
+        let mut table = get_fragmented_table();
+        let pieces = table.pieces.iter().copied().collect::<Vec<_>>();
+        assert_eq!(pieces.len(), 15);
+
+        table.remove(3, 9);
+        assert_eq!(table.to_string(), "a-bg-h");
+        assert!(table.undo());
+        validate_table(
+            &table,
+            "abcdefgh",
+            fragment_addition(),
+            &pieces,
+            "a-b-c-d-e-f-g-h",
+        );
+    }
+
+    #[test]
+    fn redo_removal_of_many_pieces() {
+        let mut table = get_fragmented_table();
+        table.remove(3, 9);
+        let pieces = table.pieces.iter().copied().collect::<Vec<_>>();
+
+        table.undo();
+        assert!(table.hot_redo());
+        validate_table(&table, "abcdefgh", fragment_addition(), &pieces, "a-bg-h");
+    }
+
+    #[test]
+    fn mixed_runs() {
+        let mut table = get_fragmented_table();
+        table.replace_all_with("-", |pos| pos.to_string());
+        assert_eq!(table.to_string(), "a1b3c5d7e9f11g13h");
+
+        table.undo();
+        assert_eq!(table.to_string(), "a-b-c-d-e-f-g-h");
+        assert_eq!(table.validate(), Ok(()));
+        table.hot_redo();
+        assert_eq!(table.to_string(), "a1b3c5d7e9f11g13h");
+        assert_eq!(table.validate(), Ok(()));
+    }
+}
+
+#[doc(hidden)]
+mod redo_branch {
+    use super::*;
+
+    fn get_forked_table() -> PieceTable {
+        // Root with the branches ", World!" and ", Moon!"
+        let mut table = PieceTable::from("Hello");
+        table.append(", World!");
+        table.undo();
+        table.append(", Moon!");
+        table.undo();
+        table
+    }
+
+    #[test]
+    fn selects_branch() {
+        let mut table = get_forked_table();
+        assert!(table.redo_branch(0));
+        assert_eq!(table.to_string(), "Hello, World!");
+
+        table.undo();
+        assert!(table.redo_branch(1));
+        assert_eq!(table.to_string(), "Hello, Moon!");
+    }
+
+    #[test]
+    fn sets_hot_path() {
+        let mut table = get_forked_table();
+        table.redo_branch(0);
+        table.undo();
+        table.redo_branch(0);
+        assert_eq!(table.history.changes[0].hot_path, Some(1));
+        table.undo();
+        assert!(table.hot_redo());
+        assert_eq!(table.to_string(), "Hello, World!");
+    }
+
+    #[test]
+    fn missing_branch() {
+        let mut table = get_forked_table();
+        assert!(!table.redo_branch(2));
+        assert_eq!(table.to_string(), "Hello");
+        assert_eq!(table.history.head, 0);
+    }
+
+    #[test]
+    fn at_tip() {
+        let mut table = PieceTable::from("Hello");
+        table.append("!");
+        assert!(!table.redo_branch(0));
+        assert_eq!(table.to_string(), "Hello!");
+    }
+}
+
+#[doc(hidden)]
+mod is_modified_since {
+    use super::*;
+    use crate::piece_table::commit_strategy::PerWord;
+
+    #[test]
+    fn unmodified() {
+        let table = PieceTable::from("Hello");
+        assert!(table.is_modified_since(table.version()).not());
+    }
+
+    #[test]
+    fn edit() {
+        let mut table = PieceTable::from("Hello");
+        let version = table.version();
+        table.append(", World!");
+        assert!(table.is_modified_since(version));
+    }
+
+    #[test]
+    fn undo_back_to_version() {
+        let mut table = PieceTable::from("Hello");
+        table.append(",");
+        let version = table.version();
+        table.append(" World!");
+        table.remove(0, 1);
+        table.undo();
+        table.undo();
+        assert!(table.is_modified_since(version).not());
+
+        table.undo();
+        assert!(table.is_modified_since(version));
+        table.hot_redo();
+        assert!(table.is_modified_since(version).not());
+    }
+
+    #[test]
+    fn other_branch() {
+        let mut table = PieceTable::from("Hello");
+        table.append(", World!");
+        let version = table.version();
+        table.undo();
+        table.append(", World!");
+        assert_eq!(table.to_string(), "Hello, World!");
+        assert!(table.is_modified_since(version));
+    }
+
+    #[test]
+    fn coalesced_edit() {
+        let mut table = PieceTable::from("");
+        table.set_commit_strategy(Box::new(PerWord));
+        table.append("H");
+        let version = table.version();
+        table.append("i");
+        assert!(table.last_edit_coalesced());
+        assert!(table.is_modified_since(version));
+    }
+
+    #[test]
+    fn independent_versions() {
+        let mut table = PieceTable::from("Hello");
+        let saved = table.version();
+        table.append(",");
+        let autosaved = table.version();
+        table.append(" World!");
+        assert!(table.is_modified_since(saved));
+        assert!(table.is_modified_since(autosaved));
+
+        table.undo();
+        assert!(table.is_modified_since(saved));
+        assert!(table.is_modified_since(autosaved).not());
+    }
+
+    #[test]
+    fn reset_history() {
+        let mut table = PieceTable::from("Hello");
+        let version = table.version();
+        table.append(", World!");
+        table.reset_history();
+        assert!(table.is_modified_since(version));
+    }
+
+    #[test]
+    fn disabled_history() {
+        let mut table = PieceTable::from("Hello");
+        table.disable_history();
+        let version = table.version();
+        table.append(", World!");
+        assert!(table.is_modified_since(version));
+        table.undo();
+        assert!(table.is_modified_since(version));
+    }
+}
+
+#[doc(hidden)]
+mod append_runs {
+    use super::*;
+
+    #[test]
+    fn single_entry_and_piece() {
+        let mut table = PieceTable::from("Log:");
+        table.set_append_runs(Some(usize::MAX));
+        table.append(" a");
+        table.append(" b");
+        table.append(" c");
+
+        let pieces = vec![
+            Piece::new(PieceSource::Original, 0, 4),
+            Piece::new(PieceSource::Addition, 0, 6),
+        ];
+        validate_table(&table, "Log:", " a b c", &pieces, "Log: a b c");
+        assert_eq!(table.history.changes.len(), 2);
+        assert_eq!(table.history.changes[1].commit.appends, 3);
+        assert!(table.last_edit_coalesced());
+
+        table.undo();
+        assert_eq!(table.to_string(), "Log:");
+        table.hot_redo();
+        assert_eq!(table.to_string(), "Log: a b c");
+    }
+
+    #[test]
+    fn limited_step() {
+        let mut table = PieceTable::from("");
+        table.set_append_runs(Some(2));
+        for c in ["a", "b", "c", "d", "e"] {
+            table.append(c);
+        }
+        assert_eq!(table.history.changes.len(), 4);
+
+        table.undo();
+        assert_eq!(table.to_string(), "abcd");
+        table.undo();
+        assert_eq!(table.to_string(), "ab");
+        table.undo();
+        assert_eq!(table.to_string(), "");
+    }
+
+    #[test]
+    fn interrupted_by_other_edit() {
+        let mut table = PieceTable::from("Log:");
+        table.set_append_runs(Some(usize::MAX));
+        table.append(" a");
+        table.insert(0, "# ");
+        table.append(" b");
+        table.append(" c");
+        assert_eq!(table.history.changes.len(), 4);
+
+        table.undo();
+        assert_eq!(table.to_string(), "# Log: a");
+        table.undo();
+        assert_eq!(table.to_string(), "Log: a");
+    }
+
+    #[test]
+    fn not_after_undo() {
+        let mut table = PieceTable::from("Log:");
+        table.set_append_runs(Some(usize::MAX));
+        table.append(" a");
+        table.append(" b");
+        table.undo();
+        table.append(" c");
+        table.append(" d");
+        assert_eq!(table.history.changes.len(), 3);
+        assert_eq!(table.to_string(), "Log: c d");
+
+        table.undo();
+        assert!(table.redo_branch(0));
+        assert_eq!(table.to_string(), "Log: a b");
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        let mut table = PieceTable::from("");
+        table.append("a");
+        table.append("b");
+        assert_eq!(table.history.changes.len(), 3);
+        assert_eq!(table.pieces.len(), 1);
+    }
+
+    #[test]
+    fn modifies_version() {
+        let mut table = PieceTable::from("");
+        table.set_append_runs(Some(usize::MAX));
+        table.append("a");
+        let version = table.version();
+        table.append("b");
+        assert!(table.is_modified_since(version));
+    }
+
+    #[test]
+    #[should_panic(expected = "Append runs must hold at least one append")]
+    fn zero_run() {
+        let mut table = PieceTable::from("");
+        table.set_append_runs(Some(0));
+    }
+}
+
+#[doc(hidden)]
+mod coalesce_pieces {
+    use super::*;
+
+    fn get_fragmented_table() -> PieceTable {
+        // Unlike `append`, `append_all` adds a piece instead of extending the last one
+        let mut table = PieceTable::from("Hello");
+        table.append_all([","]);
+        table.append_all([" World"]);
+        table.append_all(["!"]);
+        table
+    }
+
+    #[test]
+    fn merges_contiguous_pieces() {
+        let mut table = get_fragmented_table();
+        table.insert(0, ">");
+        assert_eq!(table.coalesce_pieces(), 2);
+
+        let pieces = vec![
+            Piece::new(PieceSource::Addition, 8, 1),
+            Piece::new(PieceSource::Original, 0, 5),
+            Piece::new(PieceSource::Addition, 0, 8),
+        ];
+        validate_table(&table, "Hello", ", World!>", &pieces, ">Hello, World!");
+        assert_eq!(table.coalesce_pieces(), 0);
+    }
+
+    #[test]
+    fn undo_after_compaction() {
+        let mut table = get_fragmented_table();
+        table.coalesce_pieces();
+
+        assert!(table.undo());
+        let pieces = vec![
+            Piece::new(PieceSource::Original, 0, 5),
+            Piece::new(PieceSource::Addition, 0, 1),
+            Piece::new(PieceSource::Addition, 1, 6),
+        ];
+        validate_table(&table, "Hello", ", World!", &pieces, "Hello, World");
+
+        assert!(table.undo());
+        assert!(table.undo());
+        assert_eq!(table.to_string(), "Hello");
+        assert!(table.undo().not());
+
+        while table.hot_redo() {}
+        let pieces = vec![
+            Piece::new(PieceSource::Original, 0, 5),
+            Piece::new(PieceSource::Addition, 0, 8),
+        ];
+        validate_table(&table, "Hello", ", World!", &pieces, "Hello, World!");
+        assert_eq!(table.validate(), Ok(()));
+    }
+
+    #[test]
+    fn not_an_edit() {
+        let mut table = get_fragmented_table();
+        let version = table.version();
+        table.coalesce_pieces();
+        assert_eq!(table.history.changes.len(), 4);
+        assert!(table.is_modified_since(version).not());
+    }
+
+    #[test]
+    fn discards_redo_steps() {
+        let mut table = get_fragmented_table();
+        table.append("?");
+        table.undo();
+        assert_eq!(table.coalesce_pieces(), 2);
+
+        assert!(table.undo().not());
+        assert!(table.hot_redo().not());
+        assert_eq!(table.to_string(), "Hello, World!");
+        assert_eq!(table.validate(), Ok(()));
+    }
+
+    #[test]
+    fn at_root() {
+        let mut table = PieceTable::from_parts(
+            "Hello",
+            String::new(),
+            [(PieceSource::Original, 0..2), (PieceSource::Original, 2..5)],
+        )
+        .unwrap();
+        assert_eq!(table.coalesce_pieces(), 1);
+        assert_eq!(table.history.changes.len(), 1);
+        assert_eq!(table.to_string(), "Hello");
+    }
+}
+
+#[doc(hidden)]
+mod stress {
+    use super::*;
+
+    /// Expected state of a history entry
+    struct Node {
+        text: String,
+        parent: Option<usize>,
+        children: Vec<usize>,
+        hot: Option<usize>,
+    }
+
+    /// Small xorshift generator so failures are reproducible without extra dependencies
+    struct Rng(u64);
+
+    impl Rng {
+        fn below(&mut self, n: usize) -> usize {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 % n as u64) as usize
+        }
+    }
+
+    fn run(mut table: PieceTable, seed: u64, steps: usize) {
+        let mut rng = Rng(seed);
+        let mut nodes = vec![Node {
+            text: table.to_string(),
+            parent: None,
+            children: Vec::new(),
+            hot: None,
+        }];
+        let mut head = 0;
+
+        for step in 0..steps {
+            let text = nodes[head].text.clone();
+            let op = rng.below(5);
+            let applied = match op {
+                0 | 1 => {
+                    let pos = rng.below(text.len() + 1);
+                    let insert = ["a", "bc", "-", "xyz"][rng.below(4)];
+                    table.insert(pos, insert);
+
+                    let mut new_text = text.clone();
+                    new_text.insert_str(pos, insert);
+                    Some(new_text)
+                }
+                2 if text.is_empty().not() => {
+                    let pos = rng.below(text.len());
+                    let n = 1 + rng.below((text.len() - pos).min(4));
+                    table.remove(pos, n);
+
+                    let mut new_text = text.clone();
+                    new_text.replace_range(pos..pos + n, "");
+                    Some(new_text)
+                }
+                _ => None,
+            };
+
+            if let Some(new_text) = applied {
+                let node = nodes.len();
+                nodes.push(Node {
+                    text: new_text,
+                    parent: Some(head),
+                    children: Vec::new(),
+                    hot: None,
+                });
+                nodes[head].children.push(node);
+                head = node;
+            } else {
+                match op {
+                    2 | 3 => {
+                        let expected = nodes[head].parent;
+                        assert_eq!(table.undo(), expected.is_some(), "step {step}: undo");
+                        if let Some(parent) = expected {
+                            nodes[parent].hot = Some(head);
+                            head = parent;
+                        }
+                    }
+                    _ if rng.below(2) == 0 => {
+                        let expected = nodes[head].hot;
+                        assert_eq!(table.hot_redo(), expected.is_some(), "step {step}: redo");
+                        if let Some(hot) = expected {
+                            head = hot;
+                        }
+                    }
+                    _ => {
+                        let branch = rng.below(nodes[head].children.len() + 1);
+                        let expected = nodes[head].children.get(branch).copied();
+                        assert_eq!(
+                            table.redo_branch(branch),
+                            expected.is_some(),
+                            "step {step}: redo branch {branch}"
+                        );
+                        if let Some(child) = expected {
+                            nodes[head].hot = Some(child);
+                            head = child;
+                        }
+                    }
+                }
+            }
+
+            assert_eq!(table.validate(), Ok(()), "step {step}");
+            assert_eq!(table.history.head, head, "step {step}");
+            assert_eq!(table.to_string(), nodes[head].text, "step {step}");
+        }
+    }
+
+    #[test]
+    fn random_walks() {
+        for seed in 1..=20u64 {
+            let table = PieceTable::from("Hello, World!");
+            run(table, seed.wrapping_mul(0x9e37_79b9_7f4a_7c15), 500);
+        }
+    }
+
+    #[cfg(feature = "inline-pieces")]
+    #[test]
+    fn random_walks_inline_pieces() {
+        for seed in 1..=20u64 {
+            let mut table = PieceTable::from("Hello, World!");
+            table.set_inline_pieces(true);
+            run(table, seed.wrapping_mul(0x9e37_79b9_7f4a_7c15), 500);
+        }
+    }
+}
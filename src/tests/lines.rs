@@ -0,0 +1,875 @@
+use crate::piece_table::{piece::PieceSource, PieceTable};
+
+mod line_count {
+    use super::*;
+
+    #[test]
+    fn empty() {
+        let table = PieceTable::from("");
+        assert_eq!(table.line_count(), 1);
+    }
+
+    #[test]
+    fn single_line() {
+        let table = PieceTable::from("Hello, World!");
+        assert_eq!(table.line_count(), 1);
+    }
+
+    #[test]
+    fn trailing_newline() {
+        let table = PieceTable::from("Hello,\nWorld!\n");
+        assert_eq!(table.line_count(), 3);
+    }
+
+    #[test]
+    fn crlf() {
+        let table = PieceTable::from("Hello,\r\nWorld!\r\n");
+        assert_eq!(table.line_count(), 3);
+    }
+
+    #[test]
+    fn lone_cr() {
+        let table = PieceTable::from("Hello,\rWorld!");
+        assert_eq!(table.line_count(), 2);
+    }
+
+    #[test]
+    fn crlf_split_across_pieces() {
+        let mut table = PieceTable::from("Hello,\r");
+        table.append("\nWorld!");
+        assert_eq!(table.pieces.len(), 2);
+        assert_eq!(table.line_count(), 2);
+    }
+
+    #[test]
+    fn crlf_split_by_insertion() {
+        let mut table = PieceTable::from("Hello,\r\nWorld!");
+        table.insert("Hello,\r".len(), "\r\n");
+        assert_eq!(table.to_string(), "Hello,\r\r\n\nWorld!");
+        assert_eq!(table.line_count(), 4);
+    }
+}
+
+mod lines_in {
+    use super::*;
+
+    fn get_table() -> PieceTable {
+        let mut table = PieceTable::from("Hello,\nWor");
+        table.append("ld!\r\nHow are");
+        table.append(" you?\n");
+        table
+    }
+
+    #[test]
+    fn mid_line_to_mid_line() {
+        let table = get_table();
+        let lines: Vec<String> = table.lines_in(3..21).collect();
+        assert_eq!(lines, vec!["lo,", "World!", "How ar"]);
+    }
+
+    #[test]
+    fn within_line() {
+        let table = get_table();
+        let lines: Vec<String> = table.lines_in(8..11).collect();
+        assert_eq!(lines, vec!["orl"]);
+    }
+
+    #[test]
+    fn full() {
+        let table = get_table();
+        let lines: Vec<String> = table.lines_in(0..table.len()).collect();
+        assert_eq!(lines, vec!["Hello,", "World!", "How are you?"]);
+    }
+
+    #[test]
+    fn starts_in_line_ending() {
+        let table = get_table();
+        let lines: Vec<String> = table.lines_in(14..17).collect();
+        assert_eq!(lines, vec!["Ho"]);
+    }
+
+    #[test]
+    fn starts_at_line_end() {
+        let table = get_table();
+        let lines: Vec<String> = table.lines_in(6..10).collect();
+        assert_eq!(lines, vec!["Wor"]);
+        let lines: Vec<String> = table.lines_in(13..17).collect();
+        assert_eq!(lines, vec!["Ho"]);
+    }
+
+    #[test]
+    fn starts_at_empty_line() {
+        let table = PieceTable::from("a\n\nb");
+        let lines: Vec<String> = table.lines_in(2..4).collect();
+        assert_eq!(lines, vec!["", "b"]);
+    }
+
+    #[test]
+    fn empty_lines() {
+        let table = PieceTable::from("a\n\n\nb");
+        let lines: Vec<String> = table.lines_in(0..5).collect();
+        assert_eq!(lines, vec!["a", "", "", "b"]);
+    }
+
+    #[test]
+    fn empty_range() {
+        let table = get_table();
+        assert_eq!(table.lines_in(3..3).count(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Range 0..50 out of bounds for length 28")]
+    fn out_of_bounds() {
+        let table = get_table();
+        table.lines_in(0..50).count();
+    }
+}
+
+mod collect_lines_into {
+    use super::*;
+
+    #[test]
+    fn across_pieces() {
+        let mut table = PieceTable::from("Held!\r");
+        table.insert(2, "llo,\nWor");
+        table.append("\nBye");
+
+        let mut lines = Vec::new();
+        table.collect_lines_into(&mut lines);
+        assert_eq!(lines, vec!["Hello,", "World!", "Bye"]);
+    }
+
+    #[test]
+    fn line_endings_at_piece_boundaries() {
+        // "a\r", "b\r\n", "\r", "\n\rc\n"
+        let pieces = [
+            (PieceSource::Original, 0..2),
+            (PieceSource::Addition, 0..3),
+            (PieceSource::Original, 2..3),
+            (PieceSource::Addition, 3..7),
+        ];
+        let table = PieceTable::from_parts("a\r\r", String::from("b\r\n\n\rc\n"), pieces).unwrap();
+        assert_eq!(table.chunks().count(), 4);
+
+        let mut lines = Vec::new();
+        table.collect_lines_into(&mut lines);
+        assert_eq!(lines, vec!["a", "b", "", "", "c", ""]);
+    }
+
+    #[test]
+    fn reuses_vec() {
+        let table = PieceTable::from("a\nb");
+        let mut lines = vec![String::from("x"); 5];
+        let capacity = lines.capacity();
+        table.collect_lines_into(&mut lines);
+        assert_eq!(lines, vec!["a", "b"]);
+        assert_eq!(lines.capacity(), capacity);
+    }
+
+    #[test]
+    fn grows_vec() {
+        let table = PieceTable::from("a\nb\n");
+        let mut lines = vec![String::from("x")];
+        table.collect_lines_into(&mut lines);
+        assert_eq!(lines, vec!["a", "b", ""]);
+    }
+
+    #[test]
+    fn empty() {
+        let table = PieceTable::from("");
+        let mut lines = vec![String::from("x"), String::from("y")];
+        table.collect_lines_into(&mut lines);
+        assert_eq!(lines, vec![""]);
+    }
+}
+
+mod line_byte_range {
+    use super::*;
+
+    #[test]
+    fn trailing_newline() {
+        let table = PieceTable::from("ab\ncd\n");
+        assert_eq!(table.line_byte_range(0), Some(0..2));
+        assert_eq!(table.line_byte_range(1), Some(3..5));
+        assert_eq!(table.line_byte_range(2), Some(6..6));
+        assert_eq!(table.line_byte_range(3), None);
+    }
+
+    #[test]
+    fn no_trailing_newline() {
+        let table = PieceTable::from("ab\ncd");
+        assert_eq!(table.line_byte_range(1), Some(3..5));
+        assert_eq!(table.line_byte_range(2), None);
+    }
+
+    #[test]
+    fn crlf_across_pieces() {
+        let mut table = PieceTable::from("ab\r");
+        table.append("\ncd");
+        assert_eq!(table.line_byte_range(0), Some(0..2));
+        assert_eq!(table.line_byte_range(1), Some(4..6));
+    }
+
+    #[test]
+    fn matches_line_ranges() {
+        let table = PieceTable::from("a\n\r\nb\rc\n");
+        let ranges: Vec<_> = (0..table.line_count())
+            .map(|n| table.line_byte_range(n).unwrap())
+            .collect();
+        assert_eq!(ranges, table.line_ranges().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn empty() {
+        let table = PieceTable::from("");
+        assert_eq!(table.line_byte_range(0), Some(0..0));
+        assert_eq!(table.line_byte_range(1), None);
+    }
+}
+
+mod byte_range_of_lines {
+    use super::*;
+
+    #[test]
+    fn includes_line_endings() {
+        let table = PieceTable::from("ab\ncd\r\nef");
+        assert_eq!(table.byte_range_of_lines(0..1), 0..3);
+        assert_eq!(table.byte_range_of_lines(1..2), 3..7);
+        assert_eq!(table.byte_range_of_lines(0..3), 0..9);
+    }
+
+    #[test]
+    fn clipped_at_end() {
+        let table = PieceTable::from("ab\ncd\n");
+        assert_eq!(table.byte_range_of_lines(1..5), 3..6);
+        assert_eq!(table.byte_range_of_lines(2..3), 6..6);
+    }
+
+    #[test]
+    fn crlf_across_pieces() {
+        let mut table = PieceTable::from("ab\r");
+        table.append("\ncd");
+        assert_eq!(table.byte_range_of_lines(0..1), 0..4);
+        assert_eq!(table.range_slice(table.byte_range_of_lines(1..2)), "cd");
+    }
+
+    #[test]
+    fn empty_range() {
+        let table = PieceTable::from("ab\ncd");
+        assert_eq!(table.byte_range_of_lines(1..1), 3..3);
+    }
+
+    #[test]
+    #[should_panic(expected = "Line 3 out of bounds for line count 3")]
+    fn start_out_of_bounds() {
+        let table = PieceTable::from("ab\ncd\n");
+        table.byte_range_of_lines(3..4);
+    }
+
+    #[test]
+    #[should_panic(expected = "Line range starts at 2 but ends at 1")]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn reversed() {
+        let table = PieceTable::from("ab\ncd\n");
+        table.byte_range_of_lines(2..1);
+    }
+}
+
+mod window {
+    use super::*;
+
+    #[test]
+    fn lines_across_pieces() {
+        let mut table = PieceTable::from("one\ntw");
+        table.append("o\r");
+        table.append("\nthree\n");
+        assert_eq!(table.window(0, 2), vec!["one", "two"]);
+        assert_eq!(table.window(1, 2), vec!["two", "three"]);
+    }
+
+    #[test]
+    fn line_endings_at_piece_boundaries() {
+        // "a\r", "b\r\n", "\r", "\n\rc\n"
+        let pieces = [
+            (PieceSource::Original, 0..2),
+            (PieceSource::Addition, 0..3),
+            (PieceSource::Original, 2..3),
+            (PieceSource::Addition, 3..7),
+        ];
+        let mut table =
+            PieceTable::from_parts("a\r\r", String::from("b\r\n\n\rc\n"), pieces).unwrap();
+        let lines = ["a", "b", "", "", "c", ""];
+
+        for indexed in [false, true] {
+            table.set_line_index(indexed);
+            for top_line in 0..lines.len() {
+                let end = lines.len().min(top_line + 3);
+                assert_eq!(table.window(top_line, 3), lines[top_line..end]);
+            }
+            assert!(table.window(lines.len(), 3).is_empty());
+        }
+    }
+
+    #[test]
+    fn clipped_at_end() {
+        let table = PieceTable::from("one\ntwo\n");
+        assert_eq!(table.window(1, 10), vec!["two", ""]);
+        assert_eq!(table.window(2, 10), vec![""]);
+    }
+
+    #[test]
+    fn out_of_range() {
+        let table = PieceTable::from("one\ntwo");
+        assert!(table.window(2, 1).is_empty());
+        assert!(table.window(usize::MAX, 1).is_empty());
+    }
+
+    #[test]
+    fn zero_height() {
+        let table = PieceTable::from("one\ntwo");
+        assert!(table.window(0, 0).is_empty());
+    }
+}
+
+mod line_col_of {
+    use super::*;
+
+    #[test]
+    fn line_endings() {
+        let table = PieceTable::from("ab\ncd\r\nef\rg");
+        assert_eq!(table.line_col_of(0), (0, 0));
+        assert_eq!(table.line_col_of(2), (0, 2));
+        assert_eq!(table.line_col_of(3), (1, 0));
+        assert_eq!(table.line_col_of(6), (1, 3));
+        assert_eq!(table.line_col_of(7), (2, 0));
+        assert_eq!(table.line_col_of(10), (3, 0));
+        assert_eq!(table.line_col_of(11), (3, 1));
+    }
+
+    #[test]
+    fn trailing_newline() {
+        let table = PieceTable::from("ab\n");
+        assert_eq!(table.line_col_of(3), (1, 0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Position 4 out of bounds for length 3")]
+    fn out_of_bounds() {
+        let table = PieceTable::from("ab\n");
+        table.line_col_of(4);
+    }
+}
+
+mod byte_of_line_col {
+    use super::*;
+
+    #[test]
+    fn positions() {
+        let mut table = PieceTable::from("ab\r");
+        table.append("\ncd");
+        assert_eq!(table.byte_of_line_col(0, 0), 0);
+        assert_eq!(table.byte_of_line_col(0, 2), 2);
+        assert_eq!(table.byte_of_line_col(1, 1), 5);
+        assert_eq!(table.byte_of_line_col(1, 2), 6);
+    }
+
+    #[test]
+    #[should_panic(expected = "Column 3 out of bounds for line 0 of length 2")]
+    fn column_out_of_bounds() {
+        let table = PieceTable::from("ab\ncd");
+        table.byte_of_line_col(0, 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "Line 2 out of bounds for line count 2")]
+    fn line_out_of_bounds() {
+        let table = PieceTable::from("ab\ncd");
+        table.byte_of_line_col(2, 0);
+    }
+}
+
+mod line_index {
+    use super::*;
+
+    /// Checks that all line queries agree with and without the line index
+    fn check_queries(table: &mut PieceTable) {
+        let mut results = Vec::new();
+        for enabled in [false, true] {
+            table.set_line_index(enabled);
+            let line_count = table.line_count();
+            let ranges: Vec<_> = (0..=line_count).map(|n| table.line_byte_range(n)).collect();
+            let blocks: Vec<_> = (0..line_count)
+                .flat_map(|a| (a..=line_count + 1).map(move |b| (a, b)))
+                .map(|(a, b)| table.byte_range_of_lines(a..b))
+                .collect();
+            let positions: Vec<_> = (0..=table.len()).map(|b| table.line_col_of(b)).collect();
+            results.push((line_count, ranges, blocks, positions));
+        }
+        assert_eq!(results[0], results[1]);
+    }
+
+    #[test]
+    fn matches_scan() {
+        let mut table = PieceTable::from("ab\r");
+        check_queries(&mut table);
+        table.append("\ncd\n\n");
+        check_queries(&mut table);
+        table.insert(0, "\r\r\n");
+        check_queries(&mut table);
+        table.remove(4, 3);
+        check_queries(&mut table);
+        table.undo();
+        check_queries(&mut table);
+        table.remove(0, table.len());
+        check_queries(&mut table);
+    }
+
+    #[test]
+    fn updated_on_edit() {
+        let mut table = PieceTable::from("ab\ncd");
+        table.set_line_index(true);
+        assert_eq!(table.line_count(), 2);
+
+        table.append("\nef");
+        assert_eq!(table.line_count(), 3);
+        assert_eq!(table.line_byte_range(2), Some(6..8));
+
+        table.undo();
+        assert_eq!(table.line_count(), 2);
+        table.hot_redo();
+        assert_eq!(table.line_byte_range(2), Some(6..8));
+
+        table.insert_no_history(0, "\n");
+        assert_eq!(table.line_col_of(1), (1, 0));
+    }
+
+    #[test]
+    fn line_past_end() {
+        let mut table = PieceTable::from("ab\ncd");
+        table.set_line_index(true);
+        assert_eq!(table.line_byte_range(usize::MAX), None);
+        assert_eq!(table.byte_range_of_lines(1..usize::MAX), 3..5);
+    }
+
+    /// Checks that all line queries of the table, indexed since its creation, agree
+    /// with scanning the text
+    fn check_kept_in_sync(table: &PieceTable) {
+        let mut scanning = table.clone();
+        scanning.set_line_index(false);
+
+        assert_eq!(table.line_count(), scanning.line_count());
+        for n in 0..=table.line_count() {
+            assert_eq!(table.line_byte_range(n), scanning.line_byte_range(n));
+            assert_eq!(
+                table.byte_range_of_lines(0..n),
+                scanning.byte_range_of_lines(0..n)
+            );
+        }
+        for byte in 0..=table.len() {
+            assert_eq!(table.line_col_of(byte), scanning.line_col_of(byte));
+        }
+    }
+
+    #[test]
+    fn crlf_across_pieces() {
+        let mut table = PieceTable::from("ab\r");
+        table.set_line_index(true);
+        table.append("\ncd\r");
+        assert_eq!(table.line_count(), 3);
+        assert_eq!(table.line_byte_range(1), Some(4..6));
+        assert_eq!(table.line_col_of(3), (0, 3));
+        assert_eq!(table.line_col_of(4), (1, 0));
+        check_kept_in_sync(&table);
+
+        table.insert(3, "x");
+        assert_eq!(table.line_count(), 4);
+        check_kept_in_sync(&table);
+
+        table.remove(3, 1);
+        assert_eq!(table.line_count(), 3);
+        check_kept_in_sync(&table);
+    }
+
+    #[test]
+    fn crlf_in_addition_buffer() {
+        let mut table = PieceTable::from("ab");
+        table.set_line_index(true);
+        table.append("\r");
+        table.append("\n");
+        table.insert(0, "\n");
+        assert_eq!(table.line_count(), 3);
+        check_kept_in_sync(&table);
+
+        table.remove(3, 1);
+        assert_eq!(table.line_count(), 3);
+        check_kept_in_sync(&table);
+    }
+
+    #[test]
+    fn kept_in_sync_by_edits() {
+        let fragments = ["\r", "\n", "\r\n", "x", "ab\ncd", "\n\r", "yz\r"];
+        let mut table = PieceTable::from("one\r\ntwo\rthree\n");
+        table.set_line_index(true);
+
+        // Deterministic pseudo-random edits
+        let mut seed = 7_usize;
+        for step in 0..300 {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345) % (1 << 31);
+            let pos = seed % (table.len() + 1);
+            match step % 6 {
+                0 | 1 => table.insert(pos, fragments[seed % fragments.len()]),
+                2 if pos < table.len() => {
+                    table.remove(pos, (seed % 5).min(table.len() - pos).max(1))
+                }
+                3 => {
+                    table.undo();
+                }
+                4 => {
+                    table.redo();
+                }
+                5 if pos < table.len() => table.move_range(pos, 1, seed % (table.len() + 1)),
+                _ => table.append(fragments[seed % fragments.len()]),
+            }
+            check_kept_in_sync(&table);
+        }
+    }
+
+    #[test]
+    fn kept_in_sync_without_history() {
+        let mut table = PieceTable::from("ab\r\ncd");
+        table.disable_history();
+        table.set_line_index(true);
+
+        table.insert_no_history(3, "\n\r");
+        check_kept_in_sync(&table);
+        table.remove_no_history(2, 2);
+        check_kept_in_sync(&table);
+        table.swap_ranges(0..1, 3..5);
+        check_kept_in_sync(&table);
+        table.revert_to_original();
+        check_kept_in_sync(&table);
+    }
+
+    #[test]
+    fn kept_in_sync_by_coalescing() {
+        let mut table = PieceTable::from("");
+        table.set_line_index(true);
+        table.append_all(["ab\r"]);
+        table.append_all(["\ncd"]);
+        assert_eq!(table.coalesce_pieces(), 1);
+        check_kept_in_sync(&table);
+
+        table.undo();
+        check_kept_in_sync(&table);
+    }
+}
+
+mod visual_column {
+    use super::*;
+
+    #[test]
+    fn no_tabs() {
+        let table = PieceTable::from("Hello,\nWorld!");
+        assert_eq!(table.visual_column(0, 4), 0);
+        assert_eq!(table.visual_column(5, 4), 5);
+        assert_eq!(table.visual_column(7, 4), 0);
+        assert_eq!(table.visual_column(10, 4), 3);
+        assert_eq!(table.visual_column(13, 4), 6);
+    }
+
+    #[test]
+    fn tab_stops() {
+        let table = PieceTable::from("\tab\tc\t");
+        assert_eq!(table.visual_column(1, 4), 4);
+        assert_eq!(table.visual_column(3, 4), 6);
+        assert_eq!(table.visual_column(4, 4), 8);
+        assert_eq!(table.visual_column(6, 4), 12);
+        assert_eq!(table.visual_column(6, 8), 24);
+    }
+
+    #[test]
+    fn multi_byte() {
+        let table = PieceTable::from("é\tx");
+        assert_eq!(table.visual_column("é".len(), 4), 1);
+        assert_eq!(table.visual_column("é\t".len(), 4), 4);
+    }
+
+    #[test]
+    fn across_pieces() {
+        let mut table = PieceTable::from("a\n\t");
+        table.append("\tb\tc");
+        table.insert(3, "é");
+        assert_eq!(table.to_string(), "a\n\té\tb\tc");
+        assert_eq!(table.visual_column(5, 4), 5);
+        assert_eq!(table.visual_column(6, 4), 8);
+        assert_eq!(table.visual_column(8, 4), 12);
+    }
+
+    #[test]
+    fn crlf() {
+        let table = PieceTable::from("ab\r\n\tc");
+        assert_eq!(table.visual_column(5, 2), 2);
+    }
+
+    #[test]
+    fn inside_crlf() {
+        let table = PieceTable::from("ab\r\ncd");
+        assert_eq!(table.visual_column(2, 4), 2);
+        assert_eq!(table.visual_column(3, 4), 2);
+        assert_eq!(table.visual_column(4, 4), 0);
+    }
+
+    #[test]
+    fn crlf_across_pieces() {
+        let mut table = PieceTable::from("\ta\r");
+        table.append("\nb");
+        assert_eq!(table.visual_column(3, 4), 5);
+        assert_eq!(table.visual_column(4, 4), 0);
+    }
+
+    #[test]
+    fn line_start_in_earlier_piece() {
+        let mut table = PieceTable::from("x\ra\tb");
+        table.append("c");
+        table.append("\td");
+        assert_eq!(table.to_string(), "x\ra\tbc\td");
+
+        for line_index in [false, true] {
+            table.set_line_index(line_index);
+            assert_eq!(table.visual_column(1, 4), 1);
+            assert_eq!(table.visual_column(2, 4), 0);
+            assert_eq!(table.visual_column(6, 4), 6);
+            assert_eq!(table.visual_column(7, 4), 8);
+            assert_eq!(table.visual_column(8, 4), 9);
+        }
+    }
+
+    #[test]
+    fn inside_crlf_with_line_index() {
+        let mut table = PieceTable::from("\ta\r");
+        table.append("\nb");
+        table.set_line_index(true);
+        assert_eq!(table.visual_column(3, 4), 5);
+        assert_eq!(table.visual_column(4, 4), 0);
+        assert_eq!(table.visual_column(5, 4), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Tab width must not be 0")]
+    fn zero_tab_width() {
+        let table = PieceTable::from("\t");
+        table.visual_column(1, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Position 2 out of bounds for length 1")]
+    fn out_of_bounds() {
+        let table = PieceTable::from("\t");
+        table.visual_column(2, 4);
+    }
+}
+
+mod read_from {
+    use std::io::ErrorKind;
+
+    use crate::piece_table::line_ending::LineEnding;
+
+    use super::*;
+
+    #[test]
+    fn lf() {
+        let table = PieceTable::read_from("a\nb\n".as_bytes()).unwrap();
+        assert_eq!(table.to_string(), "a\nb\n");
+        assert_eq!(table.line_ending(), LineEnding::Lf);
+    }
+
+    #[test]
+    fn crlf() {
+        let table = PieceTable::read_from("a\r\nb\r\n".as_bytes()).unwrap();
+        assert_eq!(table.to_string(), "a\r\nb\r\n");
+        assert_eq!(table.line_ending(), LineEnding::CrLf);
+    }
+
+    #[test]
+    fn dominant_style() {
+        let table = PieceTable::read_from("a\r\nb\r\nc\n".as_bytes()).unwrap();
+        assert_eq!(table.line_ending(), LineEnding::CrLf);
+
+        let table = PieceTable::read_from("a\r\nb\nc\n".as_bytes()).unwrap();
+        assert_eq!(table.line_ending(), LineEnding::Lf);
+    }
+
+    #[test]
+    fn no_line_endings() {
+        let table = PieceTable::read_from("".as_bytes()).unwrap();
+        assert_eq!(table.line_ending(), LineEnding::Lf);
+        assert!(table.is_empty());
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        let err = PieceTable::read_from(&[0x61, 0xff][..]).err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+}
+
+mod from_reader {
+    use std::io::ErrorKind;
+
+    use crate::piece_table::{
+        line_ending::LineEnding,
+        piece::{Piece, PieceSource},
+    };
+    use crate::tests::piece_table::validate_table;
+
+    use super::*;
+
+    #[test]
+    fn single_piece() {
+        let pieces = vec![Piece::new(PieceSource::Original, 0, 14)];
+        let table = PieceTable::from_reader("Hello,\r\nWorld!".as_bytes()).unwrap();
+        validate_table(&table, "Hello,\r\nWorld!", "", &pieces, "Hello,\r\nWorld!");
+        assert_eq!(table.line_ending(), LineEnding::Lf);
+    }
+
+    #[test]
+    fn empty() {
+        let table = PieceTable::from_reader("".as_bytes()).unwrap();
+        validate_table(&table, "", "", &Vec::new(), "");
+    }
+
+    #[test]
+    fn undo_stays_at_root() {
+        let mut table = PieceTable::from_reader("Hello".as_bytes()).unwrap();
+        assert!(!table.undo());
+        table.append("!");
+        table.undo();
+        assert_eq!(table.to_string(), "Hello");
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        let err = PieceTable::from_reader(&[0x61, 0xc3][..]).err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn read_error() {
+        struct Failing;
+        impl std::io::Read for Failing {
+            fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+                Err(std::io::Error::other("disk on fire"))
+            }
+        }
+
+        let err = PieceTable::from_reader(Failing).err().unwrap();
+        assert_eq!(err.to_string(), "disk on fire");
+    }
+}
+
+mod write_text {
+    use crate::piece_table::line_ending::LineEnding;
+
+    use super::*;
+
+    fn write(table: &PieceTable) -> String {
+        let mut out = Vec::new();
+        table.write_text(&mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn crlf_round_trip() {
+        let mut table = PieceTable::read_from("a\r\nb\r\n".as_bytes()).unwrap();
+        table.insert(3, "x\ny\n");
+        assert_eq!(table.to_string(), "a\r\nx\ny\nb\r\n");
+        assert_eq!(write(&table), "a\r\nx\r\ny\r\nb\r\n");
+    }
+
+    #[test]
+    fn crlf_split_across_pieces() {
+        let mut table = PieceTable::read_from("a\r\n".as_bytes()).unwrap();
+        table.insert(2, "b");
+        table.remove(2, 1);
+        table.insert(1, "\r");
+        table.remove(2, 1);
+        table.append("\n");
+        assert_eq!(table.to_string(), "a\r\n\n");
+        assert_eq!(write(&table), "a\r\n\r\n");
+    }
+
+    #[test]
+    fn lone_cr_kept() {
+        let mut table = PieceTable::from("a\rb");
+        table.set_line_ending(LineEnding::CrLf);
+        assert_eq!(write(&table), "a\rb");
+    }
+
+    #[test]
+    fn lf_unchanged() {
+        let mut table = PieceTable::from("a\r\nb\n");
+        table.append("c\n");
+        assert_eq!(write(&table), "a\r\nb\nc\n");
+    }
+
+    #[test]
+    fn dyn_writer() {
+        let mut table = PieceTable::from("a\nb");
+        table.set_line_ending(LineEnding::CrLf);
+        let mut out = Vec::new();
+        let writer: &mut dyn std::io::Write = &mut out;
+        table.write_text(writer).unwrap();
+        assert_eq!(out, b"a\r\nb");
+    }
+}
+
+mod line_ending_stats {
+    use crate::piece_table::line_ending::LineEndingStats;
+
+    use super::*;
+
+    #[test]
+    fn single_kind() {
+        let table = PieceTable::from("a\r\nb\r\n");
+        let stats = table.line_ending_stats();
+        assert_eq!(
+            stats,
+            LineEndingStats {
+                lf: 0,
+                crlf: 2,
+                cr: 0
+            }
+        );
+        assert!(!stats.is_mixed());
+    }
+
+    #[test]
+    fn crlf_split_across_pieces() {
+        let mut table = PieceTable::from("a\r");
+        table.append("\n");
+        table.append("\r");
+        table.append("\r");
+        table.append("\n");
+        assert_eq!(
+            table.line_ending_stats(),
+            LineEndingStats {
+                lf: 0,
+                crlf: 2,
+                cr: 1
+            }
+        );
+    }
+
+    #[test]
+    fn mixed() {
+        let table = PieceTable::from("a\nb\r\n");
+        assert!(table.line_ending_stats().is_mixed());
+    }
+
+    #[test]
+    fn empty() {
+        let table = PieceTable::from("");
+        assert_eq!(table.line_ending_stats(), LineEndingStats::default());
+        assert!(!table.line_ending_stats().is_mixed());
+    }
+}
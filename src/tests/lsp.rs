@@ -0,0 +1,65 @@
+use crate::piece_table::PieceTable;
+
+mod apply_lsp_change {
+    use super::*;
+
+    #[test]
+    fn single_line() {
+        let mut table = PieceTable::from("Hello, World!");
+        table.apply_lsp_change(0, 7, 0, 12, "Rust");
+        assert_eq!(table.to_string(), "Hello, Rust!");
+    }
+
+    #[test]
+    fn multi_line() {
+        let mut table = PieceTable::from("first\nsecond\r\nthird");
+        table.apply_lsp_change(0, 3, 2, 2, "");
+        assert_eq!(table.to_string(), "firird");
+    }
+
+    #[test]
+    fn insertion() {
+        let mut table = PieceTable::from("a\nc");
+        table.apply_lsp_change(1, 0, 1, 0, "b\n");
+        assert_eq!(table.to_string(), "a\nb\nc");
+    }
+
+    #[test]
+    fn surrogate_pairs() {
+        let mut table = PieceTable::from("x😀y\n😀é😀z");
+        table.apply_lsp_change(1, 2, 1, 3, "e");
+        assert_eq!(table.to_string(), "x😀y\n😀e😀z");
+        table.apply_lsp_change(0, 1, 0, 3, "");
+        assert_eq!(table.to_string(), "xy\n😀e😀z");
+    }
+
+    #[test]
+    fn across_pieces() {
+        let mut table = PieceTable::from("é");
+        table.append("😀\nab");
+        table.apply_lsp_change(0, 1, 1, 1, "-");
+        assert_eq!(table.to_string(), "é-b");
+    }
+
+    #[test]
+    fn past_line_end() {
+        let mut table = PieceTable::from("ab\ncd");
+        table.apply_lsp_change(0, 10, 0, 20, "!");
+        assert_eq!(table.to_string(), "ab!\ncd");
+    }
+
+    #[test]
+    fn single_undo() {
+        let mut table = PieceTable::from("Hello, World!");
+        table.apply_lsp_change(0, 0, 0, 5, "Bye");
+        table.undo();
+        assert_eq!(table.to_string(), "Hello, World!");
+    }
+
+    #[test]
+    #[should_panic(expected = "Line 2 out of bounds for line count 2")]
+    fn line_out_of_bounds() {
+        let mut table = PieceTable::from("a\nb");
+        table.apply_lsp_change(2, 0, 2, 0, "c");
+    }
+}
@@ -1,5 +1,7 @@
 use crate::{
     piece_table::{
+        edit_delta::EditDelta,
+        edit_error::EditError,
         piece::{Piece, PieceSource},
         PieceTable,
     },
@@ -17,10 +19,15 @@ pub(crate) fn validate_table<S1: AsRef<str>, S2: AsRef<str>, S3: AsRef<str>>(
     let addition = addition.as_ref();
     let expected = expected.as_ref();
 
-    assert_eq!(table.original, original);
+    assert_eq!(table.original.as_str(), original);
     assert_eq!(table.addition, addition);
     assert_eq!(table.pieces, *pieces);
     assert_eq!(table.total_length, expected.len());
+    assert_eq!(
+        table.total_length,
+        table.recompute_len(),
+        "Total length is out of sync with the pieces"
+    );
     assert_eq!(table.to_string(), expected);
 }
 
@@ -30,7 +37,7 @@ fn get_simple_table() -> PieceTable {
     table
 }
 
-fn get_complex_table() -> PieceTable {
+pub(crate) fn get_complex_table() -> PieceTable {
     let mut table = PieceTable::from("Held!");
     table.insert(2, "llor");
     table.insert(4, "o, W");
@@ -72,6 +79,234 @@ mod from {
     }
 }
 
+mod from_lines {
+    use super::*;
+
+    #[test]
+    fn lines() {
+        let pieces = vec![Piece::new(PieceSource::Original, 0, "a\nbc\n\nd".len())];
+
+        let table = PieceTable::from_lines(["a", "bc", "", "d"]);
+        validate_table(&table, "a\nbc\n\nd", "", &pieces, "a\nbc\n\nd");
+    }
+
+    #[test]
+    fn trailing_empty_line() {
+        let table = PieceTable::from_lines(vec![String::from("a"), String::new()]);
+        assert_eq!(table.to_string(), "a\n");
+    }
+
+    #[test]
+    fn single_line() {
+        let table = PieceTable::from_lines(["Hello, World!"]);
+        assert_eq!(table.to_string(), "Hello, World!");
+    }
+
+    #[test]
+    fn empty() {
+        let table = PieceTable::from_lines(Vec::<String>::new());
+        validate_table(&table, "", "", &Vec::new(), "");
+    }
+}
+
+mod from_line_split {
+    use super::*;
+
+    #[test]
+    fn line_endings() {
+        let text = "a\nbc\r\n\rd";
+        let pieces = vec![
+            Piece::new(PieceSource::Original, 0, "a\n".len()),
+            Piece::new(PieceSource::Original, 2, "bc\r\n".len()),
+            Piece::new(PieceSource::Original, 6, "\r".len()),
+            Piece::new(PieceSource::Original, 7, "d".len()),
+        ];
+
+        let table = PieceTable::from_line_split(text);
+        validate_table(&table, text, "", &pieces, text);
+        assert_eq!(table.validate(), Ok(()));
+    }
+
+    #[test]
+    fn trailing_line_ending() {
+        let pieces = vec![
+            Piece::new(PieceSource::Original, 0, "a\n".len()),
+            Piece::new(PieceSource::Original, 2, "b\n".len()),
+        ];
+
+        let table = PieceTable::from_line_split("a\nb\n");
+        validate_table(&table, "a\nb\n", "", &pieces, "a\nb\n");
+    }
+
+    #[test]
+    fn empty() {
+        let table = PieceTable::from_line_split("");
+        validate_table(&table, "", "", &Vec::new(), "");
+    }
+
+    #[test]
+    fn edit_and_undo() {
+        let mut table = PieceTable::from_line_split("Hello,\nWorld!");
+        table.insert(7, "Big ");
+        assert_eq!(table.to_string(), "Hello,\nBig World!");
+
+        table.undo();
+        let pieces = vec![
+            Piece::new(PieceSource::Original, 0, "Hello,\n".len()),
+            Piece::new(PieceSource::Original, 7, "World!".len()),
+        ];
+        validate_table(&table, "Hello,\nWorld!", "Big ", &pieces, "Hello,\nWorld!");
+    }
+}
+
+mod from_parts {
+    use super::*;
+
+    #[test]
+    fn complex() {
+        let pieces = vec![
+            Piece::new(PieceSource::Original, 0, 2),
+            Piece::new(PieceSource::Addition, 0, 2),
+            Piece::new(PieceSource::Addition, 4, 4),
+            Piece::new(PieceSource::Addition, 2, 2),
+            Piece::new(PieceSource::Original, 2, 3),
+        ];
+        let parts = [
+            (PieceSource::Original, 0..2),
+            (PieceSource::Addition, 0..2),
+            (PieceSource::Addition, 4..8),
+            (PieceSource::Addition, 2..4),
+            (PieceSource::Original, 2..5),
+        ];
+
+        let table = PieceTable::from_parts("Held!", String::from("lloro, W"), parts).unwrap();
+        validate_table(&table, "Held!", "lloro, W", &pieces, "Hello, World!");
+        assert_eq!(table.validate(), Ok(()));
+    }
+
+    #[test]
+    fn drops_empty_pieces() {
+        let parts = [(PieceSource::Addition, 1..1), (PieceSource::Original, 0..5)];
+        let table = PieceTable::from_parts("Hello", String::from("!"), parts).unwrap();
+
+        let pieces = vec![Piece::new(PieceSource::Original, 0, 5)];
+        validate_table(&table, "Hello", "!", &pieces, "Hello");
+    }
+
+    #[test]
+    fn editable() {
+        let parts = [(PieceSource::Addition, 0..5)];
+        let mut table = PieceTable::from_parts("", String::from("Hello"), parts).unwrap();
+        table.append("!");
+        assert_eq!(table.to_string(), "Hello!");
+        assert_eq!(table.addition_text(), "Hello!");
+
+        table.undo();
+        assert_eq!(table.to_string(), "Hello");
+        assert!(!table.undo());
+    }
+
+    #[test]
+    fn out_of_bounds() {
+        let parts = [(PieceSource::Original, 0..5), (PieceSource::Addition, 0..2)];
+        let result = PieceTable::from_parts("Hello", String::from("!"), parts);
+        assert_eq!(result.err(), Some(EditError::InvalidPiece { index: 1 }));
+    }
+
+    #[test]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn reversed() {
+        let parts = [(PieceSource::Original, 3..1)];
+        let result = PieceTable::from_parts("Hello", String::new(), parts);
+        assert_eq!(result.err(), Some(EditError::InvalidPiece { index: 0 }));
+    }
+
+    #[test]
+    fn starts_inside_char() {
+        let parts = [(PieceSource::Original, 0..1), (PieceSource::Original, 2..4)];
+        let result = PieceTable::from_parts("aéb", String::new(), parts);
+        assert_eq!(result.err(), Some(EditError::InvalidPiece { index: 1 }));
+    }
+
+    #[test]
+    fn ends_inside_char() {
+        let parts = [(PieceSource::Addition, 0..2), (PieceSource::Original, 0..1)];
+        let result = PieceTable::from_parts("a", String::from("€"), parts);
+        assert_eq!(result.err(), Some(EditError::InvalidPiece { index: 0 }));
+    }
+
+    #[test]
+    fn empty_piece_inside_char() {
+        let parts = [(PieceSource::Original, 2..2)];
+        let result = PieceTable::from_parts("aéb", String::new(), parts);
+        assert_eq!(result.err(), Some(EditError::InvalidPiece { index: 0 }));
+    }
+
+    #[test]
+    fn multi_byte() {
+        let parts = [(PieceSource::Original, 0..3), (PieceSource::Addition, 0..3)];
+        let table = PieceTable::from_parts("aéb", String::from("€"), parts).unwrap();
+        assert_eq!(table.to_string(), "aé€");
+        assert_eq!(table.validate(), Ok(()));
+    }
+}
+
+mod with_addition {
+    use super::*;
+
+    #[test]
+    fn appends_addition() {
+        let pieces = vec![
+            Piece::new(PieceSource::Original, 0, "Hello".len()),
+            Piece::new(PieceSource::Addition, 0, ", World!".len()),
+        ];
+
+        let table = PieceTable::with_addition("Hello", String::from(", World!"));
+        validate_table(&table, "Hello", ", World!", &pieces, "Hello, World!");
+    }
+
+    #[test]
+    fn empty() {
+        let table = PieceTable::with_addition("", String::new());
+        validate_table(&table, "", "", &Vec::new(), "");
+    }
+}
+
+mod from_source {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn arc_str() {
+        let pieces = vec![
+            Piece::new(PieceSource::Original, 0, 5),
+            Piece::new(PieceSource::Addition, 0, 8),
+        ];
+
+        let text: Arc<str> = Arc::from("Hello");
+        let mut table = PieceTable::from_source(text);
+        table.append(", World!");
+        validate_table(&table, "Hello", ", World!", &pieces, "Hello, World!");
+    }
+
+    #[test]
+    fn static_str() {
+        let mut table = PieceTable::from_source("Hello, World!");
+        table.remove(5, 7);
+        assert_eq!(table.to_string(), "Hello!");
+        table.undo();
+        assert_eq!(table.to_string(), "Hello, World!");
+    }
+
+    #[test]
+    fn empty() {
+        let pieces = vec![];
+
+        let table = PieceTable::from_source(String::new());
+        validate_table(&table, "", "", &pieces, "");
+    }
+}
+
 mod insert {
     use super::*;
 
@@ -102,8 +337,7 @@ mod insert {
     #[test]
     fn between_pieces() {
         let pieces = vec![
-            Piece::new(PieceSource::Addition, 0, "Hello".len()),
-            Piece::new(PieceSource::Addition, "Hello".len(), ", ".len()),
+            Piece::new(PieceSource::Addition, 0, "Hello, ".len()),
             Piece::new(PieceSource::Original, 0, "World!".len()),
         ];
 
@@ -143,7 +377,7 @@ mod insert {
     }
 
     #[test]
-    #[should_panic]
+    #[should_panic(expected = "Insert position 1 out of bounds for length 0")]
     fn out_of_bounds_pos() {
         let mut table = PieceTable::from("");
         table.insert(1, "Hello, World!");
@@ -157,6 +391,178 @@ mod insert {
     }
 }
 
+mod insert_extends_piece {
+    use super::*;
+
+    #[test]
+    fn typing_at_end() {
+        let pieces = vec![
+            Piece::new(PieceSource::Original, 0, "Hello".len()),
+            Piece::new(PieceSource::Addition, 0, ", World!".len()),
+        ];
+
+        let mut table = PieceTable::from("Hello");
+        for (pos, c) in ", World!".char_indices() {
+            table.insert(5 + pos, c.to_string());
+        }
+        validate_table(&table, "Hello", ", World!", &pieces, "Hello, World!");
+        assert_eq!(table.history.changes.len(), 9);
+        assert_eq!(table.last_edit(), Some(EditDelta::new(12, 12, 13)));
+
+        table.undo();
+        let pieces = vec![
+            Piece::new(PieceSource::Original, 0, "Hello".len()),
+            Piece::new(PieceSource::Addition, 0, ", World".len()),
+        ];
+        validate_table(&table, "Hello", ", World!", &pieces, "Hello, World");
+
+        while table.undo() {}
+        assert_eq!(table.to_string(), "Hello");
+        while table.hot_redo() {}
+        assert_eq!(table.to_string(), "Hello, World!");
+        assert_eq!(table.stats().piece_count, 2);
+    }
+
+    #[test]
+    fn typing_in_middle() {
+        let pieces = vec![
+            Piece::new(PieceSource::Original, 0, "Hello".len()),
+            Piece::new(PieceSource::Addition, 0, ", W".len()),
+            Piece::new(PieceSource::Original, 5, "orld!".len()),
+        ];
+
+        let mut table = PieceTable::from("Helloorld!");
+        table.insert(5, ",");
+        table.insert(6, " ");
+        table.insert(7, "W");
+        validate_table(&table, "Helloorld!", ", W", &pieces, "Hello, World!");
+
+        table.undo();
+        assert_eq!(table.to_string(), "Hello, orld!");
+        assert_eq!(table.stats().piece_count, 3);
+    }
+
+    #[test]
+    fn not_behind_latest_addition() {
+        let pieces = vec![
+            Piece::new(PieceSource::Addition, 2, ">".len()),
+            Piece::new(PieceSource::Original, 0, "Hello".len()),
+            Piece::new(PieceSource::Addition, 0, ", ".len()),
+            Piece::new(PieceSource::Addition, 3, "World!".len()),
+        ];
+
+        let mut table = PieceTable::from("Hello");
+        table.append(", ");
+        table.insert(0, ">");
+        table.append("World!");
+        validate_table(&table, "Hello", ", >World!", &pieces, ">Hello, World!");
+    }
+
+    #[test]
+    fn structural_change() {
+        use crate::PieceChange;
+        use std::sync::{Arc, Mutex};
+
+        let deltas = Arc::new(Mutex::new(Vec::new()));
+        let mut table = PieceTable::from("Hello");
+        table.append(",");
+        let observed = Arc::clone(&deltas);
+        table.on_structural_change(move |delta| observed.lock().unwrap().push(delta.clone()));
+
+        table.append(" World!");
+        assert_eq!(
+            deltas.lock().unwrap()[0].changes,
+            vec![PieceChange::Modified { index: 1 }]
+        );
+    }
+}
+
+mod insert_char_boundary {
+    use super::*;
+
+    #[test]
+    #[should_panic(
+        expected = "insert: position 2 (offset 2 in the Original buffer) is not a char boundary"
+    )]
+    fn split_inside_char() {
+        let mut table = PieceTable::from("aéb");
+        table.insert(2, "x");
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "insert: position 3 (offset 2 in the Addition buffer) is not a char boundary"
+    )]
+    fn split_inside_added_char() {
+        let mut table = PieceTable::from("ab");
+        table.insert(1, "€");
+        table.insert(3, "x");
+    }
+
+    #[test]
+    fn table_untouched() {
+        let mut table = PieceTable::from("aéb");
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            table.insert(2, "x");
+        }));
+        assert!(result.is_err());
+
+        let pieces = vec![Piece::new(PieceSource::Original, 0, "aéb".len())];
+        validate_table(&table, "aéb", "", &pieces, "aéb");
+    }
+
+    #[test]
+    fn piece_boundary_inside_text() {
+        let mut table = PieceTable::from("aé");
+        table.append("b");
+        table.insert(3, "x");
+        assert_eq!(table.to_string(), "aéxb");
+    }
+}
+
+mod remove_char_boundary {
+    use super::*;
+
+    #[test]
+    #[should_panic(
+        expected = "remove: position 2 (offset 2 in the Original buffer) is not a char boundary"
+    )]
+    fn start_inside_char() {
+        let mut table = PieceTable::from("aéb");
+        table.remove(2, 2);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "remove: position 3 (offset 2 in the Addition buffer) is not a char boundary"
+    )]
+    fn end_inside_added_char() {
+        let mut table = PieceTable::from("ab");
+        table.insert(1, "€");
+        table.remove(0, 3);
+    }
+
+    #[test]
+    fn table_untouched() {
+        let mut table = PieceTable::from("aéb");
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            table.remove(1, 1);
+        }));
+        assert!(result.is_err());
+
+        let pieces = vec![Piece::new(PieceSource::Original, 0, "aéb".len())];
+        validate_table(&table, "aéb", "", &pieces, "aéb");
+    }
+
+    #[test]
+    fn piece_boundaries_inside_range() {
+        let mut table = PieceTable::from("aé");
+        table.append("€b");
+        table.remove(1, 5);
+        assert_eq!(table.to_string(), "ab");
+    }
+}
+
 mod append {
     use super::*;
 
@@ -179,9 +585,7 @@ mod append {
     fn multiple() {
         let pieces = vec![
             Piece::new(PieceSource::Original, 0, "Hello".len()),
-            Piece::new(PieceSource::Addition, 0, ", ".len()),
-            Piece::new(PieceSource::Addition, ", ".len(), "World".len()),
-            Piece::new(PieceSource::Addition, ", World".len(), "!".len()),
+            Piece::new(PieceSource::Addition, 0, ", World!".len()),
         ];
 
         let mut table = PieceTable::from("Hello");
@@ -199,9 +603,96 @@ mod append {
     }
 }
 
+mod append_all {
+    use super::*;
+
+    #[test]
+    fn single_piece() {
+        let pieces = vec![
+            Piece::new(PieceSource::Original, 0, "Log:\n".len()),
+            Piece::new(PieceSource::Addition, 0, "a\nb\nc\n".len()),
+        ];
+
+        let mut table = PieceTable::from("Log:\n");
+        table.append_all(vec![
+            String::from("a\n"),
+            String::from("b\n"),
+            String::from("c\n"),
+        ]);
+        validate_table(&table, "Log:\n", "a\nb\nc\n", &pieces, "Log:\na\nb\nc\n");
+        assert_eq!(table.history.changes.len(), 2);
+    }
+
+    #[test]
+    fn single_undo_step() {
+        let mut table = PieceTable::from("");
+        table.append_all(["a", "b"]);
+        table.append_all(["c", "d"]);
+        assert_eq!(table.to_string(), "abcd");
+
+        table.undo();
+        assert_eq!(table.to_string(), "ab");
+        table.undo();
+        assert_eq!(table.to_string(), "");
+        table.hot_redo();
+        assert_eq!(table.to_string(), "ab");
+    }
+
+    #[test]
+    fn skips_empty() {
+        let pieces = vec![Piece::new(PieceSource::Original, 0, "Hello".len())];
+
+        let mut table = PieceTable::from("Hello");
+        table.append_all(["", ""]);
+        table.append_all(Vec::<&str>::new());
+        validate_table(&table, "Hello", "", &pieces, "Hello");
+        assert_eq!(table.history.changes.len(), 1);
+        assert_eq!(table.last_edit(), None);
+    }
+
+    #[test]
+    fn too_large() {
+        let mut table = PieceTable::from("Hello");
+        table.set_max_len(8);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            table.append_all([", ", "World!"]);
+        }));
+        assert!(result.is_err());
+
+        let pieces = vec![Piece::new(PieceSource::Original, 0, "Hello".len())];
+        validate_table(&table, "Hello", "", &pieces, "Hello");
+    }
+}
+
 mod remove {
     use super::*;
 
+    #[test]
+    fn whole_text() {
+        let pieces = vec![Piece::new(PieceSource::Original, 0, "Hello, World!".len())];
+
+        let mut table = PieceTable::from("Hello, World!");
+        table.remove(0, table.len());
+        validate_table(&table, "Hello, World!", "", &Vec::new(), "");
+
+        table.undo();
+        validate_table(&table, "Hello, World!", "", &pieces, "Hello, World!");
+    }
+
+    #[test]
+    fn whole_text_complex() {
+        let mut table = get_complex_table();
+        let pieces = table.pieces.iter().copied().collect::<Vec<_>>();
+        table.remove(0, table.len());
+        validate_table(&table, "Held!", "lloro, W", &Vec::new(), "");
+
+        table.undo();
+        validate_table(&table, "Held!", "lloro, W", &pieces, "Hello, World!");
+
+        table.hot_redo();
+        validate_table(&table, "Held!", "lloro, W", &Vec::new(), "");
+    }
+
     #[test]
     fn front() {
         let pieces = vec![Piece::new(
@@ -308,10 +799,10 @@ mod remove {
 
     #[test]
     fn full() {
+        // The addition pieces surrounding the removed piece are merged
         let pieces = vec![
             Piece::new(PieceSource::Original, 0, 2),
-            Piece::new(PieceSource::Addition, 0, 2),
-            Piece::new(PieceSource::Addition, 2, 2),
+            Piece::new(PieceSource::Addition, 0, 4),
             Piece::new(PieceSource::Original, 2, 3),
         ];
 
@@ -363,26 +854,196 @@ mod remove {
     }
 
     #[test]
-    #[should_panic]
-    fn empty() {
-        let mut table = PieceTable::from("");
-        table.remove(0, 5);
-    }
+    fn merge_contiguous() {
+        let pieces = vec![Piece::new(PieceSource::Original, 0, "Hello, World!".len())];
 
-    #[test]
-    #[should_panic]
-    fn out_of_bounds() {
         let mut table = PieceTable::from("Hello, World!");
-        table.remove(15, 5);
+        table.insert("Hello".len(), "XX");
+        table.remove("Hello".len(), "XX".len());
+        validate_table(&table, "Hello, World!", "XX", &pieces, "Hello, World!");
     }
 
     #[test]
-    #[should_panic]
-    fn zero_characters() {
+    fn merge_contiguous_undo() {
+        let pieces = vec![
+            Piece::new(PieceSource::Original, 0, "Hello".len()),
+            Piece::new(PieceSource::Addition, 0, "XX".len()),
+            Piece::new(PieceSource::Original, "Hello".len(), ", World!".len()),
+        ];
+
         let mut table = PieceTable::from("Hello, World!");
-        table.remove(5, 0);
-    }
-}
+        table.insert("Hello".len(), "XX");
+        table.remove("Hello".len(), "XX".len());
+        table.undo();
+        validate_table(&table, "Hello, World!", "XX", &pieces, "HelloXX, World!");
+
+        table.hot_redo();
+        let pieces = vec![Piece::new(PieceSource::Original, 0, "Hello, World!".len())];
+        validate_table(&table, "Hello, World!", "XX", &pieces, "Hello, World!");
+    }
+
+    #[test]
+    fn merge_neighbours_of_trimmed_piece() {
+        let pieces = vec![
+            Piece::new(PieceSource::Original, 0, "H".len()),
+            Piece::new(PieceSource::Addition, 0, "ab".len()),
+        ];
+
+        let mut table = PieceTable::from("Hi");
+        table.append("a");
+        table.append("b");
+        table.remove("H".len(), "i".len());
+        validate_table(&table, "Hi", "ab", &pieces, "Hab");
+    }
+
+    #[test]
+    fn merge_slice_with_previous() {
+        let pieces = vec![
+            Piece::new(PieceSource::Addition, 0, "ab".len()),
+            Piece::new(PieceSource::Addition, "abc".len(), "d".len()),
+        ];
+
+        let mut table = PieceTable::from("");
+        table.append_all(["a"]);
+        table.append_all(["bcd"]);
+        table.remove("ab".len(), "c".len());
+        validate_table(&table, "", "abcd", &pieces, "abd");
+
+        table.undo();
+        let pieces = vec![
+            Piece::new(PieceSource::Addition, 0, "a".len()),
+            Piece::new(PieceSource::Addition, "a".len(), "bcd".len()),
+        ];
+        validate_table(&table, "", "abcd", &pieces, "abcd");
+    }
+
+    #[test]
+    fn merge_at_former_slice_boundary() {
+        let pieces = vec![
+            Piece::new(PieceSource::Original, 0, "Hello".len()),
+            Piece::new(PieceSource::Addition, 0, "XXYY".len()),
+            Piece::new(PieceSource::Original, "Hello".len(), ", World!".len()),
+        ];
+
+        let mut table = PieceTable::from("Hello, World!");
+        table.insert("Hello".len(), "XXYY");
+        table.insert("HelloXX".len(), "ZZ");
+        table.remove("HelloXX".len(), "ZZ".len());
+        validate_table(
+            &table,
+            "Hello, World!",
+            "XXYYZZ",
+            &pieces,
+            "HelloXXYY, World!",
+        );
+    }
+
+    #[test]
+    fn no_merge_non_contiguous() {
+        let pieces = vec![
+            Piece::new(PieceSource::Original, 0, "Hell".len()),
+            Piece::new(PieceSource::Original, "Hello".len(), ", World!".len()),
+        ];
+
+        let mut table = PieceTable::from("Hello, World!");
+        table.insert("Hello".len(), "XX");
+        table.remove("Hell".len(), "oXX".len());
+        validate_table(&table, "Hello, World!", "XX", &pieces, "Hell, World!");
+    }
+
+    #[test]
+    fn slice_offset_piece() {
+        let pieces = vec![
+            Piece::new(PieceSource::Original, "Hello, ".len(), "Wo".len()),
+            Piece::new(PieceSource::Original, "Hello, Wor".len(), "ld!".len()),
+        ];
+
+        let mut table = PieceTable::from("Hello, World!");
+        table.remove(0, "Hello, ".len());
+        table.remove("Wo".len(), "r".len());
+        validate_table(&table, "Hello, World!", String::new(), &pieces, "Wold!");
+    }
+
+    #[test]
+    #[should_panic]
+    fn empty() {
+        let mut table = PieceTable::from("");
+        table.remove(0, 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "Remove range 15..20 out of bounds for length 13")]
+    fn out_of_bounds() {
+        let mut table = PieceTable::from("Hello, World!");
+        table.remove(15, 5);
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_characters() {
+        let mut table = PieceTable::from("Hello, World!");
+        table.remove(5, 0);
+    }
+}
+
+mod remove_zero_length_piece {
+    use super::*;
+
+    fn get_table_with_empty_piece() -> PieceTable {
+        // "Hello" "" ", World!"
+        let mut table = PieceTable::from("Hello");
+        table.append(", World!");
+        table
+            .pieces
+            .insert(1, Piece::new(PieceSource::Addition, 0, 0));
+        table
+    }
+
+    #[test]
+    fn starting_at_empty_piece() {
+        let mut table = get_table_with_empty_piece();
+        table.remove(5, 2);
+
+        let pieces = vec![
+            Piece::new(PieceSource::Original, 0, "Hello".len()),
+            Piece::new(PieceSource::Addition, 2, "World!".len()),
+        ];
+        validate_table(&table, "Hello", ", World!", &pieces, "HelloWorld!");
+    }
+
+    #[test]
+    fn ending_at_empty_piece() {
+        let mut table = get_table_with_empty_piece();
+        table.remove(3, 2);
+
+        let pieces = vec![
+            Piece::new(PieceSource::Original, 0, "Hel".len()),
+            Piece::new(PieceSource::Addition, 0, ", World!".len()),
+        ];
+        validate_table(&table, "Hello", ", World!", &pieces, "Hel, World!");
+    }
+
+    #[test]
+    fn across_empty_piece() {
+        let mut table = get_table_with_empty_piece();
+        table.remove(4, 3);
+
+        let pieces = vec![
+            Piece::new(PieceSource::Original, 0, "Hell".len()),
+            Piece::new(PieceSource::Addition, 2, "World!".len()),
+        ];
+        validate_table(&table, "Hello", ", World!", &pieces, "HellWorld!");
+    }
+
+    #[test]
+    fn undo_restores_empty_piece() {
+        let mut table = get_table_with_empty_piece();
+        let pieces = table.pieces.iter().copied().collect::<Vec<_>>();
+        table.remove(5, 2);
+        table.undo();
+        validate_table(&table, "Hello", ", World!", &pieces, "Hello, World!");
+    }
+}
 
 mod slice {
     use super::*;
@@ -443,30 +1104,2518 @@ mod slice {
     }
 
     #[test]
-    #[should_panic]
+    fn single_piece() {
+        let table = PieceTable::from("Hello, World!");
+        assert_eq!(table.to_string(), "Hello, World!");
+
+        for i in 0.."Hello, World!".len() {
+            for j in (i + 1)..="Hello, World!".len() {
+                assert_eq!(table.slice(i..j), "Hello, World!"[i..j]);
+            }
+        }
+    }
+
+    #[test]
+    fn single_piece_offset() {
+        let mut table = PieceTable::from("Hello, World!");
+        table.remove(0, "Hello, ".len());
+        assert_eq!(table.pieces.len(), 1);
+        assert_eq!(table.to_string(), "World!");
+        assert_eq!(table.slice(1..3), "or");
+    }
+
+    #[test]
+    #[should_panic(expected = "Slice index starts at 3 but ends at 1")]
+    #[allow(clippy::reversed_empty_ranges)]
     fn upper_smaller_lower() {
         let table = PieceTable::from("Hello, World!");
         table.slice(3..1);
     }
 
     #[test]
-    #[should_panic]
     fn upper_equal_lower() {
         let table = PieceTable::from("Hello, World!");
-        table.slice(3..3);
+        assert_eq!(table.slice(3..3), "");
+        assert_eq!(table.slice(13..), "");
     }
 
     #[test]
-    #[should_panic]
+    #[should_panic(expected = "Slice 20..22 out of bounds for length 13")]
     fn out_of_lower_bounds() {
         let table = PieceTable::from("Hello, World!");
         table.slice(20..22);
     }
 
     #[test]
-    #[should_panic]
+    #[should_panic(expected = "Slice 0..22 out of bounds for length 13")]
     fn out_of_upper_bounds() {
         let table = PieceTable::from("Hello, World!");
         table.slice(0..22);
     }
+
+    #[test]
+    #[should_panic(expected = "Slice boundary 2 falls inside a character")]
+    fn lower_inside_char() {
+        let table = PieceTable::from("aéb");
+        table.slice(2..4);
+    }
+
+    #[test]
+    #[should_panic(expected = "Slice boundary 3 falls inside a character")]
+    fn upper_inside_char_at_piece_edge() {
+        let mut table = PieceTable::from("ab");
+        table.insert(1, "€");
+        table.slice(0..3);
+    }
+
+    #[test]
+    fn boundaries_at_piece_edges() {
+        let mut table = PieceTable::from("ab");
+        table.insert(1, "€");
+        assert_eq!(table.slice(1..4), "€");
+        assert_eq!(table.slice(4..4), "");
+    }
+}
+
+mod length_invariant {
+    use super::*;
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "Total length is out of sync with the pieces")]
+    fn desynced_insert() {
+        let mut table = PieceTable::from("Hello, World!");
+        table.total_length += 1;
+        table.insert(0, "Hello");
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "Total length is out of sync with the pieces")]
+    fn desynced_remove() {
+        let mut table = get_complex_table();
+        table.total_length += 1;
+        table.remove(0, 1);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "Total length is out of sync with the pieces")]
+    fn desynced_undo() {
+        let mut table = get_complex_table();
+        table.total_length -= 1;
+        table.undo();
+    }
+
+    #[test]
+    fn consistent_after_edits() {
+        let mut table = get_complex_table();
+        table.remove("Hel".len(), "lo, Wo".len());
+        table.undo();
+        validate_table(
+            &table,
+            "Held!",
+            "lloro, W",
+            &Vec::from(get_complex_table().pieces),
+            "Hello, World!",
+        );
+
+        table.hot_redo();
+        assert_eq!(table.to_string(), "Helrld!");
+        assert_eq!(table.len(), "Helrld!".len());
+    }
+}
+
+mod debug {
+    use super::*;
+
+    #[test]
+    fn lists_pieces() {
+        let mut table = PieceTable::from("Held!");
+        table.insert(2, "llo\n");
+        assert_eq!(
+            format!("{table:?}"),
+            "PieceTable { len: 9, piece_count: 3, pieces: [\
+             Piece { source: Original, offset: 0, length: 2, text: \"He\" }, \
+             Piece { source: Addition, offset: 0, length: 4, text: \"llo\\n\" }, \
+             Piece { source: Original, offset: 2, length: 3, text: \"ld!\" }] }"
+        );
+    }
+
+    #[test]
+    fn empty() {
+        let table = PieceTable::from("");
+        assert_eq!(
+            format!("{table:?}"),
+            "PieceTable { len: 0, piece_count: 0, pieces: [] }"
+        );
+    }
+
+    #[test]
+    fn alternate() {
+        let table = PieceTable::from("Hi");
+        assert_eq!(
+            format!("{table:#?}"),
+            "PieceTable {
+    len: 2,
+    piece_count: 1,
+    pieces: [
+        Piece {
+            source: Original,
+            offset: 0,
+            length: 2,
+            text: \"Hi\",
+        },
+    ],
+}"
+        );
+    }
+}
+
+mod display_escaped {
+    use super::*;
+
+    #[test]
+    fn no_control_characters() {
+        let table = get_complex_table();
+        assert_eq!(table.display_escaped().to_string(), "Hello, World!");
+    }
+
+    #[test]
+    fn control_characters() {
+        let mut table = PieceTable::from("Hello,\tWorld!");
+        table.append("\r\n\u{1b}[0m");
+        assert_eq!(
+            table.display_escaped().to_string(),
+            "Hello,\\tWorld!\\r\\n\\u{1b}[0m"
+        );
+    }
+
+    #[test]
+    fn unicode() {
+        let mut table = PieceTable::from("caf");
+        table.append("é\n");
+        assert_eq!(table.display_escaped().to_string(), "café\\n");
+    }
+
+    #[test]
+    fn empty() {
+        let table = PieceTable::from("");
+        assert_eq!(table.display_escaped().to_string(), "");
+    }
+}
+
+mod last_edit {
+    use super::*;
+
+    #[test]
+    fn none() {
+        let table = PieceTable::from("Hello, World!");
+        assert_eq!(table.last_edit(), None);
+    }
+
+    #[test]
+    fn insert() {
+        let mut table = PieceTable::from("Held!");
+        table.insert(2, "llor");
+        assert_eq!(table.last_edit(), Some(EditDelta::new(2, 2, 6)));
+    }
+
+    #[test]
+    fn remove() {
+        let mut table = get_complex_table();
+        table.remove("Hel".len(), "lo, Wo".len());
+        assert_eq!(table.last_edit(), Some(EditDelta::new(3, 9, 3)));
+    }
+
+    #[test]
+    fn undo_redo() {
+        let mut table = get_complex_table();
+        table.remove("Hel".len(), "lo, Wo".len());
+
+        table.undo();
+        assert_eq!(table.last_edit(), Some(EditDelta::new(3, 3, 9)));
+
+        table.undo();
+        assert_eq!(table.last_edit(), Some(EditDelta::new(4, 8, 4)));
+
+        table.hot_redo();
+        assert_eq!(table.last_edit(), Some(EditDelta::new(4, 4, 8)));
+    }
+
+    #[test]
+    fn undo_at_root() {
+        let mut table = PieceTable::from("Hello, World!");
+        table.append("!");
+        table.undo();
+        table.undo();
+        assert_eq!(table.last_edit(), Some(EditDelta::new(13, 14, 13)));
+    }
+
+    #[test]
+    fn combine_after() {
+        let first = EditDelta::insertion(0, 5);
+        let second = EditDelta::removal(10, 3);
+        assert_eq!(first.then(second), EditDelta::new(0, 8, 10));
+    }
+
+    #[test]
+    fn combine_before() {
+        let first = EditDelta::removal(10, 3);
+        let second = EditDelta::insertion(2, 4);
+        assert_eq!(first.then(second), EditDelta::new(2, 13, 14));
+    }
+
+    #[test]
+    fn combine_overlapping() {
+        let first = EditDelta::insertion(5, 2);
+        let second = EditDelta::removal(6, 4);
+        assert_eq!(first.then(second), EditDelta::new(5, 8, 6));
+    }
+}
+
+mod disable_history {
+    use super::*;
+
+    #[test]
+    fn from_readonly() {
+        let pieces = vec![
+            Piece::new(PieceSource::Original, 0, 2),
+            Piece::new(PieceSource::Addition, 0, 2),
+            Piece::new(PieceSource::Addition, 4, 4),
+            Piece::new(PieceSource::Addition, 2, 2),
+            Piece::new(PieceSource::Original, 2, 3),
+        ];
+
+        let mut table = PieceTable::from_readonly("Held!");
+        table.insert(2, "llor");
+        table.insert(4, "o, W");
+        validate_table(&table, "Held!", "lloro, W", &pieces, "Hello, World!");
+        assert_eq!(table.history.changes.len(), 1);
+        assert!(table.history.changes[0].commit.changes.is_empty());
+    }
+
+    #[test]
+    fn undo_redo_noop() {
+        let pieces = vec![Piece::new(PieceSource::Original, 0, "Hello".len())];
+
+        let mut table = PieceTable::from_readonly("Hello, World!");
+        table.remove("Hello".len(), ", World!".len());
+        table.undo();
+        validate_table(&table, "Hello, World!", "", &pieces, "Hello");
+        table.hot_redo();
+        validate_table(&table, "Hello, World!", "", &pieces, "Hello");
+    }
+
+    #[test]
+    fn discards_existing_history() {
+        let mut table = get_complex_table();
+        table.disable_history();
+        table.remove(0, "Hello, ".len());
+        table.undo();
+
+        assert_eq!(table.history.changes.len(), 1);
+        assert_eq!(table.history.head, 0);
+        assert_eq!(table.to_string(), "World!");
+    }
+
+    #[test]
+    fn last_edit() {
+        let mut table = PieceTable::from_readonly("Hello");
+        table.append(", World!");
+        assert_eq!(table.last_edit(), Some(EditDelta::new(5, 5, 13)));
+    }
+}
+
+mod no_history {
+    use crate::BoundsPolicy;
+
+    use super::*;
+
+    #[test]
+    fn insert() {
+        let pieces = vec![
+            Piece::new(PieceSource::Original, 0, 2),
+            Piece::new(PieceSource::Addition, 0, 2),
+            Piece::new(PieceSource::Addition, 4, 4),
+            Piece::new(PieceSource::Addition, 2, 2),
+            Piece::new(PieceSource::Original, 2, 3),
+        ];
+
+        let mut table = PieceTable::from("Held!");
+        table.insert_no_history(2, "llor");
+        table.insert_no_history(4, "o, W");
+        validate_table(&table, "Held!", "lloro, W", &pieces, "Hello, World!");
+        assert_eq!(table.history.changes.len(), 1);
+        assert_eq!(table.last_edit(), Some(EditDelta::new(4, 4, 8)));
+    }
+
+    #[test]
+    fn remove() {
+        let mut table = get_complex_table();
+        table.remove_no_history(5, 7);
+        assert_eq!(table.to_string(), "Hello!");
+        assert_eq!(table.history.changes.len(), 3);
+        assert_eq!(table.history.head, 2);
+    }
+
+    #[test]
+    fn keeps_history() {
+        let mut table = PieceTable::from("Hello");
+        table.append(",");
+        table.insert_no_history(0, "> ");
+        table.reset_history();
+        table.append(" World!");
+        table.undo();
+        assert_eq!(table.to_string(), "> Hello,");
+        assert!(!table.undo());
+    }
+
+    #[test]
+    fn modifies_version() {
+        let mut table = PieceTable::from("Hello");
+        let version = table.version();
+        table.remove_no_history(0, 1);
+        assert!(table.is_modified_since(version));
+    }
+
+    #[test]
+    fn clamped_removal() {
+        let mut table = PieceTable::from("Hello");
+        table.set_bounds_policy(BoundsPolicy::Clamp);
+        table.remove_no_history(3, 10);
+        table.remove_no_history(10, 1);
+        assert_eq!(table.to_string(), "Hel");
+    }
+
+    #[test]
+    #[should_panic(expected = "Insert position 6 out of bounds for length 5")]
+    fn insert_out_of_bounds() {
+        let mut table = PieceTable::from("Hello");
+        table.insert_no_history(6, "!");
+    }
+}
+
+mod range_slice {
+    use std::ops::Bound;
+
+    use super::*;
+
+    #[test]
+    fn matches_slice() {
+        let table = get_complex_table();
+
+        for i in 0.."Hello, World!".len() {
+            for j in (i + 1)..="Hello, World!".len() {
+                assert_eq!(table.range_slice(i..j), table.slice(i..j));
+            }
+        }
+        assert_eq!(table.range_slice(..), table.slice(..));
+    }
+
+    #[test]
+    fn excluded_start() {
+        let table = get_complex_table();
+        let range = (Bound::Excluded(6), Bound::Unbounded);
+        assert_eq!(table.range_slice(range), "World!");
+    }
+
+    #[test]
+    fn included_end() {
+        let table = get_complex_table();
+        let range = (Bound::Excluded(6), Bound::Included(11));
+        assert_eq!(table.range_slice(range), "World");
+    }
+
+    #[test]
+    #[should_panic(expected = "Upper inclusive index must not be usize::MAX")]
+    fn included_end_max() {
+        let table = get_complex_table();
+        table.range_slice(0..=usize::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "Lower exclusive index must not be usize::MAX")]
+    fn excluded_start_max() {
+        let table = get_complex_table();
+        table.range_slice((Bound::Excluded(usize::MAX), Bound::Unbounded));
+    }
+}
+
+mod slice_ranges {
+    use super::*;
+
+    #[test]
+    fn complex() {
+        // "He" "ll" "o, W" "or" "ld!"
+        let table = get_complex_table();
+        assert_eq!(table.slice_ranges(&[1..3, 5..9, 12..13]), "el, Wo!");
+    }
+
+    #[test]
+    fn several_ranges_in_one_piece() {
+        let table = get_complex_table();
+        assert_eq!(table.slice_ranges(&[4..5, 5..5, 6..8]), "o W");
+    }
+
+    #[test]
+    fn adjacent_ranges() {
+        let table = get_complex_table();
+        assert_eq!(table.slice_ranges(&[0..4, 4..13]), "Hello, World!");
+    }
+
+    #[test]
+    fn empty() {
+        let table = get_complex_table();
+        assert_eq!(table.slice_ranges(&[]), "");
+        assert_eq!(table.slice_ranges(&[3..3, 13..13]), "");
+    }
+
+    #[test]
+    #[should_panic(expected = "Range 4..6 starts before the previous range ends at 5")]
+    fn overlapping() {
+        let table = get_complex_table();
+        table.slice_ranges(&[1..5, 4..6]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Range 10..14 out of bounds for length 13")]
+    fn out_of_bounds() {
+        let table = get_complex_table();
+        table.slice_ranges(&[1..5, 10..14]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Slice boundary 2 falls inside a character")]
+    fn inside_char() {
+        let table = PieceTable::from("aéb€c");
+        table.slice_ranges(&[0..1, 2..4]);
+    }
+}
+
+mod try_insert {
+    use super::*;
+
+    #[test]
+    fn success() {
+        let pieces = vec![
+            Piece::new(PieceSource::Original, 0, "Hello".len()),
+            Piece::new(PieceSource::Addition, 0, ", ".len()),
+            Piece::new(PieceSource::Original, 5, "World!".len()),
+        ];
+
+        let mut table = PieceTable::from("HelloWorld!");
+        assert_eq!(table.try_insert("Hello".len(), ", "), Ok(()));
+        validate_table(&table, "HelloWorld!", ", ", &pieces, "Hello, World!");
+    }
+
+    #[test]
+    fn inside_char() {
+        let mut table = PieceTable::from("aéb");
+        assert_eq!(
+            table.try_insert(2, "x"),
+            Err(EditError::NotCharBoundary { byte: 2 })
+        );
+        assert_eq!(table.to_string(), "aéb");
+        assert_eq!(table.history.changes.len(), 1);
+    }
+
+    #[test]
+    fn out_of_bounds_pos() {
+        let mut table = PieceTable::from("Hello");
+        assert_eq!(
+            table.try_insert(6, "!"),
+            Err(EditError::PositionOutOfBounds { pos: 6, len: 5 })
+        );
+    }
+
+    #[test]
+    fn empty_string() {
+        let mut table = PieceTable::from("Hello");
+        assert_eq!(table.try_append(""), Err(EditError::EmptyString));
+    }
+
+    #[test]
+    fn rejected_leaves_history() {
+        let mut table = PieceTable::from("Hello");
+        table.append("!");
+        assert!(table.try_insert(7, "?").is_err());
+        assert!(table.try_insert(0, "").is_err());
+        assert_eq!(table.history.changes.len(), 2);
+        assert_eq!(table.history.head, 1);
+        assert_eq!(table.last_edit(), Some(EditDelta::new(5, 5, 6)));
+
+        table.undo();
+        assert_eq!(table.to_string(), "Hello");
+    }
+
+    #[test]
+    fn too_large() {
+        let pieces = vec![Piece::new(PieceSource::Original, 0, "Hello".len())];
+
+        let mut table = PieceTable::from("Hello");
+        table.set_max_len(10);
+        assert_eq!(
+            table.try_append(", World!"),
+            Err(EditError::TooLarge {
+                len: 13,
+                max_len: 10
+            })
+        );
+        validate_table(&table, "Hello", "", &pieces, "Hello");
+        assert_eq!(table.history.changes.len(), 1);
+    }
+
+    #[test]
+    fn exactly_max_len() {
+        let mut table = PieceTable::from("Hello");
+        table.set_max_len(13);
+        assert_eq!(table.try_append(", World!"), Ok(()));
+        assert_eq!(table.to_string(), "Hello, World!");
+    }
+
+    #[test]
+    #[should_panic(expected = "Edit would grow the text to 13 bytes, exceeding the maximum of 10")]
+    fn insert_too_large() {
+        let mut table = PieceTable::from("Hello");
+        table.set_max_len(10);
+        table.append(", World!");
+    }
+}
+
+mod try_remove {
+    use crate::BoundsPolicy;
+
+    use super::*;
+
+    #[test]
+    fn success() {
+        let pieces = vec![
+            Piece::new(PieceSource::Original, 0, 2),
+            Piece::new(PieceSource::Addition, 0, 2),
+            Piece::new(PieceSource::Addition, 4, 1),
+            Piece::new(PieceSource::Original, 4, 1),
+        ];
+
+        let mut table = get_complex_table();
+        assert_eq!(table.try_remove(5, 7), Ok(()));
+        validate_table(&table, "Held!", "lloro, W", &pieces, "Hello!");
+
+        table.undo();
+        assert_eq!(table.to_string(), "Hello, World!");
+    }
+
+    #[test]
+    fn out_of_bounds() {
+        let mut table = get_complex_table();
+        assert_eq!(
+            table.try_remove(10, 5),
+            Err(EditError::RangeOutOfBounds {
+                start: 10,
+                end: 15,
+                len: 13
+            })
+        );
+        assert_eq!(
+            table.try_remove(14, 0),
+            Err(EditError::RangeOutOfBounds {
+                start: 14,
+                end: 14,
+                len: 13
+            })
+        );
+    }
+
+    #[test]
+    fn overflowing_range() {
+        let mut table = get_complex_table();
+        assert_eq!(
+            table.try_remove(1, usize::MAX),
+            Err(EditError::RangeOutOfBounds {
+                start: 1,
+                end: usize::MAX,
+                len: 13
+            })
+        );
+    }
+
+    #[test]
+    fn empty_range() {
+        let mut table = get_complex_table();
+        assert_eq!(table.try_remove(5, 0), Err(EditError::EmptyRange));
+    }
+
+    #[test]
+    fn rejected_leaves_table() {
+        let pieces = vec![
+            Piece::new(PieceSource::Original, 0, 2),
+            Piece::new(PieceSource::Addition, 0, 2),
+            Piece::new(PieceSource::Addition, 4, 4),
+            Piece::new(PieceSource::Addition, 2, 2),
+            Piece::new(PieceSource::Original, 2, 3),
+        ];
+
+        let mut table = get_complex_table();
+        assert!(table.try_remove(3, 20).is_err());
+        assert!(table.try_remove(3, 0).is_err());
+        validate_table(&table, "Held!", "lloro, W", &pieces, "Hello, World!");
+        assert_eq!(table.history.changes.len(), 3);
+        assert_eq!(table.last_edit(), Some(EditDelta::new(4, 4, 8)));
+    }
+
+    #[test]
+    fn inside_char() {
+        let mut table = PieceTable::from("aé");
+        table.append("€b");
+        assert_eq!(
+            table.try_remove(2, 2),
+            Err(EditError::NotCharBoundary { byte: 2 })
+        );
+        assert_eq!(
+            table.try_remove(1, 3),
+            Err(EditError::NotCharBoundary { byte: 4 })
+        );
+        assert_eq!(table.try_remove(1, 5), Ok(()));
+        assert_eq!(table.to_string(), "ab");
+    }
+
+    #[test]
+    fn clamped() {
+        let mut table = get_complex_table();
+        table.set_bounds_policy(BoundsPolicy::Clamp);
+        assert_eq!(table.try_remove(5, 20), Ok(()));
+        assert_eq!(table.try_remove(20, 1), Ok(()));
+        assert_eq!(table.to_string(), "Hello");
+    }
+}
+
+mod insert_bytes {
+    use super::*;
+
+    #[test]
+    fn valid() {
+        let pieces = vec![
+            Piece::new(PieceSource::Original, 0, "Hello".len()),
+            Piece::new(PieceSource::Addition, 0, ", ".len()),
+            Piece::new(PieceSource::Original, 5, "World!".len()),
+        ];
+
+        let mut table = PieceTable::from("HelloWorld!");
+        assert!(table.insert_bytes(5, b", ").is_ok());
+        validate_table(&table, "HelloWorld!", ", ", &pieces, "Hello, World!");
+    }
+
+    #[test]
+    fn multi_byte() {
+        let mut table = PieceTable::from("ac");
+        assert!(table.insert_bytes(1, "ñ€".as_bytes()).is_ok());
+        assert_eq!(table.to_string(), "añ€c");
+    }
+
+    #[test]
+    fn invalid() {
+        let mut table = PieceTable::from("Hello");
+        // Truncated "€"
+        let err = table.insert_bytes(5, &[b'!', 0xe2, 0x82]).unwrap_err();
+        assert_eq!(err.valid_up_to(), 1);
+
+        let pieces = vec![Piece::new(PieceSource::Original, 0, "Hello".len())];
+        validate_table(&table, "Hello", "", &pieces, "Hello");
+        assert_eq!(table.history.changes.len(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Inserted string must not be empty")]
+    fn empty() {
+        let mut table = PieceTable::from("Hello");
+        let _ = table.insert_bytes(0, b"");
+    }
+}
+
+mod insert_chars {
+    use super::*;
+
+    #[test]
+    fn middle() {
+        let pieces = vec![
+            Piece::new(PieceSource::Original, 0, "Hello".len()),
+            Piece::new(PieceSource::Addition, 0, ", ".len()),
+            Piece::new(PieceSource::Original, 5, "World!".len()),
+        ];
+
+        let mut table = PieceTable::from("HelloWorld!");
+        table.insert_chars(5, [',', ' ']);
+        validate_table(&table, "HelloWorld!", ", ", &pieces, "Hello, World!");
+    }
+
+    #[test]
+    fn single_commit() {
+        let mut table = PieceTable::from("ac");
+        table.insert_chars(1, "ñb€".chars());
+        assert_eq!(table.to_string(), "añb€c");
+        assert_eq!(table.history.changes.len(), 2);
+    }
+
+    #[test]
+    fn empty() {
+        let mut table = PieceTable::from("Hello");
+        table.insert_chars(3, std::iter::empty());
+        assert_eq!(table.to_string(), "Hello");
+        assert_eq!(table.history.changes.len(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Insert position 6 out of bounds for length 5")]
+    fn out_of_bounds() {
+        let mut table = PieceTable::from("Hello");
+        table.insert_chars(6, ['!']);
+    }
+}
+
+mod stats {
+    use crate::Stats;
+
+    use super::*;
+
+    #[test]
+    fn original_only() {
+        let table = PieceTable::from("Hello, World!");
+        let stats = Stats {
+            piece_count: 1,
+            byte_len: 13,
+            addition_bytes: 0,
+            reclaimable_bytes: 0,
+        };
+        assert_eq!(table.stats(), stats);
+    }
+
+    #[test]
+    fn complex() {
+        let table = get_complex_table();
+        let stats = Stats {
+            piece_count: 5,
+            byte_len: 13,
+            addition_bytes: 8,
+            reclaimable_bytes: 0,
+        };
+        assert_eq!(table.stats(), stats);
+    }
+
+    #[test]
+    fn removed_additions() {
+        let mut table = get_complex_table();
+        table.remove("Hel".len(), "lo, Wo".len());
+        let stats = Stats {
+            piece_count: 4,
+            byte_len: 7,
+            addition_bytes: 8,
+            reclaimable_bytes: 6,
+        };
+        assert_eq!(table.stats(), stats);
+    }
+
+    #[test]
+    fn empty() {
+        let table = PieceTable::from("");
+        let stats = Stats {
+            piece_count: 0,
+            byte_len: 0,
+            addition_bytes: 0,
+            reclaimable_bytes: 0,
+        };
+        assert_eq!(table.stats(), stats);
+    }
+}
+
+mod addition_liveness {
+    use super::*;
+
+    #[test]
+    fn original_only() {
+        let table = PieceTable::from("Hello, World!");
+        assert_eq!(table.addition_liveness(), (0, 0));
+    }
+
+    #[test]
+    fn complex() {
+        let table = get_complex_table();
+        assert_eq!(table.addition_liveness(), (8, 8));
+    }
+
+    #[test]
+    fn removed_and_undone() {
+        let mut table = get_complex_table();
+        table.remove("Hel".len(), "lo, Wo".len());
+        assert_eq!(table.addition_liveness(), (2, 8));
+
+        table.undo();
+        table.undo();
+        assert_eq!(table.addition_liveness(), (4, 8));
+    }
+}
+
+mod history_stats {
+    use crate::{
+        history::{commit::Commit, entry::Entry},
+        HistoryStats,
+    };
+
+    use super::*;
+
+    #[test]
+    fn fresh() {
+        let table = PieceTable::from("Hello");
+        let stats = HistoryStats {
+            total_entries: 1,
+            reachable_entries: 1,
+            depth_from_root: 0,
+        };
+        assert_eq!(table.history_stats(), stats);
+    }
+
+    #[test]
+    fn undone() {
+        let mut table = get_complex_table();
+        table.undo();
+        let stats = HistoryStats {
+            total_entries: 3,
+            reachable_entries: 3,
+            depth_from_root: 1,
+        };
+        assert_eq!(table.history_stats(), stats);
+    }
+
+    #[test]
+    fn branches() {
+        let mut table = get_complex_table();
+        table.undo();
+        table.undo();
+        table.append("!");
+        table.append("?");
+        let stats = HistoryStats {
+            total_entries: 5,
+            reachable_entries: 5,
+            depth_from_root: 2,
+        };
+        assert_eq!(table.history_stats(), stats);
+    }
+
+    #[test]
+    fn orphaned() {
+        let mut table = get_complex_table();
+        table.history.changes.push(Entry::new(Commit::new()));
+        let stats = HistoryStats {
+            total_entries: 4,
+            reachable_entries: 3,
+            depth_from_root: 2,
+        };
+        assert_eq!(table.history_stats(), stats);
+    }
+
+    #[test]
+    fn disabled() {
+        let mut table = PieceTable::from_readonly("Hello");
+        table.append(", World!");
+        assert_eq!(table.history_stats().total_entries, 1);
+        assert_eq!(table.history_stats().depth_from_root, 0);
+    }
+}
+
+mod clone {
+    use std::sync::{Arc, Mutex};
+
+    use crate::PerWord;
+
+    use super::*;
+
+    #[test]
+    fn independent_edits() {
+        let table = get_complex_table();
+        let mut clone = table.clone();
+        clone.append(" Bye!");
+        clone.remove(0, 7);
+
+        let pieces = vec![
+            Piece::new(PieceSource::Original, 0, 2),
+            Piece::new(PieceSource::Addition, 0, 2),
+            Piece::new(PieceSource::Addition, 4, 4),
+            Piece::new(PieceSource::Addition, 2, 2),
+            Piece::new(PieceSource::Original, 2, 3),
+        ];
+        validate_table(&table, "Held!", "lloro, W", &pieces, "Hello, World!");
+        assert_eq!(clone.to_string(), "World! Bye!");
+        assert_eq!(clone.addition_text(), "lloro, W Bye!");
+    }
+
+    #[test]
+    fn independent_history() {
+        let mut table = get_complex_table();
+        let mut clone = table.clone();
+        assert_eq!(clone.version(), table.version());
+
+        assert!(clone.undo());
+        assert_eq!(clone.to_string(), "Hellorld!");
+        assert_eq!(table.to_string(), "Hello, World!");
+        assert!(!table.can_redo());
+
+        table.undo();
+        table.undo();
+        assert_eq!(table.to_string(), "Held!");
+        assert!(clone.redo());
+        assert_eq!(clone.to_string(), "Hello, World!");
+    }
+
+    #[test]
+    fn keeps_settings() {
+        let mut table = PieceTable::from("Hello,\nWorld!");
+        table.set_max_len(20);
+        table.set_slice_cache(4);
+        table.set_line_index(true);
+        table.disable_history();
+
+        let mut clone = table.clone();
+        assert_eq!(clone.line_col_of(9), (1, 2));
+        assert_eq!(
+            clone.try_append("!".repeat(10)),
+            Err(EditError::TooLarge {
+                len: 23,
+                max_len: 20
+            })
+        );
+        clone.append("!");
+        assert!(!clone.can_undo());
+        assert_eq!(table.line_count(), 2);
+    }
+
+    #[test]
+    fn drops_callbacks() {
+        let deltas = Arc::new(Mutex::new(0));
+        let mut table = PieceTable::from("Hello");
+        let observed = Arc::clone(&deltas);
+        table.on_structural_change(move |_| *observed.lock().unwrap() += 1);
+        table.set_commit_strategy(Box::new(PerWord));
+
+        let mut clone = table.clone();
+        clone.append("!");
+        clone.append("!");
+        assert_eq!(*deltas.lock().unwrap(), 0);
+        clone.undo();
+        assert_eq!(clone.to_string(), "Hello!");
+    }
+}
+
+mod replace {
+    use crate::{history::change::ChangeType, BoundsPolicy};
+
+    use super::*;
+
+    #[test]
+    fn single_undo_step() {
+        let mut table = get_complex_table();
+        table.replace(7, 5, "Rust");
+        assert_eq!(table.to_string(), "Hello, Rust!");
+        assert_eq!(table.last_edit(), Some(EditDelta::new(7, 12, 11)));
+        assert_eq!(table.history.changes.len(), 4);
+
+        assert!(table.undo());
+        assert_eq!(table.to_string(), "Hello, World!");
+        assert!(table.redo());
+        assert_eq!(table.to_string(), "Hello, Rust!");
+    }
+
+    #[test]
+    fn deletions_before_insertions() {
+        let mut table = PieceTable::from("Hello, World!");
+        table.replace(7, 5, "Rust");
+
+        let changes = &table.history.changes[1].commit.changes;
+        let first_insertion = changes
+            .iter()
+            .position(|change| change.typ == ChangeType::Insertion)
+            .unwrap();
+        assert!(changes[first_insertion..]
+            .iter()
+            .all(|change| change.typ == ChangeType::Insertion));
+    }
+
+    #[test]
+    fn insert_only() {
+        let mut table = PieceTable::from("HelloWorld!");
+        table.replace(5, 0, ", ");
+        assert_eq!(table.to_string(), "Hello, World!");
+    }
+
+    #[test]
+    fn remove_only() {
+        let mut table = get_complex_table();
+        table.replace(5, 7, "");
+        assert_eq!(table.to_string(), "Hello!");
+        table.undo();
+        assert_eq!(table.to_string(), "Hello, World!");
+    }
+
+    #[test]
+    #[should_panic(expected = "Replace range 10..15 out of bounds for length 13")]
+    fn out_of_bounds() {
+        let mut table = get_complex_table();
+        table.replace(10, 5, "x");
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds for length 13")]
+    fn overflowing_range() {
+        let mut table = get_complex_table();
+        table.replace(1, usize::MAX, "x");
+    }
+
+    #[test]
+    fn clamped() {
+        let mut table = get_complex_table();
+        table.set_bounds_policy(BoundsPolicy::Clamp);
+        table.replace(7, 20, "Rust");
+        assert_eq!(table.to_string(), "Hello, Rust");
+        table.replace(20, 1, "!");
+        assert_eq!(table.to_string(), "Hello, Rust!");
+    }
+}
+
+mod replace_range {
+    use super::*;
+
+    #[test]
+    fn replace() {
+        let mut table = get_complex_table();
+        table.replace_range(7..12, "Rust");
+        assert_eq!(table.to_string(), "Hello, Rust!");
+        assert_eq!(table.last_edit(), Some(EditDelta::new(7, 12, 11)));
+    }
+
+    #[test]
+    fn insert_only() {
+        let mut table = PieceTable::from("HelloWorld!");
+        table.replace_range(5..5, ", ");
+        assert_eq!(table.to_string(), "Hello, World!");
+    }
+
+    #[test]
+    fn remove_only() {
+        let mut table = get_complex_table();
+        table.replace_range(5..7, "");
+        assert_eq!(table.to_string(), "HelloWorld!");
+    }
+
+    #[test]
+    fn noop() {
+        let mut table = get_complex_table();
+        table.replace_range(5..5, "");
+        assert_eq!(table.to_string(), "Hello, World!");
+        assert_eq!(table.history.changes.len(), 3);
+    }
+
+    #[test]
+    fn unchanged_text() {
+        let mut table = get_complex_table();
+        let pieces = table.pieces.iter().copied().collect::<Vec<_>>();
+        table.replace_range(3..9, "lo, Wo");
+        validate_table(&table, "Held!", "lloro, W", &pieces, "Hello, World!");
+        assert_eq!(table.history.changes.len(), 3);
+        assert_eq!(table.last_edit(), Some(EditDelta::new(4, 4, 8)));
+    }
+
+    #[test]
+    fn same_length_different_text() {
+        let mut table = get_complex_table();
+        table.replace_range(3..9, "lo, Wi");
+        assert_eq!(table.to_string(), "Hello, Wirld!");
+        assert_eq!(table.history.changes.len(), 4);
+    }
+
+    #[test]
+    fn single_undo() {
+        let pieces = vec![Piece::new(PieceSource::Original, 0, "Hello, World!".len())];
+
+        let mut table = PieceTable::from("Hello, World!");
+        table.replace_range(0..5, "Bye");
+        table.undo();
+        validate_table(&table, "Hello, World!", "Bye", &pieces, "Hello, World!");
+        table.hot_redo();
+        assert_eq!(table.to_string(), "Bye, World!");
+    }
+
+    #[test]
+    #[should_panic(expected = "Replace range 10..20 out of bounds for length 13")]
+    fn out_of_bounds() {
+        let mut table = get_complex_table();
+        table.replace_range(10..20, "");
+    }
+
+    #[test]
+    #[should_panic(expected = "Byte 2 is not a char boundary")]
+    fn empty_inside_char() {
+        let mut table = PieceTable::from("aéb");
+        table.replace_range(2..2, "x");
+    }
+}
+
+mod checked_slice {
+    use super::*;
+
+    #[test]
+    fn matches_slice() {
+        let table = get_complex_table();
+
+        for i in 0.."Hello, World!".len() {
+            for j in (i + 1)..="Hello, World!".len() {
+                assert_eq!(table.checked_slice(i..j), Ok(table.slice(i..j)));
+            }
+        }
+    }
+
+    #[test]
+    fn empty_range() {
+        let table = get_complex_table();
+        assert_eq!(table.checked_slice(4..4), Ok(String::new()));
+    }
+
+    #[test]
+    fn out_of_bounds() {
+        let table = get_complex_table();
+        assert_eq!(
+            table.checked_slice(5..20),
+            Err(EditError::RangeOutOfBounds {
+                start: 5,
+                end: 20,
+                len: 13
+            })
+        );
+    }
+
+    #[test]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn reversed() {
+        let table = get_complex_table();
+        assert_eq!(
+            table.checked_slice(5..2),
+            Err(EditError::RangeOutOfBounds {
+                start: 5,
+                end: 2,
+                len: 13
+            })
+        );
+    }
+
+    #[test]
+    fn not_char_boundary() {
+        let mut table = PieceTable::from("aé");
+        table.append("öb");
+        assert_eq!(
+            table.checked_slice(2..6),
+            Err(EditError::NotCharBoundary { byte: 2 })
+        );
+        assert_eq!(
+            table.checked_slice(0..4),
+            Err(EditError::NotCharBoundary { byte: 4 })
+        );
+        assert_eq!(table.checked_slice(1..5), Ok(String::from("éö")));
+    }
+
+    #[test]
+    fn corrupt_piece() {
+        let mut table = get_complex_table();
+        table.pieces[2].offset = 100;
+        assert_eq!(table.checked_slice(0..3), Ok(String::from("Hel")));
+        assert_eq!(
+            table.checked_slice(0..6),
+            Err(EditError::InvalidPiece { index: 2 })
+        );
+    }
+}
+
+mod bounds_policy {
+    use crate::BoundsPolicy;
+
+    use super::*;
+
+    fn get_clamped_table() -> PieceTable {
+        let mut table = get_complex_table();
+        table.set_bounds_policy(BoundsPolicy::Clamp);
+        table
+    }
+
+    #[test]
+    #[should_panic(expected = "Insert position 14 out of bounds for length 13")]
+    fn panic_is_default() {
+        let mut table = get_complex_table();
+        table.insert(14, "!");
+    }
+
+    #[test]
+    fn clamp_insert() {
+        let mut table = get_clamped_table();
+        table.insert(usize::MAX, "!!");
+        assert_eq!(table.to_string(), "Hello, World!!!");
+        assert_eq!(table.try_insert(42, "?"), Ok(()));
+        assert_eq!(table.to_string(), "Hello, World!!!?");
+    }
+
+    #[test]
+    fn clamp_remove_over_long() {
+        let mut table = get_clamped_table();
+        table.remove(5, 100);
+        assert_eq!(table.to_string(), "Hello");
+        assert_eq!(table.len(), 5);
+
+        table.undo();
+        assert_eq!(table.to_string(), "Hello, World!");
+    }
+
+    #[test]
+    fn clamp_remove_past_end() {
+        let mut table = get_clamped_table();
+        let pieces = table.pieces.iter().copied().collect::<Vec<_>>();
+        table.remove(20, 3);
+        validate_table(&table, "Held!", "lloro, W", &pieces, "Hello, World!");
+
+        // The ignored removal must not create an undo step
+        table.undo();
+        assert_eq!(table.to_string(), "Hellorld!");
+    }
+
+    #[test]
+    fn clamp_slice() {
+        let table = get_clamped_table();
+        assert_eq!(table.range_slice(7..100), "World!");
+        assert_eq!(table.range_slice(20..30), "");
+        assert_eq!(table.range_slice(..=usize::MAX - 1), "Hello, World!");
+        assert_eq!(table.slice(0..5), "Hello");
+    }
+
+    #[test]
+    #[should_panic(expected = "Slice 7..100 out of bounds for length 13")]
+    fn panic_slice() {
+        let table = get_complex_table();
+        table.range_slice(7..100);
+    }
+
+    #[test]
+    #[should_panic(expected = "Remove range 5..105 out of bounds for length 13")]
+    fn panic_remove() {
+        let mut table = get_complex_table();
+        table.remove(5, 100);
+    }
+
+    #[test]
+    fn switch_back_to_panic() {
+        let mut table = get_clamped_table();
+        table.set_bounds_policy(BoundsPolicy::Panic);
+        assert_eq!(
+            table.try_insert(42, "!"),
+            Err(EditError::PositionOutOfBounds { pos: 42, len: 13 })
+        );
+    }
+}
+
+mod insert_with_inverse {
+    use crate::InverseEdit;
+
+    use super::*;
+
+    #[test]
+    fn middle() {
+        let mut table = get_complex_table();
+        let inverse = table.insert_with_inverse(5, " there");
+        assert_eq!(inverse, InverseEdit::Remove { pos: 5, len: 6 });
+        assert_eq!(table.to_string(), "Hello there, World!");
+
+        let redo = inverse.apply(&mut table);
+        assert_eq!(table.to_string(), "Hello, World!");
+        assert_eq!(
+            redo,
+            InverseEdit::Insert {
+                pos: 5,
+                text: String::from(" there")
+            }
+        );
+    }
+
+    #[test]
+    fn recorded_in_history() {
+        let mut table = PieceTable::from("Hello");
+        table.insert_with_inverse(5, "!");
+        table.undo();
+        assert_eq!(table.to_string(), "Hello");
+    }
+
+    #[test]
+    #[should_panic(expected = "Insert position 6 out of bounds for length 5")]
+    fn out_of_bounds() {
+        let mut table = PieceTable::from("Hello");
+        table.insert_with_inverse(6, "!");
+    }
+}
+
+mod remove_with_inverse {
+    use crate::{BoundsPolicy, InverseEdit};
+
+    use super::*;
+
+    #[test]
+    fn across_pieces() {
+        let mut table = get_complex_table();
+        let inverse = table.remove_with_inverse(3, 6);
+        assert_eq!(
+            inverse,
+            InverseEdit::Insert {
+                pos: 3,
+                text: String::from("lo, Wo")
+            }
+        );
+        assert_eq!(table.to_string(), "Helrld!");
+
+        let redo = inverse.apply(&mut table);
+        assert_eq!(table.to_string(), "Hello, World!");
+        assert_eq!(redo, InverseEdit::Remove { pos: 3, len: 6 });
+    }
+
+    #[test]
+    fn clamped() {
+        let mut table = get_complex_table();
+        table.set_bounds_policy(BoundsPolicy::Clamp);
+        let inverse = table.remove_with_inverse(7, 100);
+        assert_eq!(
+            inverse,
+            InverseEdit::Insert {
+                pos: 7,
+                text: String::from("World!")
+            }
+        );
+
+        let inverse = table.remove_with_inverse(20, 1);
+        assert_eq!(
+            inverse,
+            InverseEdit::Insert {
+                pos: 7,
+                text: String::new()
+            }
+        );
+        assert_eq!(
+            inverse.apply(&mut table),
+            InverseEdit::Remove { pos: 7, len: 0 }
+        );
+        assert_eq!(table.to_string(), "Hello, ");
+    }
+
+    #[test]
+    #[should_panic(expected = "Remove range 3..20 out of bounds for length 13")]
+    fn out_of_bounds() {
+        let mut table = get_complex_table();
+        table.remove_with_inverse(3, 17);
+    }
+
+    #[test]
+    #[should_panic(expected = "Remove range 3..18446744073709551615 out of bounds for length 13")]
+    fn overflowing() {
+        let mut table = get_complex_table();
+        table.remove_with_inverse(3, usize::MAX);
+    }
+}
+
+mod pieces_in {
+    use crate::PieceInfo;
+
+    use super::*;
+
+    #[test]
+    fn inside_single_piece() {
+        let table = get_complex_table();
+        let pieces: Vec<_> = table.pieces_in(6..8).collect();
+        assert_eq!(
+            pieces,
+            vec![(
+                PieceInfo {
+                    index: 2,
+                    source: PieceSource::Addition,
+                    offset: 4,
+                    start: 4,
+                    text: "o, W",
+                },
+                2..4
+            )]
+        );
+    }
+
+    #[test]
+    fn piece_boundaries() {
+        let table = get_complex_table();
+        let ranges: Vec<_> = table
+            .pieces_in(2..6)
+            .map(|(info, range)| (info.index, range))
+            .collect();
+        assert_eq!(ranges, vec![(1, 0..2), (2, 0..2)]);
+    }
+
+    #[test]
+    fn whole_text() {
+        let table = get_complex_table();
+        let text: String = table
+            .pieces_in(0..table.len())
+            .map(|(info, range)| &info.text[range])
+            .collect();
+        assert_eq!(text, "Hello, World!");
+    }
+
+    #[test]
+    fn empty_range() {
+        let table = get_complex_table();
+        assert_eq!(table.pieces_in(4..4).count(), 0);
+        assert_eq!(PieceTable::from("").pieces_in(0..0).count(), 0);
+    }
+
+    #[test]
+    fn after_edits() {
+        let mut table = get_complex_table();
+        assert_eq!(table.pieces_in(11..12).next().unwrap().0.index, 4);
+
+        // The indexed piece starts behind the edit are outdated
+        table.insert(0, "Oh, ");
+        let (info, range) = table.pieces_in(15..16).next().unwrap();
+        assert_eq!((info.index, info.start, range), (5, 14, 1..2));
+        table.remove(0, 4);
+        let (info, range) = table.pieces_in(11..12).next().unwrap();
+        assert_eq!((info.index, info.start, range), (4, 10, 1..2));
+    }
+
+    #[test]
+    #[should_panic(expected = "Range 10..14 out of bounds for length 13")]
+    fn out_of_bounds() {
+        let table = get_complex_table();
+        let _ = table.pieces_in(10..14);
+    }
+}
+
+mod write {
+    use std::io::{ErrorKind, Write};
+
+    use super::*;
+
+    #[test]
+    fn appends() {
+        let pieces = vec![
+            Piece::new(PieceSource::Original, 0, 5),
+            Piece::new(PieceSource::Addition, 0, 8),
+        ];
+
+        let mut table = PieceTable::from("Hello");
+        table.write_all(b", World!").unwrap();
+        validate_table(&table, "Hello", ", World!", &pieces, "Hello, World!");
+        assert_eq!(table.last_edit(), Some(EditDelta::new(5, 5, 13)));
+    }
+
+    #[test]
+    fn undoable() {
+        let mut table = PieceTable::from("Hello");
+        table.write_all(b", ").unwrap();
+        table.write_all("Wörld!".as_bytes()).unwrap();
+        assert_eq!(table.to_string(), "Hello, Wörld!");
+
+        table.undo();
+        assert_eq!(table.to_string(), "Hello, ");
+        table.undo();
+        assert_eq!(table.to_string(), "Hello");
+    }
+
+    #[test]
+    fn split_char() {
+        let mut table = PieceTable::from("Hello");
+        let bytes = ", Wö€".as_bytes();
+        assert_eq!(table.write(&bytes[..4]).unwrap(), 4);
+        assert_eq!(table.to_string(), "Hello, W");
+        assert_eq!(table.flush().unwrap_err().kind(), ErrorKind::InvalidData);
+
+        assert_eq!(table.write(&bytes[4..6]).unwrap(), 2);
+        assert_eq!(table.write(&bytes[6..7]).unwrap(), 1);
+        assert_eq!(table.to_string(), "Hello, Wö");
+        assert_eq!(table.write(&bytes[7..]).unwrap(), 1);
+        assert_eq!(table.to_string(), "Hello, Wö€");
+        table.flush().unwrap();
+
+        table.undo();
+        assert_eq!(table.to_string(), "Hello, Wö");
+    }
+
+    #[test]
+    fn byte_by_byte() {
+        let mut table = PieceTable::from("");
+        for byte in "Grüße, 世界!".as_bytes() {
+            table.write_all(&[*byte]).unwrap();
+        }
+        table.flush().unwrap();
+        assert_eq!(table.to_string(), "Grüße, 世界!");
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        let mut table = PieceTable::from("Hello");
+        let err = table.write(b"!\xff").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(table.to_string(), "Hello");
+        assert_eq!(table.history.changes.len(), 1);
+        table.flush().unwrap();
+    }
+
+    #[test]
+    fn invalid_continuation() {
+        let mut table = PieceTable::from("Hello");
+        table.write_all(b"\xc3").unwrap();
+        let err = table.write(b"!").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(table.to_string(), "Hello");
+
+        table.write_all(b"\xb6").unwrap();
+        assert_eq!(table.to_string(), "Helloö");
+    }
+
+    #[test]
+    fn too_large() {
+        let mut table = PieceTable::from("Hello");
+        table.set_max_len(6);
+        let err = table.write(b", World!").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Other);
+        assert_eq!(
+            err.to_string(),
+            "Edit would grow the text to 13 bytes, exceeding the maximum of 6"
+        );
+        assert_eq!(table.to_string(), "Hello");
+    }
+
+    #[test]
+    fn empty() {
+        let mut table = PieceTable::from("Hello");
+        assert_eq!(table.write(b"").unwrap(), 0);
+        table.flush().unwrap();
+        assert_eq!(table.history.changes.len(), 1);
+    }
+
+    #[test]
+    fn copy_from_reader() {
+        let mut table = PieceTable::from("");
+        let copied = std::io::copy(&mut "Hello, World!".as_bytes(), &mut table).unwrap();
+        assert_eq!(copied, 13);
+        assert_eq!(table.to_string(), "Hello, World!");
+    }
+}
+
+mod piece_at {
+    use super::*;
+
+    #[test]
+    fn finds_containing_piece() {
+        let table = get_complex_table();
+        assert_eq!(table.piece_at(0), (0, 0));
+        assert_eq!(table.piece_at(1), (0, 0));
+        assert_eq!(table.piece_at(2), (1, 2));
+        assert_eq!(table.piece_at(7), (2, 4));
+        assert_eq!(table.piece_at(12), (4, 10));
+        assert_eq!(table.piece_at(13), (5, 13));
+    }
+
+    #[test]
+    fn skips_empty_pieces() {
+        let mut table = PieceTable::from_parts(
+            "Hello",
+            String::new(),
+            [(PieceSource::Original, 0..2), (PieceSource::Original, 2..5)],
+        )
+        .unwrap();
+        table
+            .pieces
+            .insert(1, Piece::new(PieceSource::Original, 2, 0));
+        assert_eq!(table.piece_at(1), (0, 0));
+        assert_eq!(table.piece_at(2), (2, 2));
+    }
+
+    #[test]
+    fn empty_table() {
+        let table = PieceTable::from("");
+        assert_eq!(table.piece_at(0), (0, 0));
+    }
+
+    #[test]
+    fn follows_edits() {
+        let mut table = get_complex_table();
+        assert_eq!(table.piece_at(12), (4, 10));
+
+        table.insert(1, "e");
+        assert_eq!(table.to_string(), "Heello, World!");
+        assert_eq!(table.piece_at(13), (6, 11));
+
+        table.remove(0, 6);
+        assert_eq!(table.to_string(), ", World!");
+        assert_eq!(table.piece_at(0), (0, 0));
+        assert_eq!(table.piece_at(7), (2, 5));
+
+        table.undo();
+        assert_eq!(table.piece_at(13), (6, 11));
+        table.undo();
+        assert_eq!(table.piece_at(12), (4, 10));
+        table.hot_redo();
+        assert_eq!(table.piece_at(13), (6, 11));
+    }
+
+    #[test]
+    fn matches_linear_scan() {
+        // Small xorshift generator so failures are reproducible without extra dependencies
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut below = |n: usize| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % n as u64) as usize
+        };
+
+        let mut table = PieceTable::from("Hello, World!");
+        for _ in 0..500 {
+            let len = table.len();
+            match below(4) {
+                0 | 1 => table.insert(below(len + 1), ["a", "bc", "xyz"][below(3)]),
+                2 if len > 0 => {
+                    let pos = below(len);
+                    table.remove(pos, 1 + below((len - pos).min(4)));
+                }
+                _ => {
+                    table.undo();
+                }
+            }
+
+            let pos = below(table.len() + 1);
+            let mut start = 0;
+            let expected = table
+                .pieces
+                .iter()
+                .position(|piece| {
+                    start += piece.length;
+                    start > pos
+                })
+                .map_or((table.pieces.len(), table.len()), |idx| {
+                    (idx, start - table.pieces[idx].length)
+                });
+            assert_eq!(table.piece_at(pos), expected);
+        }
+    }
+}
+
+mod chunks {
+    use super::*;
+
+    #[test]
+    fn piece_order() {
+        let table = get_complex_table();
+        let chunks: Vec<_> = table.chunks().collect();
+        assert_eq!(chunks, vec!["He", "ll", "o, W", "or", "ld!"]);
+        assert_eq!(chunks.concat(), table.to_string());
+    }
+
+    #[test]
+    fn borrows_buffers() {
+        let table = get_complex_table();
+        let chunks: Vec<_> = table.chunks().collect();
+        assert!(std::ptr::eq(chunks[0], &table.original_text()[..2]));
+        assert!(std::ptr::eq(chunks[2], &table.addition_text()[4..]));
+    }
+
+    #[test]
+    fn after_removal() {
+        let mut table = get_complex_table();
+        table.remove(3, 8);
+        assert_eq!(table.chunks().collect::<Vec<_>>(), vec!["He", "l", "d!"]);
+    }
+
+    #[test]
+    fn empty() {
+        let table = PieceTable::from("");
+        assert_eq!(table.chunks().next(), None);
+    }
+}
+
+mod to_chunks {
+    use super::*;
+
+    #[test]
+    fn complex() {
+        let table = get_complex_table();
+        assert_eq!(table.to_chunks(), vec!["He", "ll", "o, W", "or", "ld!"]);
+    }
+
+    #[test]
+    fn empty() {
+        let table = PieceTable::from("");
+        assert!(table.to_chunks().is_empty());
+    }
+}
+
+mod write_to {
+    use std::io::{self, Write};
+
+    use crate::piece_table::line_ending::LineEnding;
+
+    use super::*;
+
+    /// Accepts `capacity` writes, fails on every later one
+    struct LimitedWriter {
+        written: Vec<u8>,
+        capacity: usize,
+        calls: usize,
+    }
+
+    impl Write for LimitedWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.calls += 1;
+            if self.calls > self.capacity {
+                return Err(io::Error::other("writer is full"));
+            }
+            self.written.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn matches_to_string() {
+        let mut table = PieceTable::from("Held!");
+        table.insert(2, "llor");
+        table.insert(4, "o, W");
+        table.remove(7, 2);
+
+        let mut out = Vec::new();
+        table.write_to(&mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), table.to_string());
+    }
+
+    #[test]
+    fn keeps_line_endings() {
+        let mut table = PieceTable::read_from("a\r\nb\r\n".as_bytes()).unwrap();
+        table.append("c\n");
+        assert_eq!(table.line_ending(), LineEnding::CrLf);
+
+        let mut out = Vec::new();
+        table.write_to(&mut out).unwrap();
+        assert_eq!(out, b"a\r\nb\r\nc\n");
+    }
+
+    #[test]
+    fn empty() {
+        let table = PieceTable::from("");
+        let mut out = Vec::new();
+        table.write_to(&mut out).unwrap();
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn stops_at_first_error() {
+        let mut table = PieceTable::from("ab");
+        table.append("cd");
+        table.append("ef");
+
+        let mut writer = LimitedWriter {
+            written: Vec::new(),
+            capacity: 1,
+            calls: 0,
+        };
+        let err = table.write_to(&mut writer).unwrap_err();
+        assert_eq!(err.to_string(), "writer is full");
+        assert_eq!(writer.written, b"ab");
+        assert_eq!(writer.calls, 2);
+    }
+
+    #[test]
+    fn dyn_writer() {
+        let table = PieceTable::from("Hello");
+        let mut out = Vec::new();
+        let writer: &mut dyn Write = &mut out;
+        table.write_to(writer).unwrap();
+        assert_eq!(out, b"Hello");
+    }
+}
+
+mod validate {
+    use super::*;
+
+    #[test]
+    fn valid() {
+        let mut table = get_complex_table();
+        table.remove(3, 6);
+        table.undo();
+        assert_eq!(table.validate(), Ok(()));
+        assert_eq!(PieceTable::from("").validate(), Ok(()));
+    }
+
+    #[test]
+    fn invalid_piece() {
+        let mut table = get_complex_table();
+        table.pieces[4].length = 10;
+        assert_eq!(table.validate(), Err(EditError::InvalidPiece { index: 4 }));
+    }
+
+    #[test]
+    fn piece_splits_char() {
+        let mut table = PieceTable::from("aé");
+        table.append("€");
+        table.pieces[1].length = 2;
+        table.total_length -= 1;
+        assert_eq!(table.validate(), Err(EditError::InvalidPiece { index: 1 }));
+    }
+
+    #[test]
+    fn length_mismatch() {
+        let mut table = get_complex_table();
+        table.total_length = 20;
+        assert_eq!(
+            table.validate(),
+            Err(EditError::LengthMismatch {
+                len: 20,
+                actual: 13
+            })
+        );
+    }
+
+    #[test]
+    fn corrupt_history() {
+        let mut table = get_complex_table();
+        table.history.head = 10;
+        assert_eq!(table.validate(), Err(EditError::InvalidHistory));
+    }
+}
+
+mod reset_history {
+    use super::*;
+
+    #[test]
+    fn recovers_corrupt_history() {
+        let mut table = get_complex_table();
+        table.history.head = 10;
+        table.reset_history();
+        assert_eq!(table.validate(), Ok(()));
+
+        assert!(!table.undo());
+        table.append(" Bye!");
+        assert!(table.undo());
+        assert!(!table.undo());
+        assert_eq!(table.to_string(), "Hello, World!");
+    }
+
+    #[test]
+    fn keeps_text() {
+        let pieces = get_complex_table().pieces.into_iter().collect::<Vec<_>>();
+        let mut table = get_complex_table();
+        table.reset_history();
+        validate_table(&table, "Held!", "lloro, W", &pieces, "Hello, World!");
+    }
+}
+
+mod buffer_text {
+    use super::*;
+
+    #[test]
+    fn complex() {
+        let table = get_complex_table();
+        assert_eq!(table.original_text(), "Held!");
+        assert_eq!(table.addition_text(), "lloro, W");
+    }
+
+    #[test]
+    fn empty() {
+        let table = PieceTable::from("");
+        assert_eq!(table.original_text(), "");
+        assert_eq!(table.addition_text(), "");
+    }
+}
+
+mod display_range {
+    use super::*;
+
+    #[test]
+    fn across_pieces() {
+        let table = get_complex_table();
+        assert_eq!(table.display_range(1..12).to_string(), "ello, World");
+        assert_eq!(table.display_range(0..13).to_string(), "Hello, World!");
+    }
+
+    #[test]
+    fn inside_piece() {
+        let table = get_complex_table();
+        assert_eq!(table.display_range(5..7).to_string(), ", ");
+    }
+
+    #[test]
+    fn empty() {
+        let table = get_complex_table();
+        assert_eq!(table.display_range(4..4).to_string(), "");
+        assert_eq!(PieceTable::from("").display_range(0..0).to_string(), "");
+    }
+
+    #[test]
+    fn formatting() {
+        let table = get_complex_table();
+        assert_eq!(format!("<{}>", table.display_range(7..12)), "<World>");
+    }
+
+    #[test]
+    #[should_panic(expected = "Range 7..14 out of bounds for length 13")]
+    fn out_of_bounds() {
+        let table = get_complex_table();
+        let _ = table.display_range(7..14).to_string();
+    }
+
+    #[test]
+    #[should_panic(expected = "Slice boundary 2 falls inside a character")]
+    fn inside_char() {
+        let table = PieceTable::from("aéb");
+        let _ = table.display_range(2..4).to_string();
+    }
+
+    #[test]
+    #[should_panic(expected = "Slice boundary 3 falls inside a character")]
+    fn inside_char_at_piece_edge() {
+        let mut table = PieceTable::from("ab");
+        table.insert(1, "€");
+        let _ = table.display_range(0..3).to_string();
+    }
+}
+
+mod slice_cache {
+    use super::*;
+
+    #[test]
+    fn disabled_by_default() {
+        let table = get_complex_table();
+        assert_eq!(table.slice(0..5), "Hello");
+        assert_eq!(table.slice(0..5), "Hello");
+        assert_eq!(table.slice_cache_hits(), 0);
+    }
+
+    #[test]
+    fn identical_slices_hit() {
+        let mut table = get_complex_table();
+        table.set_slice_cache(2);
+        assert_eq!(table.slice(3..9), "lo, Wo");
+        assert_eq!(table.slice(3..9), "lo, Wo");
+        assert_eq!(table.slice_cache_hits(), 1);
+
+        assert_eq!(table.slice(0..13), "Hello, World!");
+        assert_eq!(table.slice(0..13), "Hello, World!");
+        assert_eq!(table.slice_cache_hits(), 2);
+
+        // Displaying writes the pieces directly
+        assert_eq!(table.to_string(), "Hello, World!");
+        assert_eq!(table.slice_cache_hits(), 2);
+    }
+
+    #[test]
+    fn edit_clears() {
+        let mut table = get_complex_table();
+        table.set_slice_cache(2);
+        assert_eq!(table.slice(0..5), "Hello");
+        table.insert(0, ">");
+        assert_eq!(table.slice(0..5), ">Hell");
+        assert_eq!(table.slice_cache_hits(), 0);
+
+        table.undo();
+        assert_eq!(table.slice(0..5), "Hello");
+        table.hot_redo();
+        assert_eq!(table.slice(0..5), ">Hell");
+        assert_eq!(table.slice_cache_hits(), 0);
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let mut table = get_complex_table();
+        table.set_slice_cache(2);
+        table.slice(0..1);
+        table.slice(1..2);
+        table.slice(0..1);
+        table.slice(2..3);
+        assert_eq!(table.slice_cache_hits(), 1);
+
+        table.slice(0..1);
+        assert_eq!(table.slice_cache_hits(), 2);
+        table.slice(1..2);
+        assert_eq!(table.slice_cache_hits(), 2);
+    }
+
+    #[test]
+    fn disable() {
+        let mut table = get_complex_table();
+        table.set_slice_cache(2);
+        table.slice(0..1);
+        table.slice(0..1);
+        table.set_slice_cache(0);
+        table.slice(0..1);
+        assert_eq!(table.slice_cache_hits(), 0);
+    }
+}
+
+mod empty_state {
+    use super::*;
+
+    fn assert_empty(table: &PieceTable) {
+        assert!(table.is_empty());
+        assert_eq!(table.len(), 0);
+        assert!(table.pieces.is_empty(), "pieces left: {:?}", table.pieces);
+        assert_eq!(table.slice(0..0), "");
+        assert_eq!(table.slice(..), "");
+        assert_eq!(table.to_string(), "");
+        assert_eq!(table.validate(), Ok(()));
+    }
+
+    #[test]
+    fn from_empty() {
+        assert_empty(&PieceTable::from(""));
+    }
+
+    #[test]
+    fn removed_original() {
+        let mut table = PieceTable::from("Hello");
+        table.remove(0, 5);
+        assert_empty(&table);
+    }
+
+    #[test]
+    fn removed_pieces_piecewise() {
+        let mut table = get_complex_table();
+        while !table.is_empty() {
+            table.remove(0, 1);
+        }
+        assert_empty(&table);
+    }
+
+    #[test]
+    fn removed_across_pieces() {
+        let mut table = get_complex_table();
+        table.remove(0, table.len());
+        assert_empty(&table);
+    }
+
+    #[test]
+    fn undone_insertion() {
+        let mut table = PieceTable::from("");
+        table.insert(0, "Hello");
+        table.undo();
+        assert_empty(&table);
+    }
+
+    #[test]
+    fn cached() {
+        let mut table = PieceTable::from("Hello");
+        table.set_slice_cache(4);
+        table.remove(0, 5);
+        assert_empty(&table);
+    }
+}
+
+#[cfg(feature = "intern-additions")]
+mod intern_additions {
+    use super::*;
+
+    #[test]
+    fn reuses_equal_insertions() {
+        let mut table = PieceTable::from("ab");
+        table.insert(1, "    ");
+        table.append("    ");
+        table.insert(0, "  ");
+
+        // Only equal strings are reused, not substrings of earlier insertions
+        let pieces = vec![
+            Piece::new(PieceSource::Addition, 4, 2),
+            Piece::new(PieceSource::Original, 0, 1),
+            Piece::new(PieceSource::Addition, 0, 4),
+            Piece::new(PieceSource::Original, 1, 1),
+            Piece::new(PieceSource::Addition, 0, 4),
+        ];
+        validate_table(&table, "ab", "      ", &pieces, "  a    b    ");
+    }
+
+    #[test]
+    fn undo_and_redo() {
+        let mut table = PieceTable::from("x");
+        table.append("--");
+        table.append("--");
+        table.undo();
+        table.undo();
+        table.append("--");
+        assert_eq!(table.to_string(), "x--");
+        assert_eq!(table.addition_text(), "--");
+        assert_eq!(table.validate(), Ok(()));
+    }
+
+    #[test]
+    fn long_insertions_are_copied() {
+        let long = "x".repeat(65);
+        let mut table = PieceTable::from("");
+        table.append(&long);
+        table.append(&long);
+        assert_eq!(table.addition_text().len(), 130);
+    }
+
+    #[test]
+    fn undo_and_redo_removal_of_shared_bytes() {
+        let mut table = PieceTable::from("a.b.c");
+        for pos in [4, 3, 2, 1] {
+            table.insert(pos, "ab");
+        }
+        assert_eq!(table.to_string(), "aab.abbab.abc");
+        let before = table.pieces.iter().copied().collect::<Vec<_>>();
+
+        table.remove(2, 8);
+        let after = table.pieces.iter().copied().collect::<Vec<_>>();
+        assert_eq!(table.to_string(), "aaabc");
+
+        table.undo();
+        validate_table(&table, "a.b.c", "ab", &before, "aab.abbab.abc");
+        table.hot_redo();
+        validate_table(&table, "a.b.c", "ab", &after, "aaabc");
+    }
+}
+
+#[cfg(feature = "inline-pieces")]
+mod inline_pieces {
+    use super::*;
+
+    fn type_at(table: &mut PieceTable, pos: usize, text: &str) {
+        for (i, c) in text.char_indices() {
+            table.insert(pos + i, c.to_string());
+        }
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        let mut table = PieceTable::from("ab");
+        table.insert(1, "x");
+
+        let pieces = vec![
+            Piece::new(PieceSource::Original, 0, 1),
+            Piece::new(PieceSource::Addition, 0, 1),
+            Piece::new(PieceSource::Original, 1, 1),
+        ];
+        validate_table(&table, "ab", "x", &pieces, "axb");
+    }
+
+    #[test]
+    fn typing_grows_one_piece() {
+        let mut table = PieceTable::from("ab");
+        table.set_inline_pieces(true);
+        type_at(&mut table, 1, "xyz");
+
+        let pieces = vec![
+            Piece::new(PieceSource::Original, 0, 1),
+            Piece::inline(0, "xyz"),
+            Piece::new(PieceSource::Original, 1, 1),
+        ];
+        validate_table(&table, "ab", "", &pieces, "axyzb");
+    }
+
+    #[test]
+    fn long_run_continues_in_addition() {
+        let mut table = PieceTable::from("");
+        table.set_inline_pieces(true);
+        type_at(&mut table, 0, "abcdefghij");
+        table.append("klmnopqrstuvwxyz");
+
+        let pieces = vec![
+            Piece::inline(0, "abcdefgh"),
+            Piece::new(PieceSource::Addition, 0, 18),
+        ];
+        validate_table(
+            &table,
+            "",
+            "ijklmnopqrstuvwxyz",
+            &pieces,
+            "abcdefghijklmnopqrstuvwxyz",
+        );
+    }
+
+    #[test]
+    fn split_and_merge() {
+        let mut table = PieceTable::from("--");
+        table.set_inline_pieces(true);
+        table.append("abcd");
+        table.insert(3, "é");
+
+        let pieces = vec![
+            Piece::new(PieceSource::Original, 0, 2),
+            Piece::inline(0, "a"),
+            Piece::inline(4, "é"),
+            Piece::inline(1, "bcd"),
+        ];
+        validate_table(&table, "--", "", &pieces, "--aébcd");
+
+        table.remove(3, 2);
+        let pieces = vec![
+            Piece::new(PieceSource::Original, 0, 2),
+            Piece::inline(0, "abcd"),
+        ];
+        validate_table(&table, "--", "", &pieces, "--abcd");
+
+        table.remove(2, 1);
+        table.remove(4, 1);
+        let pieces = vec![
+            Piece::new(PieceSource::Original, 0, 2),
+            Piece::inline(1, "bc"),
+        ];
+        validate_table(&table, "--", "", &pieces, "--bc");
+    }
+
+    #[test]
+    fn undo_and_redo() {
+        let mut table = PieceTable::from("ab");
+        table.set_inline_pieces(true);
+        table.insert(1, "xy");
+        table.insert(2, "-");
+        let pieces = table.pieces.iter().copied().collect::<Vec<_>>();
+
+        table.undo();
+        assert_eq!(table.to_string(), "axyb");
+        table.undo();
+        assert_eq!(table.to_string(), "ab");
+
+        table.hot_redo();
+        table.hot_redo();
+        validate_table(&table, "ab", "", &pieces, "ax-yb");
+        assert_eq!(table.validate(), Ok(()));
+    }
+
+    #[test]
+    fn anchors_tell_pieces_apart() {
+        let mut table = PieceTable::from("ab");
+        table.set_inline_pieces(true);
+        table.insert(1, "xy");
+        table.insert(0, "xy");
+        let anchors = table.anchors_for(&[1, 4]);
+
+        table.insert(2, "--");
+        table.remove(0, 1);
+        let positions: Vec<_> = anchors.iter().map(|a| table.anchor_pos(*a)).collect();
+        assert_eq!(table.to_string(), "y--axyb");
+        assert_eq!(positions, vec![Some(0), Some(5)]);
+    }
+
+    #[test]
+    fn append_runs() {
+        let mut table = PieceTable::from("> ");
+        table.set_inline_pieces(true);
+        table.set_append_runs(Some(usize::MAX));
+        for c in "hello, world".chars() {
+            table.append(c.to_string());
+        }
+
+        let pieces = vec![
+            Piece::new(PieceSource::Original, 0, 2),
+            Piece::inline(0, "hello, w"),
+            Piece::new(PieceSource::Addition, 0, 4),
+        ];
+        validate_table(&table, "> ", "orld", &pieces, "> hello, world");
+        assert_eq!(table.history_stats().total_entries, 2);
+
+        table.undo();
+        assert_eq!(table.to_string(), "> ");
+        table.hot_redo();
+        validate_table(&table, "> ", "orld", &pieces, "> hello, world");
+    }
+
+    #[test]
+    fn line_index() {
+        let mut table = PieceTable::from("a\nb");
+        table.set_inline_pieces(true);
+        table.set_line_index(true);
+        table.insert(1, "\r");
+        table.insert(4, "\n\r");
+        assert_eq!(table.to_string(), "a\r\nb\n\r");
+        assert_eq!(table.line_count(), 4);
+        assert_eq!(table.line_byte_range(1), Some(3..4));
+        assert_eq!(table.line_col_of(5), (2, 0));
+
+        // The `\r` in front of the removed `\n` ends a line on its own
+        table.remove(2, 1);
+        assert_eq!(table.line_count(), 4);
+        assert_eq!(table.line_byte_range(1), Some(2..3));
+        assert_eq!(table.line_col_of(2), (1, 0));
+    }
+
+    #[test]
+    fn from_parts_rejects_inline() {
+        let pieces = [(PieceSource::Original, 0..1), (PieceSource::Inline, 0..1)];
+        assert_eq!(
+            PieceTable::from_parts("a", String::from("b"), pieces).err(),
+            Some(EditError::InvalidPiece { index: 1 })
+        );
+    }
+}
+
+mod revert_to_original {
+    use super::*;
+
+    #[test]
+    fn complex() {
+        let mut table = get_complex_table();
+        table.revert_to_original();
+
+        let pieces = vec![Piece::new(PieceSource::Original, 0, "Held!".len())];
+        validate_table(&table, "Held!", "lloro, W", &pieces, "Held!");
+        assert_eq!(table.original_content(), "Held!");
+    }
+
+    #[test]
+    fn single_undo_step() {
+        let mut table = get_complex_table();
+        table.revert_to_original();
+        table.undo();
+        assert_eq!(table.to_string(), "Hello, World!");
+
+        table.hot_redo();
+        assert_eq!(table.to_string(), "Held!");
+    }
+
+    #[test]
+    fn unchanged() {
+        let mut table = PieceTable::from("Hello");
+        table.append("!");
+        table.remove(5, 1);
+        table.revert_to_original();
+        assert_eq!(table.history.changes.len(), 3);
+    }
+
+    #[test]
+    fn empty_original() {
+        let mut table = PieceTable::from("");
+        table.append("Hello");
+        table.revert_to_original();
+        validate_table(&table, "", "Hello", &Vec::new(), "");
+
+        table.undo();
+        assert_eq!(table.to_string(), "Hello");
+    }
+
+    #[test]
+    fn emptied_text() {
+        let mut table = PieceTable::from("Hello");
+        table.remove(0, 5);
+        table.revert_to_original();
+        assert_eq!(table.to_string(), "Hello");
+        assert_eq!(table.validate(), Ok(()));
+    }
+}
+
+mod map_range {
+    use super::*;
+
+    #[test]
+    fn uppercase_cross_piece() {
+        let mut table = get_complex_table();
+        table.map_range(1..9, str::to_uppercase);
+        assert_eq!(table.to_string(), "HELLO, WOrld!");
+
+        table.undo();
+        assert_eq!(table.to_string(), "Hello, World!");
+        assert_eq!(table.history.head, 2);
+    }
+
+    #[test]
+    fn changes_length() {
+        let mut table = get_complex_table();
+        table.map_range(5..7, |_| String::from(" - "));
+        assert_eq!(table.to_string(), "Hello - World!");
+    }
+
+    #[test]
+    fn unchanged() {
+        let mut table = get_complex_table();
+        let pieces = table.pieces.iter().copied().collect::<Vec<_>>();
+        table.map_range(0..1, str::to_uppercase);
+        table.map_range(4..7, str::to_lowercase);
+
+        validate_table(&table, "Held!", "lloro, W", &pieces, "Hello, World!");
+        assert_eq!(table.history.changes.len(), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "Replace range 10..14 out of bounds for length 13")]
+    fn out_of_bounds() {
+        let mut table = get_complex_table();
+        table.map_range(10..14, str::to_uppercase);
+    }
+
+    #[test]
+    #[should_panic(expected = "Byte 2 is not a char boundary")]
+    fn inside_char() {
+        let mut table = PieceTable::from("aéb");
+        table.map_range(0..2, str::to_uppercase);
+    }
+}
+
+#[cfg(feature = "mmap")]
+mod from_mmap {
+    use super::*;
+    use std::{
+        fs::{self, File},
+        io::ErrorKind,
+        path::PathBuf,
+    };
+
+    /// Writes `content` to a file in the temp directory unique to the test `name`
+    fn temp_file(name: &str, content: &[u8]) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("piece_table_{name}_{}.txt", std::process::id()));
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn edits() {
+        let path = temp_file("mmap_edits", "Hello, World!".as_bytes());
+        let file = File::open(&path).unwrap();
+        let mut table = unsafe { PieceTable::from_mmap(&file) }.unwrap();
+
+        table.insert(5, " there");
+        table.remove(11, 7);
+        let pieces = vec![
+            Piece::new(PieceSource::Original, 0, 5),
+            Piece::new(PieceSource::Addition, 0, 6),
+            Piece::new(PieceSource::Original, 12, 1),
+        ];
+        validate_table(&table, "Hello, World!", " there", &pieces, "Hello there!");
+
+        table.undo();
+        table.undo();
+        assert_eq!(table.to_string(), "Hello, World!");
+
+        drop(table);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn empty_file() {
+        let path = temp_file("mmap_empty", b"");
+        let file = File::open(&path).unwrap();
+        let mut table = unsafe { PieceTable::from_mmap(&file) }.unwrap();
+        assert!(table.is_empty());
+
+        table.append("Hello");
+        let pieces = vec![Piece::new(PieceSource::Addition, 0, 5)];
+        validate_table(&table, "", "Hello", &pieces, "Hello");
+
+        drop(table);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        let path = temp_file("mmap_invalid", b"Hello\xff");
+        let file = File::open(&path).unwrap();
+        let error = unsafe { PieceTable::from_mmap(&file) }.unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+
+        fs::remove_file(&path).unwrap();
+    }
 }
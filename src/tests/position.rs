@@ -0,0 +1,342 @@
+use crate::piece_table::{
+    position::{BytePos, CharPos},
+    PieceTable,
+};
+
+fn get_multibyte_table() -> PieceTable {
+    // "añb€c" with "ñb" and "€" inserted into "ac"
+    let mut table = PieceTable::from("ac");
+    table.insert(1, "ñb");
+    table.insert(4, "€");
+    table
+}
+
+mod to_byte_pos {
+    use super::*;
+
+    #[test]
+    fn across_pieces() {
+        let table = get_multibyte_table();
+        let positions: Vec<_> = (0..=5).map(|c| table.to_byte_pos(CharPos(c))).collect();
+        assert_eq!(
+            positions,
+            vec![0, 1, 3, 4, 7, 8]
+                .into_iter()
+                .map(BytePos)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn empty() {
+        let table = PieceTable::from("");
+        assert_eq!(table.to_byte_pos(CharPos(0)), BytePos(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Char position 6 out of bounds for char count 5")]
+    fn out_of_bounds() {
+        let table = get_multibyte_table();
+        table.to_byte_pos(CharPos(6));
+    }
+}
+
+mod to_char_pos {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let table = get_multibyte_table();
+        for c in 0..=5 {
+            assert_eq!(table.to_char_pos(table.to_byte_pos(CharPos(c))), CharPos(c));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Byte 5 is not a char boundary")]
+    fn not_char_boundary() {
+        let table = get_multibyte_table();
+        table.to_char_pos(BytePos(5));
+    }
+
+    #[test]
+    #[should_panic(expected = "Byte position 9 out of bounds for length 8")]
+    fn out_of_bounds() {
+        let table = get_multibyte_table();
+        table.to_char_pos(BytePos(9));
+    }
+}
+
+mod byte_pos_edits {
+    use super::*;
+
+    #[test]
+    fn insert_remove_slice() {
+        let mut table = get_multibyte_table();
+        let pos = table.to_byte_pos(CharPos(3));
+        table.insert_at(pos, "-");
+        assert_eq!(table.to_string(), "añb-€c");
+
+        table.remove_at(BytePos(1), 2);
+        assert_eq!(table.to_string(), "ab-€c");
+        assert_eq!(table.slice_at(BytePos(2)..BytePos(6)), "-€");
+    }
+
+    #[test]
+    fn conversions() {
+        assert_eq!(BytePos::from(3), BytePos(3));
+        assert_eq!(usize::from(CharPos(4)), 4);
+    }
+}
+
+mod get_chars {
+    use super::*;
+
+    #[test]
+    fn across_pieces() {
+        let table = get_multibyte_table();
+        assert_eq!(table.get_chars(1..4), Some(String::from("ñb€")));
+        assert_eq!(table.get_chars(0..5), Some(String::from("añb€c")));
+    }
+
+    #[test]
+    fn empty_range() {
+        let table = get_multibyte_table();
+        assert_eq!(table.get_chars(5..5), Some(String::new()));
+        assert_eq!(PieceTable::from("").get_chars(0..0), Some(String::new()));
+    }
+
+    #[test]
+    fn out_of_bounds() {
+        let table = get_multibyte_table();
+        assert_eq!(table.get_chars(3..6), None);
+        assert_eq!(table.get_chars(6..7), None);
+    }
+
+    #[test]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn reversed() {
+        let table = get_multibyte_table();
+        assert_eq!(table.get_chars(3..1), None);
+    }
+}
+
+mod char_boundaries_in {
+    use super::*;
+
+    #[test]
+    fn whole_text() {
+        let table = get_multibyte_table();
+        let boundaries: Vec<_> = table.char_boundaries_in(0..table.len()).collect();
+        assert_eq!(boundaries, vec![0, 1, 3, 4, 7]);
+    }
+
+    #[test]
+    fn range_starts_mid_char_in_other_piece() {
+        // "ñ" is at 1..3 in the added piece "ñb", "€" at 4..7 in its own piece
+        let table = get_multibyte_table();
+        let boundaries: Vec<_> = table.char_boundaries_in(2..6).collect();
+        assert_eq!(boundaries, vec![3, 4]);
+    }
+
+    #[test]
+    fn range_inside_char() {
+        let table = get_multibyte_table();
+        assert_eq!(table.char_boundaries_in(5..6).count(), 0);
+    }
+
+    #[test]
+    fn empty() {
+        let table = get_multibyte_table();
+        assert_eq!(table.char_boundaries_in(3..3).count(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Range 5..9 out of bounds for length 8")]
+    fn out_of_bounds() {
+        let table = get_multibyte_table();
+        let _ = table.char_boundaries_in(5..9);
+    }
+}
+
+mod slice_from_end {
+    use super::*;
+
+    #[test]
+    fn cross_piece() {
+        let table = get_multibyte_table();
+        assert_eq!(table.slice_from_end(5), "b€c");
+    }
+
+    #[test]
+    fn snaps_forward() {
+        let table = get_multibyte_table();
+        assert_eq!(table.slice_from_end(6), "b€c");
+        assert_eq!(table.slice_from_end(7), "ñb€c");
+    }
+
+    #[test]
+    fn zero_and_clamped() {
+        let table = get_multibyte_table();
+        assert_eq!(table.slice_from_end(0), "");
+        assert_eq!(table.slice_from_end(100), "añb€c");
+        assert_eq!(PieceTable::from("").slice_from_end(3), "");
+    }
+}
+
+mod slice_to_end_minus {
+    use super::*;
+
+    #[test]
+    fn cross_piece() {
+        let table = get_multibyte_table();
+        assert_eq!(table.slice_to_end_minus(5), "añ");
+    }
+
+    #[test]
+    fn complements_slice_from_end() {
+        let table = get_multibyte_table();
+        for n in 0..=table.len() + 1 {
+            let text = table.slice_to_end_minus(n) + &table.slice_from_end(n);
+            assert_eq!(text, "añb€c", "split at {n} bytes from the end");
+        }
+    }
+
+    #[test]
+    fn zero_and_clamped() {
+        let table = get_multibyte_table();
+        assert_eq!(table.slice_to_end_minus(0), "añb€c");
+        assert_eq!(table.slice_to_end_minus(100), "");
+    }
+}
+
+mod remove_chars {
+    use super::*;
+
+    #[test]
+    fn cross_piece() {
+        let mut table = get_multibyte_table();
+        table.remove_chars(1, 3);
+        assert_eq!(table.to_string(), "ac");
+    }
+
+    #[test]
+    fn single_multi_byte_char() {
+        let mut table = get_multibyte_table();
+        table.remove_chars(3, 1);
+        assert_eq!(table.to_string(), "añbc");
+    }
+
+    #[test]
+    fn up_to_end() {
+        let mut table = get_multibyte_table();
+        table.remove_chars(2, 3);
+        assert_eq!(table.to_string(), "añ");
+    }
+
+    #[test]
+    #[should_panic(expected = "Char range 3..6 out of bounds for char count 5")]
+    fn out_of_bounds() {
+        let mut table = get_multibyte_table();
+        table.remove_chars(3, 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "Must remove at least 1 character")]
+    fn zero() {
+        let mut table = get_multibyte_table();
+        table.remove_chars(1, 0);
+    }
+}
+
+mod insert_char_pos {
+    use super::*;
+
+    #[test]
+    fn between_multi_byte_chars() {
+        let mut table = get_multibyte_table();
+        table.insert_char_pos(4, "x");
+        assert_eq!(table.to_string(), "añb€xc");
+        table.insert_char_pos(2, "y");
+        assert_eq!(table.to_string(), "añyb€xc");
+    }
+
+    #[test]
+    fn ends() {
+        let mut table = get_multibyte_table();
+        table.insert_char_pos(0, "<");
+        table.insert_char_pos(6, ">");
+        assert_eq!(table.to_string(), "<añb€c>");
+    }
+
+    #[test]
+    #[should_panic(expected = "Char position 6 out of bounds for char count 5")]
+    fn out_of_bounds() {
+        let mut table = get_multibyte_table();
+        table.insert_char_pos(6, "x");
+    }
+}
+
+mod char_len {
+    use super::*;
+
+    #[test]
+    fn multi_byte() {
+        let table = get_multibyte_table();
+        assert_eq!(table.char_len(), 5);
+        assert_eq!(table.len(), 8);
+    }
+
+    #[test]
+    fn empty() {
+        let mut table = PieceTable::from("");
+        assert_eq!(table.char_len(), 0);
+        table.append("€");
+        table.remove(0, 3);
+        assert_eq!(table.char_len(), 0);
+    }
+}
+
+#[cfg(feature = "unicode")]
+mod grapheme_count {
+    use super::*;
+
+    #[test]
+    fn empty() {
+        assert_eq!(PieceTable::from("").grapheme_count(), 0);
+    }
+
+    #[test]
+    fn single_piece() {
+        let table = PieceTable::from("e\u{301}a\r\n");
+        assert_eq!(table.grapheme_count(), 3);
+    }
+
+    #[test]
+    fn family_emoji_across_pieces() {
+        // Man, ZWJ, woman, ZWJ, girl: one grapheme cluster of 5 chars
+        let family = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+        let mut table = PieceTable::from("a\u{1F468}\u{200D}b");
+        table.remove(8, 1);
+        table.append("\u{1F469}\u{200D}\u{1F467}");
+        assert_eq!(table.to_string(), format!("a{family}"));
+        assert_eq!(table.chunks().count(), 2);
+
+        assert_eq!(table.char_len(), 6);
+        assert_eq!(table.grapheme_count(), 2);
+    }
+
+    #[test]
+    fn regional_indicators_across_pieces() {
+        // Flags pair up regional indicators, which needs the text in front of each piece
+        let mut table = PieceTable::from("\u{1F1E9}\u{1F1EA}\u{1F1EB}");
+        table.append("\u{1F1F7}");
+        table.append("x");
+        table.insert(0, "\u{1F1EB}\u{1F1F7}");
+        assert_eq!(table.grapheme_count(), 4);
+
+        // Removing one indicator shifts the pairs: RD, EF, R, x
+        table.remove(0, 4);
+        assert_eq!(table.grapheme_count(), 4);
+    }
+}
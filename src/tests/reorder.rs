@@ -0,0 +1,221 @@
+use std::ops::Not;
+
+use crate::piece_table::{
+    piece::{Piece, PieceSource},
+    PieceTable,
+};
+use crate::tests::piece_table::{get_complex_table, validate_table};
+
+mod swap_ranges {
+    use super::*;
+
+    #[test]
+    fn lines() {
+        let mut table = PieceTable::from("one\ntwo\nthree\n");
+        table.swap_ranges(4..8, 8..14);
+        assert_eq!(table.to_string(), "one\nthree\ntwo\n");
+        assert_eq!(table.addition_text(), "");
+
+        let pieces = vec![
+            Piece::new(PieceSource::Original, 0, 4),
+            Piece::new(PieceSource::Original, 8, 6),
+            Piece::new(PieceSource::Original, 4, 4),
+        ];
+        validate_table(
+            &table,
+            "one\ntwo\nthree\n",
+            "",
+            &pieces,
+            "one\nthree\ntwo\n",
+        );
+
+        assert!(table.undo());
+        let pieces = vec![Piece::new(PieceSource::Original, 0, 14)];
+        validate_table(
+            &table,
+            "one\ntwo\nthree\n",
+            "",
+            &pieces,
+            "one\ntwo\nthree\n",
+        );
+        assert!(table.undo().not());
+
+        assert!(table.hot_redo());
+        assert_eq!(table.to_string(), "one\nthree\ntwo\n");
+    }
+
+    #[test]
+    fn with_text_between() {
+        let mut table = get_complex_table();
+        table.swap_ranges(7..12, 0..5);
+        assert_eq!(table.to_string(), "World, Hello!");
+        assert_eq!(table.addition_text(), "lloro, W");
+        assert_eq!(table.validate(), Ok(()));
+
+        table.undo();
+        assert_eq!(table.to_string(), "Hello, World!");
+        assert_eq!(table.validate(), Ok(()));
+    }
+
+    #[test]
+    fn swap_back_merges_pieces() {
+        let mut table = PieceTable::from("ab");
+        table.swap_ranges(0..1, 1..2);
+        assert_eq!(table.to_string(), "ba");
+        table.swap_ranges(0..1, 1..2);
+
+        let pieces = vec![Piece::new(PieceSource::Original, 0, 2)];
+        validate_table(&table, "ab", "", &pieces, "ab");
+    }
+
+    #[test]
+    fn empty_range_moves_text() {
+        let mut table = PieceTable::from("Hello, World!");
+        table.swap_ranges(0..7, 12..12);
+        assert_eq!(table.to_string(), "WorldHello, !");
+    }
+
+    #[test]
+    fn no_op() {
+        let mut table = PieceTable::from("Hello, World!");
+        table.swap_ranges(0..5, 5..5);
+        table.swap_ranges(3..3, 8..8);
+        assert_eq!(table.to_string(), "Hello, World!");
+        assert!(table.undo().not());
+    }
+
+    #[test]
+    fn single_undo_step() {
+        let mut table = get_complex_table();
+        table.swap_ranges(2..4, 9..11);
+        assert_eq!(table.to_string(), "Herlo, Wolld!");
+        assert_eq!(table.history.changes.len(), 4);
+    }
+
+    #[test]
+    fn multi_byte() {
+        let mut table = PieceTable::from("añb€c");
+        table.swap_ranges(1..3, 4..7);
+        assert_eq!(table.to_string(), "a€bñc");
+    }
+
+    #[test]
+    #[should_panic(expected = "Swap ranges 0..5 and 3..8 overlap")]
+    fn overlapping() {
+        let mut table = PieceTable::from("Hello, World!");
+        table.swap_ranges(0..5, 3..8);
+    }
+
+    #[test]
+    #[should_panic(expected = "Swap ranges 2..2 and 0..5 overlap")]
+    fn empty_inside() {
+        let mut table = PieceTable::from("Hello, World!");
+        table.swap_ranges(2..2, 0..5);
+    }
+
+    #[test]
+    #[should_panic(expected = "Swap range 10..14 out of bounds for length 13")]
+    fn out_of_bounds() {
+        let mut table = PieceTable::from("Hello, World!");
+        table.swap_ranges(0..5, 10..14);
+    }
+
+    #[test]
+    #[should_panic(expected = "Position 2 falls inside a character")]
+    fn inside_char() {
+        let mut table = PieceTable::from("añb€c");
+        table.swap_ranges(0..2, 4..7);
+    }
+}
+
+mod move_range {
+    use super::*;
+
+    #[test]
+    fn forward() {
+        let mut table = PieceTable::from("one\ntwo\nthree\n");
+        table.move_range(0, 4, 14);
+        assert_eq!(table.to_string(), "two\nthree\none\n");
+        assert_eq!(table.addition_text(), "");
+
+        let pieces = vec![
+            Piece::new(PieceSource::Original, 4, 10),
+            Piece::new(PieceSource::Original, 0, 4),
+        ];
+        validate_table(
+            &table,
+            "one\ntwo\nthree\n",
+            "",
+            &pieces,
+            "two\nthree\none\n",
+        );
+    }
+
+    #[test]
+    fn backward() {
+        let mut table = get_complex_table();
+        table.move_range(7, 5, 0);
+        assert_eq!(table.to_string(), "WorldHello, !");
+        assert_eq!(table.addition_text(), "lloro, W");
+        assert_eq!(table.validate(), Ok(()));
+    }
+
+    #[test]
+    fn single_undo_step() {
+        let mut table = get_complex_table();
+        table.move_range(0, 5, 12);
+        assert_eq!(table.to_string(), ", WorldHello!");
+
+        assert!(table.undo());
+        assert_eq!(table.to_string(), "Hello, World!");
+        assert_eq!(table.validate(), Ok(()));
+        assert!(table.hot_redo());
+        assert_eq!(table.to_string(), ", WorldHello!");
+    }
+
+    #[test]
+    fn into_itself() {
+        let mut table = PieceTable::from("Hello, World!");
+        table.move_range(2, 5, 2);
+        table.move_range(2, 5, 4);
+        table.move_range(2, 5, 7);
+        table.move_range(2, 0, 10);
+        assert_eq!(table.to_string(), "Hello, World!");
+        assert!(table.undo().not());
+    }
+
+    #[test]
+    fn to_end() {
+        let mut table = PieceTable::from("Hello, World!");
+        table.move_range(0, 7, 13);
+        assert_eq!(table.to_string(), "World!Hello, ");
+    }
+
+    #[test]
+    #[should_panic(expected = "Move range 10..15 out of bounds for length 13")]
+    fn out_of_bounds() {
+        let mut table = PieceTable::from("Hello, World!");
+        table.move_range(10, 5, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Move range 10..18446744073709551615 out of bounds for length 13")]
+    fn overflowing() {
+        let mut table = PieceTable::from("Hello, World!");
+        table.move_range(10, usize::MAX, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Move target 14 out of bounds for length 13")]
+    fn target_out_of_bounds() {
+        let mut table = PieceTable::from("Hello, World!");
+        table.move_range(0, 5, 14);
+    }
+
+    #[test]
+    #[should_panic(expected = "Position 2 falls inside a character")]
+    fn inside_char() {
+        let mut table = PieceTable::from("añb€c");
+        table.move_range(4, 3, 2);
+    }
+}
@@ -0,0 +1,311 @@
+use crate::piece_table::{
+    piece::{Piece, PieceSource},
+    PieceTable,
+};
+use crate::tests::piece_table::{get_complex_table, validate_table};
+
+mod find_all {
+    use super::*;
+
+    #[test]
+    fn cross_piece() {
+        let mut table = PieceTable::from("Held!");
+        table.insert(2, "llor");
+        table.insert(4, "o, W");
+        assert_eq!(table.find_all("lo"), vec![3]);
+        assert_eq!(table.find_all("l"), vec![2, 3, 10]);
+    }
+
+    #[test]
+    fn non_overlapping() {
+        let table = PieceTable::from("aaaa");
+        assert_eq!(table.find_all("aa"), vec![0, 2]);
+    }
+
+    #[test]
+    fn no_match() {
+        let table = PieceTable::from("Hello, World!");
+        assert!(table.find_all("xyz").is_empty());
+    }
+
+    #[test]
+    fn partial_matches() {
+        let mut table = PieceTable::from("aab");
+        table.append("aaab");
+        assert_eq!(table.find_all("aaab"), vec![3]);
+        assert_eq!(table.find_all("aab"), vec![0, 4]);
+    }
+
+    #[test]
+    fn multi_byte() {
+        let mut table = PieceTable::from("ñ€ñ");
+        table.insert(2, "€");
+        assert_eq!(table.to_string(), "ñ€€ñ");
+        assert_eq!(table.find_all("€ñ"), vec![5]);
+        assert_eq!(table.find_all("€"), vec![2, 5]);
+    }
+
+    #[test]
+    fn matches_naive_search() {
+        let mut table = PieceTable::from("abcabcab");
+        table.insert(3, "cab");
+        table.insert(1, "ba");
+        table.append("ab");
+        let text = table.to_string();
+
+        for pattern in ["a", "ab", "abc", "cab", "bab", "abcab", "ca", "bb", "x"] {
+            let expected: Vec<usize> = text.match_indices(pattern).map(|(i, _)| i).collect();
+            assert_eq!(table.find_all(pattern), expected, "pattern {pattern:?}");
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Pattern must not be empty")]
+    fn empty_pattern() {
+        let table = PieceTable::from("Hello, World!");
+        table.find_all("");
+    }
+}
+
+mod find {
+    use super::*;
+
+    #[test]
+    fn first_match() {
+        let mut table = PieceTable::from("Held!");
+        table.insert(2, "llor");
+        table.insert(4, "o, W");
+        assert_eq!(table.find("l"), Some(2));
+        assert_eq!(table.find("o, Wor"), Some(4));
+        assert_eq!(table.find("World!"), Some(7));
+    }
+
+    #[test]
+    fn no_match() {
+        let table = PieceTable::from("Hello, World!");
+        assert_eq!(table.find("World?"), None);
+        assert_eq!(table.find("Hello, World!!"), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Pattern must not be empty")]
+    fn empty_pattern() {
+        let table = PieceTable::from("Hello, World!");
+        table.find("");
+    }
+}
+
+mod replace_all_with {
+    use super::*;
+
+    #[test]
+    fn numbered() {
+        let mut table = PieceTable::from("x-x-x");
+        let mut n = 0;
+        let count = table.replace_all_with("x", |_| {
+            n += 1;
+            n.to_string()
+        });
+
+        assert_eq!(count, 3);
+        assert_eq!(table.to_string(), "1-2-3");
+    }
+
+    #[test]
+    fn receives_positions() {
+        let mut table = PieceTable::from("ab ab ab");
+        let mut positions = Vec::new();
+        table.replace_all_with("ab", |pos| {
+            positions.push(pos);
+            String::from("abc")
+        });
+
+        assert_eq!(positions, vec![0, 3, 6]);
+        assert_eq!(table.to_string(), "abc abc abc");
+    }
+
+    #[test]
+    fn shrinking_and_growing() {
+        let mut table = PieceTable::from("one two one two");
+        let count = table.replace_all_with("one", |pos| {
+            if pos == 0 {
+                String::from("1")
+            } else {
+                String::from("eleven")
+            }
+        });
+
+        assert_eq!(count, 2);
+        assert_eq!(table.to_string(), "1 two eleven two");
+    }
+
+    #[test]
+    fn empty_replacement() {
+        let mut table = PieceTable::from("Hello, World!");
+        table.replace_all_with("l", |_| String::new());
+        assert_eq!(table.to_string(), "Heo, Word!");
+    }
+
+    #[test]
+    fn single_undo() {
+        let mut table = PieceTable::from("Hello");
+        table.append(", World!");
+        table.replace_all_with("o", |_| String::from("0"));
+        assert_eq!(table.to_string(), "Hell0, W0rld!");
+
+        table.undo();
+        let pieces = vec![
+            Piece::new(PieceSource::Original, 0, "Hello".len()),
+            Piece::new(PieceSource::Addition, 0, ", World!".len()),
+        ];
+        // Interned insertions share the bytes of the first replacement
+        let addition = if cfg!(feature = "intern-additions") {
+            ", World!0"
+        } else {
+            ", World!00"
+        };
+        validate_table(&table, "Hello", addition, &pieces, "Hello, World!");
+
+        table.hot_redo();
+        assert_eq!(table.to_string(), "Hell0, W0rld!");
+    }
+
+    #[test]
+    fn no_match() {
+        let mut table = PieceTable::from("Hello, World!");
+        assert_eq!(table.replace_all_with("xyz", |_| String::new()), 0);
+        assert_eq!(table.history.changes.len(), 1);
+    }
+}
+
+mod remove_matches {
+    use super::*;
+
+    #[test]
+    fn cross_piece() {
+        let mut table = PieceTable::from("a b c");
+        table.insert(3, " d e ");
+        assert_eq!(table.to_string(), "a b d e  c");
+
+        assert_eq!(table.remove_matches(" "), 5);
+        assert_eq!(table.to_string(), "abdec");
+    }
+
+    #[test]
+    fn adjacent() {
+        let mut table = PieceTable::from("xxabxx");
+        table.append("xx");
+        assert_eq!(table.remove_matches("x"), 6);
+        assert_eq!(table.to_string(), "ab");
+    }
+
+    #[test]
+    fn single_undo() {
+        let mut table = PieceTable::from("Hello");
+        table.append(", World!");
+        assert_eq!(table.remove_matches("o"), 2);
+        assert_eq!(table.to_string(), "Hell, Wrld!");
+
+        table.undo();
+        let pieces = vec![
+            Piece::new(PieceSource::Original, 0, "Hello".len()),
+            Piece::new(PieceSource::Addition, 0, ", World!".len()),
+        ];
+        validate_table(&table, "Hello", ", World!", &pieces, "Hello, World!");
+    }
+
+    #[test]
+    fn no_match() {
+        let mut table = PieceTable::from("Hello, World!");
+        assert_eq!(table.remove_matches("xyz"), 0);
+        assert_eq!(table.history.changes.len(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Pattern must not be empty")]
+    fn empty_pattern() {
+        let mut table = PieceTable::from("Hello");
+        table.remove_matches("");
+    }
+}
+
+mod text_eq {
+    use super::*;
+
+    #[test]
+    fn cross_piece() {
+        let table = get_complex_table();
+        assert!(table.text_eq("Hello, World!"));
+        assert!(!table.text_eq("Hello, World?"));
+        assert!(!table.text_eq("Hello, Wo!"));
+    }
+
+    #[test]
+    fn difference_at_piece_boundary() {
+        let table = get_complex_table();
+        assert!(!table.text_eq("Hexlo, World!"));
+    }
+
+    #[test]
+    fn empty() {
+        assert!(PieceTable::from("").text_eq(""));
+        assert!(!PieceTable::from("").text_eq("a"));
+        assert!(!get_complex_table().text_eq(""));
+    }
+}
+
+mod starts_with {
+    use super::*;
+
+    #[test]
+    fn cross_piece() {
+        let mut table = PieceTable::from("Held!");
+        table.insert(2, "llor");
+        table.insert(4, "o, W");
+        assert!(table.starts_with("Hello, World"));
+        assert!(table.starts_with("Hello, World!"));
+        assert!(!table.starts_with("Hello, Wirld"));
+    }
+
+    #[test]
+    fn longer_than_text() {
+        let table = PieceTable::from("Hello");
+        assert!(!table.starts_with("Hello, World!"));
+    }
+
+    #[test]
+    fn empty_prefix() {
+        assert!(PieceTable::from("").starts_with(""));
+        assert!(PieceTable::from("Hello").starts_with(""));
+    }
+}
+
+mod ensure_prefix {
+    use super::*;
+
+    #[test]
+    fn inserts() {
+        let pieces = vec![
+            Piece::new(PieceSource::Addition, 0, "Hello, ".len()),
+            Piece::new(PieceSource::Original, 0, "World!".len()),
+        ];
+
+        let mut table = PieceTable::from("World!");
+        assert!(table.ensure_prefix("Hello, "));
+        validate_table(&table, "World!", "Hello, ", &pieces, "Hello, World!");
+    }
+
+    #[test]
+    fn prefix_split_across_pieces() {
+        let mut table = PieceTable::from("Held!");
+        table.insert(2, "llor");
+        table.insert(4, "o, W");
+        let pieces = table.pieces.iter().copied().collect::<Vec<_>>();
+
+        assert!(!table.ensure_prefix("Hello, W"));
+        validate_table(&table, "Held!", "lloro, W", &pieces, "Hello, World!");
+
+        table.undo();
+        assert_eq!(table.to_string(), "Hellorld!");
+    }
+}
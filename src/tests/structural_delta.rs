@@ -0,0 +1,147 @@
+use std::{
+    cell::Cell,
+    sync::{mpsc, Arc, Mutex},
+};
+
+use crate::piece_table::{
+    piece::Piece,
+    structural_delta::{PieceChange, StructuralDelta},
+    PieceTable,
+};
+use crate::tests::piece_table::get_complex_table;
+
+fn observe(table: &mut PieceTable) -> Arc<Mutex<Vec<StructuralDelta>>> {
+    let deltas = Arc::new(Mutex::new(Vec::new()));
+    let observed = Arc::clone(&deltas);
+    table.on_structural_change(move |delta| observed.lock().unwrap().push(delta.clone()));
+    deltas
+}
+
+/// Runs `edit` and checks that the reported deltas turn the old piece list into the new one,
+/// pieces not reported as changed must be unchanged
+fn check_edit<F: FnOnce(&mut PieceTable)>(table: &mut PieceTable, edit: F) {
+    let deltas = observe(table);
+    let before: Vec<Piece> = table.pieces.iter().copied().collect();
+    edit(table);
+
+    let mut mirror: Vec<Option<usize>> = (0..before.len()).map(Some).collect();
+    for delta in deltas.lock().unwrap().iter() {
+        for change in &delta.changes {
+            match *change {
+                PieceChange::Inserted { index } => mirror.insert(index, None),
+                PieceChange::Removed { index } => {
+                    mirror.remove(index);
+                }
+                PieceChange::Modified { index } => mirror[index] = None,
+            }
+        }
+    }
+
+    assert_eq!(mirror.len(), table.pieces.len());
+    for (idx, old_idx) in mirror.iter().enumerate() {
+        if let Some(old_idx) = old_idx {
+            assert_eq!(table.pieces[idx], before[*old_idx]);
+        }
+    }
+}
+
+mod on_structural_change {
+    use super::*;
+
+    #[test]
+    fn split_on_insert() {
+        let mut table = PieceTable::from("HelloWorld!");
+        let deltas = observe(&mut table);
+        table.insert(5, ", ");
+        assert_eq!(
+            deltas.lock().unwrap()[0].changes,
+            vec![
+                PieceChange::Modified { index: 0 },
+                PieceChange::Inserted { index: 1 },
+                PieceChange::Inserted { index: 2 },
+            ]
+        );
+    }
+
+    #[test]
+    fn insert() {
+        let mut table = get_complex_table();
+        check_edit(&mut table, |table| table.insert(7, "big "));
+        check_edit(&mut table, |table| table.insert(0, ">"));
+    }
+
+    #[test]
+    fn remove() {
+        let mut table = get_complex_table();
+        check_edit(&mut table, |table| table.remove(3, 6));
+        check_edit(&mut table, |table| table.remove(0, table.len()));
+    }
+
+    #[test]
+    fn remove_with_merge() {
+        let mut table = PieceTable::from("Hello, World!");
+        table.insert(5, "XX");
+        check_edit(&mut table, |table| table.remove(5, 2));
+        assert_eq!(table.pieces.len(), 1);
+    }
+
+    #[test]
+    fn swap_ranges() {
+        let mut table = get_complex_table();
+        check_edit(&mut table, |table| table.swap_ranges(0..3, 7..12));
+        check_edit(&mut table, |table| table.swap_ranges(0..5, 10..13));
+    }
+
+    #[test]
+    fn undo_redo() {
+        let mut table = get_complex_table();
+        table.remove(3, 6);
+        check_edit(&mut table, |table| {
+            table.undo();
+        });
+        check_edit(&mut table, |table| {
+            table.hot_redo();
+        });
+        check_edit(&mut table, |table| {
+            table.undo();
+            table.undo();
+            table.hot_redo();
+        });
+    }
+
+    #[test]
+    fn history_disabled() {
+        let mut table = PieceTable::from_readonly("Hello");
+        let deltas = observe(&mut table);
+        table.append("!");
+        assert_eq!(deltas.lock().unwrap().len(), 1);
+        assert_eq!(table.history.changes.len(), 1);
+    }
+
+    #[test]
+    fn nothing_changed() {
+        let mut table = get_complex_table();
+        let deltas = observe(&mut table);
+        table.replace_range(3..3, "");
+        table.append_all([""]);
+        assert!(deltas.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn non_sync_callback() {
+        fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+        let mut table = get_complex_table();
+        let (sender, receiver) = mpsc::channel();
+        let calls = Cell::new(0);
+        table.on_structural_change(move |_| {
+            calls.set(calls.get() + 1);
+            sender.send(calls.get()).unwrap();
+        });
+        assert_send_sync(&table);
+
+        table.append("!");
+        table.remove(0, 2);
+        assert_eq!(receiver.try_iter().collect::<Vec<_>>(), vec![1, 2]);
+    }
+}